@@ -0,0 +1,157 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand};
+use sqlx::MySqlPool;
+use walkdir::WalkDir;
+
+use crate::importers::{self, ImportFile};
+use crate::models::backup::Backup;
+use crate::services::backup_service::BackupService;
+use crate::services::diary_service::DiaryService;
+use crate::services::site_service::SiteService;
+
+/// ほぼ日だいあり バックエンド
+#[derive(Debug, Parser)]
+#[command(name = "backend")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// APIサーバーを起動します（サブコマンド省略時の既定）
+    Serve,
+    /// バックアップの書き出しとリストアを行います
+    #[command(subcommand)]
+    Backup(BackupCommand),
+    /// 外部形式のファイル、またはディレクトリ内のファイルからエントリを取り込みます
+    Import {
+        /// 形式（markdown / movable-type / day-one / journey）
+        format: String,
+        /// 取り込むファイル（ZIP可）、またはディレクトリ
+        path: PathBuf,
+    },
+    /// 公開エントリから静的サイト（HTMLとAtomフィード）を生成します
+    Site {
+        /// 書き出し先のディレクトリ
+        out_dir: PathBuf,
+        /// サイトを配置するURL（例: https://example.com/diary/）
+        #[arg(long, default_value = "/")]
+        base_url: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BackupCommand {
+    /// データベースの内容をJSONファイルに書き出します
+    Export {
+        /// 書き出し先のファイル
+        output: PathBuf,
+    },
+    /// JSONファイルからデータベースをリストアします
+    Restore {
+        /// 読み込むバックアップファイル
+        input: PathBuf,
+        /// 書き込みを行わず、追加される行と衝突だけを表示します
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+pub async fn run_backup(pool: &MySqlPool, command: BackupCommand) -> Result<(), Box<dyn std::error::Error>> {
+    let backup_service = BackupService::new(pool);
+
+    match command {
+        BackupCommand::Export { output } => {
+            let backup = backup_service
+                .export()
+                .await
+                .map_err(|e| format!("Failed to export backup: {:?}", e))?;
+            let file = std::fs::File::create(&output)?;
+            serde_json::to_writer(std::io::BufWriter::new(file), &backup)?;
+            tracing::info!(
+                "exported {} entries and {} tags to {}",
+                backup.entries.len(),
+                backup.tags.len(),
+                output.display()
+            );
+        }
+        BackupCommand::Restore { input, dry_run } => {
+            let file = std::fs::File::open(&input)?;
+            let backup: Backup = serde_json::from_reader(std::io::BufReader::new(file))?;
+            let report = backup_service
+                .restore(&backup, dry_run)
+                .await
+                .map_err(|e| format!("Failed to restore backup: {:?}", e))?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn run_import(pool: &MySqlPool, format: &str, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let importer = importers::importer_for(format).ok_or_else(|| format!("Unknown import format: {}", format))?;
+
+    let files = if path.is_dir() {
+        read_directory(path, importer.extensions())?
+    } else {
+        let name = path.display().to_string();
+        importers::unpack_upload(&name, std::fs::read(path)?, importer.extensions())
+            .await
+            .map_err(|e| format!("Failed to read {}: {:?}", name, e))?
+    };
+    tracing::info!("read {} files from {}", files.len(), path.display());
+
+    let parsed = importer
+        .parse(&files)
+        .map_err(|e| format!("Failed to parse {}: {:?}", path.display(), e))?;
+    let report = DiaryService::new(pool)
+        .import_entries(parsed)
+        .await
+        .map_err(|e| format!("Failed to import entries: {:?}", e))?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    Ok(())
+}
+
+// ディレクトリを再帰的にたどり、対象の拡張子のファイルを読み込みます。
+// `.obsidian` や `.git` などの隠しディレクトリは対象外です。
+fn read_directory(root: &Path, extensions: &[&str]) -> Result<Vec<ImportFile>, Box<dyn std::error::Error>> {
+    let mut files = Vec::new();
+    let walker = WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.'));
+
+    for entry in walker {
+        let entry = entry?;
+        let matches_extension = entry
+            .path()
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extensions.contains(&extension.to_lowercase().as_str()));
+        if !entry.file_type().is_file() || !matches_extension {
+            continue;
+        }
+
+        let name = entry.path().strip_prefix(root).unwrap_or(entry.path()).display().to_string();
+        files.push(ImportFile {
+            name,
+            data: std::fs::read(entry.path())?,
+        });
+    }
+
+    Ok(files)
+}
+
+pub async fn run_site(pool: &MySqlPool, out_dir: &Path, base_url: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let written = SiteService::new(pool)
+        .generate(out_dir, base_url)
+        .await
+        .map_err(|e| format!("Failed to generate site: {:?}", e))?;
+    tracing::info!("wrote {} files to {}", written, out_dir.display());
+
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, TimeZone};
+
+// 日本で使うことを想定した既定のタイムゾーン
+const DEFAULT_TIMEZONE_OFFSET_SECS: i32 = 9 * 3600;
+
+/// 日記のタイムゾーン（UTCからのオフセット）を取得します。
+/// DBの日時はUTCで保存されているため、日付の区切りや外部形式との変換に使います。
+/// 環境変数 `DIARY_TIMEZONE` に `+09:00` のような形式で指定します。
+pub fn diary_timezone() -> FixedOffset {
+    std::env::var("DIARY_TIMEZONE")
+        .ok()
+        .and_then(|value| value.trim().parse::<FixedOffset>().ok())
+        .unwrap_or_else(|| FixedOffset::east_opt(DEFAULT_TIMEZONE_OFFSET_SECS).unwrap())
+}
+
+/// 日記のタイムゾーンでの日付の始まり（0時）を、DBと同じUTCの日時で返します。
+pub fn start_of_day_utc(date: NaiveDate, timezone: FixedOffset) -> NaiveDateTime {
+    // 固定オフセットのため、ローカル時刻は常に一意に決まる
+    timezone
+        .from_local_datetime(&date.and_time(chrono::NaiveTime::MIN))
+        .unwrap()
+        .naive_utc()
+}
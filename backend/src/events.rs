@@ -0,0 +1,227 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use serde::Serialize;
+use sqlx::{MySql, Transaction};
+use tokio::sync::broadcast;
+
+use crate::handlers::diary::EntryWithTagsResponse;
+use crate::models::tag::Tag;
+use crate::services::webhook_service;
+
+/// イベントの種類の一覧（Webhookの購読で指定できる値）
+pub const EVENT_TYPES: [&str; 5] = ["entry.created", "entry.updated", "entry.deleted", "entry.unlocked", "tag.created"];
+
+/// 再送できないイベントがあったことを知らせるイベントの種類（SSEだけで配信します）
+/// 受け取ったクライアントは、一覧などを読み込み直します。
+pub const RESET_EVENT: &str = "reset";
+
+// 再送のために保持するイベント数の上限
+const HISTORY_CAPACITY: usize = 256;
+// 購読者ごとに溜められる未配信イベント数の上限（超えた購読者は切断され、再接続時に再送を受ける）
+const CHANNEL_CAPACITY: usize = 64;
+
+static BUS: LazyLock<EventBus> = LazyLock::new(EventBus::new);
+
+/// エントリやタグの変更を知らせるイベント
+pub enum DiaryEvent<'a> {
+    EntryCreated(&'a EntryWithTagsResponse),
+    EntryUpdated(&'a EntryWithTagsResponse),
+    EntryDeleted { id: i32 },
+    // タイムカプセルの開封日時になった
+    EntryUnlocked(&'a EntryWithTagsResponse),
+    TagCreated(&'a Tag),
+}
+
+impl DiaryEvent<'_> {
+    /// イベントの種類（SSEの `event` フィールドとWebhookの購読に使います）
+    pub fn name(&self) -> &'static str {
+        match self {
+            DiaryEvent::EntryCreated(_) => "entry.created",
+            DiaryEvent::EntryUpdated(_) => "entry.updated",
+            DiaryEvent::EntryDeleted { .. } => "entry.deleted",
+            DiaryEvent::EntryUnlocked(_) => "entry.unlocked",
+            DiaryEvent::TagCreated(_) => "tag.created",
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Result<String> {
+        #[derive(Serialize)]
+        struct DeletedEntry {
+            id: i32,
+        }
+
+        match self {
+            DiaryEvent::EntryCreated(entry) | DiaryEvent::EntryUpdated(entry) | DiaryEvent::EntryUnlocked(entry) => {
+                serde_json::to_string(entry)
+            }
+            DiaryEvent::EntryDeleted { id } => serde_json::to_string(&DeletedEntry { id: *id }),
+            DiaryEvent::TagCreated(tag) => serde_json::to_string(tag),
+        }
+    }
+}
+
+/// 配信済みのイベント（内容は発行時にJSONにしておく）
+#[derive(Debug)]
+pub struct PublishedEvent {
+    pub id: u64,
+    pub name: &'static str,
+    pub data: String,
+}
+
+struct EventBus {
+    sender: broadcast::Sender<Arc<PublishedEvent>>,
+    history: Mutex<History>,
+}
+
+struct History {
+    next_id: u64,
+    events: VecDeque<Arc<PublishedEvent>>,
+}
+
+impl History {
+    // `last_event_id` より後のイベントを返します。
+    // 保持している範囲より前のイベント（古くて捨てたもの、再起動前のもの）が抜けている場合は、代わりに `reset` を返します。
+    fn replay(&self, last_event_id: u64) -> Vec<Arc<PublishedEvent>> {
+        let oldest_id = self.events.front().map_or(self.next_id, |event| event.id);
+        if last_event_id.saturating_add(1) < oldest_id {
+            // 次の再接続では、この時点より後のイベントから再送する
+            return vec![Arc::new(PublishedEvent {
+                id: self.next_id.saturating_sub(1),
+                name: RESET_EVENT,
+                data: "{}".to_string(),
+            })];
+        }
+        self.events
+            .iter()
+            .filter(|event| event.id > last_event_id)
+            .cloned()
+            .collect()
+    }
+}
+
+impl EventBus {
+    fn new() -> Self {
+        // 再起動後のIDが以前のIDより大きくなるよう、起動時刻（マイクロ秒）から採番する
+        let next_id = u64::try_from(chrono::Utc::now().timestamp_micros()).unwrap_or(0);
+        Self {
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+            history: Mutex::new(History {
+                next_id,
+                events: VecDeque::with_capacity(HISTORY_CAPACITY),
+            }),
+        }
+    }
+}
+
+/// 変更と同じトランザクションで記録したイベント。
+/// コミットした後に `publish` に渡して配信します。
+#[must_use = "recorded events must be published after the transaction commits"]
+pub struct RecordedEvent {
+    name: &'static str,
+    data: String,
+}
+
+/// 購読しているWebhookへの配信を、イベントの元になった変更と同じトランザクションでキューに追加します（アウトボックス）。
+/// 配信は変更と一緒にコミット・ロールバックされるため、保存した変更の配信が失われることはありません。
+/// 戻り値はコミットした後に `publish` に渡します。
+pub async fn record(tx: &mut Transaction<'_, MySql>, event: DiaryEvent<'_>) -> Result<RecordedEvent, sqlx::Error> {
+    let name = event.name();
+    let data = event
+        .to_json()
+        .map_err(|err| sqlx::Error::Protocol(format!("failed to serialize {} event: {}", name, err)))?;
+    webhook_service::enqueue_deliveries_tx(tx, name, &data).await?;
+    Ok(RecordedEvent { name, data })
+}
+
+/// コミットしたイベントをSSEの購読者に配信し、Webhookの送信タスクに知らせます。
+pub fn publish(events: impl IntoIterator<Item = RecordedEvent>) {
+    let mut published = false;
+    for event in events {
+        broadcast(event.name, event.data);
+        published = true;
+    }
+    if published {
+        webhook_service::wake_worker();
+    }
+}
+
+// イベントを購読者に配信し、再送用に保持します
+fn broadcast(name: &'static str, data: String) {
+    // 購読の開始と配信が入れ替わらないよう、ロックを持ったまま送る
+    let mut history = BUS.history.lock().unwrap();
+    let published = Arc::new(PublishedEvent {
+        id: history.next_id,
+        name,
+        data,
+    });
+    history.next_id += 1;
+    if history.events.len() >= HISTORY_CAPACITY {
+        history.events.pop_front();
+    }
+    history.events.push_back(published.clone());
+    // 購読者がいない場合のエラーは無視する
+    let _ = BUS.sender.send(published);
+}
+
+/// イベントを購読します。
+/// `last_event_id` を指定した場合は、それより後に発行され、まだ保持しているイベントを再送分として返します。
+/// 保持していないイベントが間にある場合は、再送分の代わりに `reset` イベントを返します。
+pub fn subscribe(last_event_id: Option<u64>) -> (Vec<Arc<PublishedEvent>>, broadcast::Receiver<Arc<PublishedEvent>>) {
+    let history = BUS.history.lock().unwrap();
+    let missed = last_event_id.map(|last_event_id| history.replay(last_event_id)).unwrap_or_default();
+    (missed, BUS.sender.subscribe())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(next_id: u64, ids: &[u64]) -> History {
+        History {
+            next_id,
+            events: ids
+                .iter()
+                .map(|&id| {
+                    Arc::new(PublishedEvent {
+                        id,
+                        name: "entry.created",
+                        data: "{}".to_string(),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    fn names_and_ids(events: &[Arc<PublishedEvent>]) -> Vec<(&'static str, u64)> {
+        events.iter().map(|event| (event.name, event.id)).collect()
+    }
+
+    #[test]
+    fn replays_events_after_last_event_id() {
+        let history = history(13, &[10, 11, 12]);
+
+        assert_eq!(names_and_ids(&history.replay(10)), [("entry.created", 11), ("entry.created", 12)]);
+        assert_eq!(
+            names_and_ids(&history.replay(9)),
+            [("entry.created", 10), ("entry.created", 11), ("entry.created", 12)]
+        );
+        assert!(history.replay(12).is_empty());
+    }
+
+    #[test]
+    fn resets_when_events_were_dropped_from_history() {
+        let history = history(13, &[10, 11, 12]);
+
+        assert_eq!(names_and_ids(&history.replay(8)), [(RESET_EVENT, 12)]);
+    }
+
+    #[test]
+    fn resets_when_last_event_id_is_from_before_restart() {
+        // 再起動後でまだイベントがない
+        let history = history(1_000, &[]);
+
+        assert_eq!(names_and_ids(&history.replay(500)), [(RESET_EVENT, 999)]);
+        assert!(history.replay(999).is_empty());
+    }
+}
@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Multipart, Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use sqlx::MySqlPool; // State抽出のために保持
+
+// サービスとそのエラー型をインポート
+use crate::images::{self, ThumbnailFormat};
+use crate::models::attachment::AttachmentResponse;
+use crate::services::attachment_service::{AttachmentService, AttachmentServiceError, UploadedFile};
+use crate::storage::Storage;
+
+// 添付ファイルとサムネイルは内容が変わらない（差し替えは新しいIDになる）ため、長期間キャッシュさせる
+// 日記の添付ファイルは個人的なものなので、共有キャッシュ（プロキシやCDN）には保存させない
+const IMMUTABLE_CACHE_CONTROL: &str = "private, max-age=31536000, immutable";
+
+#[derive(Debug, Deserialize)]
+pub struct UploadQuery {
+    // 画像の位置情報などのメタデータを残す
+    #[serde(default)]
+    keep_metadata: bool,
+}
+
+// エントリの添付ファイル一覧を取得
+pub async fn get_attachments(
+    State(pool): State<MySqlPool>,
+    State(storage): State<Arc<dyn Storage>>,
+    Path(entry_id): Path<i32>,
+) -> impl IntoResponse {
+    let attachment_service = AttachmentService::new(&pool, &storage);
+
+    match attachment_service.get_attachments(entry_id).await {
+        Ok(attachments) => {
+            let attachments: Vec<AttachmentResponse> = attachments.into_iter().map(AttachmentResponse::from).collect();
+            (StatusCode::OK, Json(attachments)).into_response()
+        }
+        Err(AttachmentServiceError::NotFound) => {
+            (StatusCode::NOT_FOUND, "Entry not found").into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch attachments: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching attachments: {:?}", e)).into_response()
+        }
+    }
+}
+
+// エントリにファイルを添付（multipart/form-data、ファイル名の付いたパートをすべて保存する）
+pub async fn upload_attachments(
+    State(pool): State<MySqlPool>,
+    State(storage): State<Arc<dyn Storage>>,
+    Path(entry_id): Path<i32>,
+    Query(query): Query<UploadQuery>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let mut files = Vec::new();
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return (e.status(), e.body_text()).into_response(),
+        };
+        let Some(filename) = field.file_name().map(str::to_string) else {
+            continue;
+        };
+        match field.bytes().await {
+            Ok(data) => files.push(UploadedFile { filename, data }),
+            Err(e) => return (e.status(), e.body_text()).into_response(),
+        }
+    }
+    if files.is_empty() {
+        return (StatusCode::BAD_REQUEST, "No files were uploaded").into_response();
+    }
+
+    let attachment_service = AttachmentService::new(&pool, &storage);
+
+    match attachment_service.upload_attachments(entry_id, files, query.keep_metadata).await {
+        Ok(attachments) => {
+            let attachments: Vec<AttachmentResponse> = attachments.into_iter().map(AttachmentResponse::from).collect();
+            (StatusCode::CREATED, Json(attachments)).into_response()
+        }
+        Err(AttachmentServiceError::NotFound) => {
+            (StatusCode::NOT_FOUND, "Entry not found").into_response()
+        }
+        Err(AttachmentServiceError::InvalidFile(reason)) => {
+            (StatusCode::BAD_REQUEST, reason).into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to upload attachments: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error uploading attachments: {:?}", e)).into_response()
+        }
+    }
+}
+
+// 添付ファイルをダウンロード
+pub async fn get_attachment(
+    State(pool): State<MySqlPool>,
+    State(storage): State<Arc<dyn Storage>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    let attachment_service = AttachmentService::new(&pool, &storage);
+
+    match attachment_service.get_attachment_file(id).await {
+        Ok((attachment, data)) => {
+            // 内容から判定した画像以外は、APIと同じオリジンで開かれないようダウンロードさせる
+            // （以前に送られた種類のまま保存された行もあるため、完全一致で確認する）
+            let inline = images::INLINE_CONTENT_TYPES.contains(&attachment.content_type.as_str());
+            let content_type = if inline {
+                attachment.content_type
+            } else {
+                "application/octet-stream".to_string()
+            };
+            let disposition = format!(
+                "{}; filename*=UTF-8''{}",
+                if inline { "inline" } else { "attachment" },
+                percent_encode(&attachment.filename)
+            );
+            (
+                [
+                    (header::CONTENT_TYPE, content_type),
+                    (header::CONTENT_DISPOSITION, disposition),
+                    (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+                    (header::CACHE_CONTROL, IMMUTABLE_CACHE_CONTROL.to_string()),
+                ],
+                data,
+            )
+                .into_response()
+        }
+        Err(AttachmentServiceError::NotFound) => {
+            (StatusCode::NOT_FOUND, "Attachment not found").into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch attachment: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching attachment: {:?}", e)).into_response()
+        }
+    }
+}
+
+// 画像の添付ファイルのサムネイルを取得（`:name` は `320.webp` のような {長辺}.{拡張子}）
+pub async fn get_thumbnail(
+    State(pool): State<MySqlPool>,
+    State(storage): State<Arc<dyn Storage>>,
+    Path((id, name)): Path<(i32, String)>,
+) -> impl IntoResponse {
+    let parsed = name.split_once('.').and_then(|(max_size, extension)| {
+        Some((max_size.parse::<u32>().ok()?, ThumbnailFormat::from_extension(extension)?))
+    });
+    let Some((max_size, format)) = parsed else {
+        return (StatusCode::NOT_FOUND, "Thumbnail not found").into_response();
+    };
+
+    let attachment_service = AttachmentService::new(&pool, &storage);
+
+    match attachment_service.get_thumbnail_file(id, max_size, format).await {
+        Ok(data) => (
+            [
+                (header::CONTENT_TYPE, format.content_type()),
+                (header::CACHE_CONTROL, IMMUTABLE_CACHE_CONTROL),
+            ],
+            data,
+        )
+            .into_response(),
+        Err(AttachmentServiceError::NotFound) => {
+            (StatusCode::NOT_FOUND, "Thumbnail not found").into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch thumbnail: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching thumbnail: {:?}", e)).into_response()
+        }
+    }
+}
+
+// 添付ファイルを削除
+pub async fn delete_attachment(
+    State(pool): State<MySqlPool>,
+    State(storage): State<Arc<dyn Storage>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    let attachment_service = AttachmentService::new(&pool, &storage);
+
+    match attachment_service.delete_attachment(id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(AttachmentServiceError::NotFound) => {
+            (StatusCode::NOT_FOUND, "Attachment not found").into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to delete attachment: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error deleting attachment: {:?}", e)).into_response()
+        }
+    }
+}
+
+// Content-Disposition の filename* に使うパーセントエンコーディング（RFC 5987）
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            byte => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
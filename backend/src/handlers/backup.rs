@@ -0,0 +1,54 @@
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use sqlx::MySqlPool; // State抽出のために保持
+
+// サービスとそのエラー型をインポート
+use crate::services::backup_service::{BackupService, BackupServiceError};
+use crate::models::backup::{Backup, RestoreQuery};
+
+// バックアップ（JSON）をダウンロード
+pub async fn get_backup(State(pool): State<MySqlPool>) -> impl IntoResponse {
+    let backup_service = BackupService::new(&pool);
+
+    match backup_service.export().await {
+        Ok(backup) => {
+            let filename = format!("diary-backup-{}.json", backup.exported_at.format("%Y%m%d"));
+            (
+                StatusCode::OK,
+                [(header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename))],
+                Json(backup),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to export backup: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error exporting backup: {:?}", e)).into_response()
+        }
+    }
+}
+
+// バックアップをリストア（?dry_run=true で衝突の確認のみ）
+pub async fn restore_backup(
+    State(pool): State<MySqlPool>,
+    Query(query): Query<RestoreQuery>,
+    Json(backup): Json<Backup>,
+) -> impl IntoResponse {
+    let backup_service = BackupService::new(&pool);
+
+    match backup_service.restore(&backup, query.dry_run).await {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(BackupServiceError::UnsupportedVersion(version)) => (
+            StatusCode::BAD_REQUEST,
+            format!("Unsupported backup version: {}", version),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to restore backup: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error restoring backup: {:?}", e)).into_response()
+        }
+    }
+}
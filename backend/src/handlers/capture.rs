@@ -0,0 +1,110 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use sqlx::MySqlPool; // State抽出のために保持
+
+// サービスとそのエラー型をインポート
+use crate::services::capture_service::{CaptureService, CaptureServiceError};
+use crate::models::capture::{CaptureRequest, CaptureResponse, CreateCaptureTokenRequest};
+
+// キャプチャ用トークンの一覧を取得（トークン本体は含まない）
+pub async fn get_capture_tokens(State(pool): State<MySqlPool>) -> impl IntoResponse {
+    let capture_service = CaptureService::new(&pool);
+
+    match capture_service.get_tokens().await {
+        Ok(tokens) => (StatusCode::OK, Json(tokens)).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to fetch capture tokens: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching capture tokens: {:?}", e)).into_response()
+        }
+    }
+}
+
+// キャプチャ用トークンを発行
+pub async fn create_capture_token(
+    State(pool): State<MySqlPool>,
+    Json(request): Json<CreateCaptureTokenRequest>,
+) -> impl IntoResponse {
+    let capture_service = CaptureService::new(&pool);
+
+    match capture_service.create_token(&request.name).await {
+        Ok(token) => (StatusCode::CREATED, Json(token)).into_response(),
+        Err(CaptureServiceError::EmptyContent) => {
+            (StatusCode::BAD_REQUEST, "Name cannot be empty").into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to create capture token: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error creating capture token: {:?}", e)).into_response()
+        }
+    }
+}
+
+// キャプチャ用トークンを削除（失効）
+pub async fn delete_capture_token(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    let capture_service = CaptureService::new(&pool);
+
+    match capture_service.delete_token(id).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(CaptureServiceError::NotFound) => {
+            (StatusCode::NOT_FOUND, "Capture token not found").into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to delete capture token: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error deleting capture token: {:?}", e)).into_response()
+        }
+    }
+}
+
+// URLに含めたトークンで認証し、送られた本文からエントリを作成
+// ボディはプレーンテキスト、フォーム（application/x-www-form-urlencoded）、JSONのいずれでも受け付ける
+pub async fn capture(
+    State(pool): State<MySqlPool>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+
+    let request = match content_type.as_str() {
+        "application/json" => serde_json::from_slice::<CaptureRequest>(&body).map_err(|e| e.to_string()),
+        "application/x-www-form-urlencoded" => {
+            serde_urlencoded::from_bytes::<CaptureRequest>(&body).map_err(|e| e.to_string())
+        }
+        _ => String::from_utf8(body.to_vec())
+            .map(|content| CaptureRequest { content, tags: None })
+            .map_err(|_| "Body must be UTF-8 text".to_string()),
+    };
+    let request = match request {
+        Ok(request) => request,
+        Err(message) => return (StatusCode::BAD_REQUEST, message).into_response(),
+    };
+
+    let capture_service = CaptureService::new(&pool);
+    let tags = request.tags.map(|tags| tags.into_vec()).unwrap_or_default();
+
+    match capture_service.capture(&token, &request.content, tags).await {
+        Ok(id) => (StatusCode::CREATED, Json(CaptureResponse { id })).into_response(),
+        Err(CaptureServiceError::Unauthorized) => {
+            (StatusCode::UNAUTHORIZED, "Invalid capture token").into_response()
+        }
+        Err(CaptureServiceError::EmptyContent) => {
+            (StatusCode::BAD_REQUEST, "Content cannot be empty").into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to capture entry: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error capturing entry: {:?}", e)).into_response()
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use sqlx::MySqlPool; // State抽出のために保持
+
+// サービスとそのエラー型をインポート
+use crate::services::comment_service::{CommentService, CommentServiceError};
+use crate::models::comment::{CreateCommentRequest, UpdateCommentRequest};
+
+// エントリのコメント一覧を取得
+pub async fn get_comments(
+    State(pool): State<MySqlPool>,
+    Path(entry_id): Path<i32>,
+) -> impl IntoResponse {
+    let comment_service = CommentService::new(&pool);
+
+    match comment_service.get_comments(entry_id).await {
+        Ok(comments) => (StatusCode::OK, Json(comments)).into_response(),
+        Err(CommentServiceError::NotFound) => {
+            (StatusCode::NOT_FOUND, "Entry not found").into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch comments: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching comments: {:?}", e)).into_response()
+        }
+    }
+}
+
+// エントリにコメントを追加
+pub async fn create_comment(
+    State(pool): State<MySqlPool>,
+    Path(entry_id): Path<i32>,
+    Json(request): Json<CreateCommentRequest>,
+) -> impl IntoResponse {
+    // ハンドラー層での基本的なバリデーション
+    if request.author.trim().is_empty() || request.content.trim().is_empty() {
+        return (StatusCode::BAD_REQUEST, "Author and content cannot be empty").into_response();
+    }
+    if request.author.trim().chars().count() > 50 {
+        return (StatusCode::BAD_REQUEST, "Author name is too long").into_response();
+    }
+
+    let comment_service = CommentService::new(&pool);
+
+    match comment_service.create_comment(entry_id, &request).await {
+        Ok(comment) => (StatusCode::CREATED, Json(comment)).into_response(),
+        Err(CommentServiceError::NotFound) => {
+            (StatusCode::NOT_FOUND, "Entry not found").into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to create comment: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error creating comment: {:?}", e)).into_response()
+        }
+    }
+}
+
+// コメントの本文を更新
+pub async fn update_comment(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<i32>,
+    Json(request): Json<UpdateCommentRequest>,
+) -> impl IntoResponse {
+    if request.content.trim().is_empty() {
+        return (StatusCode::BAD_REQUEST, "Content cannot be empty").into_response();
+    }
+
+    let comment_service = CommentService::new(&pool);
+
+    match comment_service.update_comment(id, &request).await {
+        Ok(comment) => (StatusCode::OK, Json(comment)).into_response(),
+        Err(CommentServiceError::NotFound) => {
+            (StatusCode::NOT_FOUND, "Comment not found").into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to update comment: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error updating comment: {:?}", e)).into_response()
+        }
+    }
+}
+
+// コメントを削除
+pub async fn delete_comment(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    let comment_service = CommentService::new(&pool);
+
+    match comment_service.delete_comment(id).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(CommentServiceError::NotFound) => {
+            (StatusCode::NOT_FOUND, "Comment not found").into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to delete comment: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error deleting comment: {:?}", e)).into_response()
+        }
+    }
+}
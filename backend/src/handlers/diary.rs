@@ -1,36 +1,51 @@
+use std::sync::Arc;
+
 use axum::{
     Json,
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
 };
+use chrono::{Datelike, NaiveDate};
 use serde::{Deserialize, Serialize};
 use sqlx::MySqlPool; // State抽出のために保持
 
+use crate::config;
+
 // サービスとそのエラー型をインポート
 use crate::services::diary_service::DiaryService;
+use crate::services::prompt_service::{PromptService, PromptServiceError};
+use crate::storage::Storage;
 // リクエスト/レスポンス関連のモデルと構造体を保持
-use crate::models::entry::{CreateEntryRequest, Entry}; // Entryは当面保持、EntriesResponseが削除されれば後で削除検討
+use crate::models::entry::{CreateEntryRequest, RandomEntryQuery};
 use crate::models::tag::{EntryWithTags, Tag}; // TagはEntryWithTagsResponseで使用、EntryWithTagsはcreate_entryで使用
+use crate::models::attachment::AttachmentResponse;
+use crate::models::reaction::ReactionCount;
 
 #[derive(Debug, Deserialize)]
 pub struct PaginationQuery {
     page: Option<u32>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct EntriesResponse {
-    pub entries: Vec<Entry>,
-    pub total_pages: u32,
-    pub current_page: u32,
-}
-
 #[derive(Debug, Serialize)]
 pub struct EntryWithTagsResponse {
     pub id: i32,
     pub content: String,
+    // Markdownとして描画し、サニタイズした本文
+    pub content_html: String,
     pub datetime: chrono::NaiveDateTime,
     pub tags: Vec<Tag>,
+    pub comment_count: i64,
+    pub reaction_counts: Vec<ReactionCount>,
+    pub attachments: Vec<AttachmentResponse>,
+    // 答えたお題のID
+    pub prompt_id: Option<i32>,
+    // タイムカプセルの開封日時
+    pub unlock_at: Option<chrono::NaiveDateTime>,
+    // 開封前のタイムカプセル（本文は開封日時を知らせる文に置き換えている）
+    pub locked: bool,
+    // 予約投稿の公開日時
+    pub publish_at: Option<chrono::NaiveDateTime>,
 }
 
 #[derive(Debug, Serialize)]
@@ -40,6 +55,29 @@ pub struct EntriesWithTagsResponse {
     pub current_page: u32,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct OnThisDayQuery {
+    // `MM-DD` 形式。省略時は今日（日記のタイムゾーン）
+    date: Option<String>,
+    // 前後3日を含む1週間のエントリを返す
+    #[serde(default)]
+    week: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OnThisDayYear {
+    pub year: i32,
+    pub years_ago: i32,
+    pub entries: Vec<EntryWithTagsResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OnThisDayResponse {
+    pub date: String,
+    pub week: bool,
+    pub years: Vec<OnThisDayYear>,
+}
+
 pub async fn get_entries(
     State(pool): State<MySqlPool>,
     Query(pagination): Query<PaginationQuery>,
@@ -67,6 +105,27 @@ pub async fn create_entry(
     State(pool): State<MySqlPool>,
     Json(request): Json<EntryWithTags>,
 ) -> impl IntoResponse {
+    // タイムカプセルの開封日時は未来の日時に限る
+    if request.unlock_at.is_some_and(|unlock_at| unlock_at <= chrono::Utc::now().naive_utc()) {
+        return (StatusCode::BAD_REQUEST, "`unlock_at` must be in the future").into_response();
+    }
+    // 予約投稿の公開日時も未来の日時に限る
+    if request.publish_at.is_some_and(|publish_at| publish_at <= chrono::Utc::now().naive_utc()) {
+        return (StatusCode::BAD_REQUEST, "`publish_at` must be in the future").into_response();
+    }
+
+    // 答えたお題が存在するかを確認
+    if let Some(prompt_id) = request.prompt_id {
+        match PromptService::new(&pool).get_prompt(prompt_id).await {
+            Ok(_) => {}
+            Err(PromptServiceError::NotFound) => return (StatusCode::BAD_REQUEST, "Prompt not found").into_response(),
+            Err(e) => {
+                tracing::error!("Failed to fetch prompt: {:?}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching prompt: {:?}", e)).into_response();
+            }
+        }
+    }
+
     // DiaryServiceをインスタンス化
     let diary_service = DiaryService::new(&pool);
 
@@ -80,6 +139,19 @@ pub async fn create_entry(
     }
 }
 
+// 公開日時前の予約投稿を取得
+pub async fn get_scheduled_entries(State(pool): State<MySqlPool>) -> impl IntoResponse {
+    let diary_service = DiaryService::new(&pool);
+
+    match diary_service.get_scheduled_entries().await {
+        Ok(entries) => (StatusCode::OK, Json(entries)).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to fetch scheduled entries: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching scheduled entries: {:?}", e)).into_response()
+        }
+    }
+}
+
 // 従来のCreateEntryRequestを使用するエンドポイントも残しておく（後方互換性のため）
 pub async fn create_simple_entry(
     State(pool): State<MySqlPool>,
@@ -111,3 +183,108 @@ pub async fn get_entry_count(State(pool): State<MySqlPool>) -> impl IntoResponse
         }
     }
 }
+
+// 過去の各年の同じ日（または同じ週）に書いたエントリを年ごとに取得
+pub async fn get_entries_on_this_day(
+    State(pool): State<MySqlPool>,
+    Query(query): Query<OnThisDayQuery>,
+) -> impl IntoResponse {
+    let (month, day) = match query.date.as_deref() {
+        Some(date) => match parse_month_day(date) {
+            Some(month_day) => month_day,
+            None => return (StatusCode::BAD_REQUEST, "`date` must be in MM-DD format").into_response(),
+        },
+        None => {
+            let today = chrono::Utc::now().with_timezone(&config::diary_timezone());
+            (today.month(), today.day())
+        }
+    };
+
+    let diary_service = DiaryService::new(&pool);
+
+    match diary_service.get_entries_on_this_day(month, day, query.week).await {
+        Ok(years) => {
+            let response = OnThisDayResponse {
+                date: format!("{:02}-{:02}", month, day),
+                week: query.week,
+                years,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch entries on this day: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching entries: {:?}", e)).into_response()
+        }
+    }
+}
+
+// 条件に合うエントリをランダムに1件取得（`mode=rediscover` で最近表示していないものを優先）
+pub async fn get_random_entry(
+    State(pool): State<MySqlPool>,
+    Query(query): Query<RandomEntryQuery>,
+) -> impl IntoResponse {
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from > to
+    {
+        return (StatusCode::BAD_REQUEST, "`from` must not be after `to`").into_response();
+    }
+
+    let diary_service = DiaryService::new(&pool);
+
+    match diary_service.get_random_entry(&query).await {
+        Ok(Some(entry)) => (StatusCode::OK, Json(entry)).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "No entries found").into_response(),
+        Err(e) => {
+            tracing::error!("Failed to fetch random entry: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching random entry: {:?}", e)).into_response()
+        }
+    }
+}
+
+// `MM-DD` を月と日に分けます（うるう年にしかない 02-29 も受け付ける）
+fn parse_month_day(value: &str) -> Option<(u32, u32)> {
+    let (month, day) = value.split_once('-')?;
+    let (month, day) = (month.parse().ok()?, day.parse().ok()?);
+    NaiveDate::from_ymd_opt(2000, month, day)?;
+    Some((month, day))
+}
+
+// エントリの本文とタグを更新（タグは指定されたものに置き換える）
+pub async fn update_entry(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<i32>,
+    Json(request): Json<EntryWithTags>,
+) -> impl IntoResponse {
+    if request.content.trim().is_empty() {
+        return (StatusCode::BAD_REQUEST, "Content cannot be empty").into_response();
+    }
+
+    let diary_service = DiaryService::new(&pool);
+
+    match diary_service.update_entry_with_tags(id, &request).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (StatusCode::NOT_FOUND, "Entry not found").into_response(),
+        Err(e) => {
+            tracing::error!("Failed to update entry: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error updating entry: {:?}", e)).into_response()
+        }
+    }
+}
+
+// エントリを削除（タグとの関連・コメント・リアクション・添付ファイルも削除される）
+pub async fn delete_entry(
+    State(pool): State<MySqlPool>,
+    State(storage): State<Arc<dyn Storage>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    let diary_service = DiaryService::new(&pool);
+
+    match diary_service.delete_entry(id, storage.as_ref()).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (StatusCode::NOT_FOUND, "Entry not found").into_response(),
+        Err(e) => {
+            tracing::error!("Failed to delete entry: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error deleting entry: {:?}", e)).into_response()
+        }
+    }
+}
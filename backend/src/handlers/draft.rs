@@ -0,0 +1,105 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use sqlx::MySqlPool; // State抽出のために保持
+
+use crate::services::diary_service::DiaryService;
+use crate::models::entry::SaveDraftRequest;
+
+// 下書きのキーの長さの上限
+const MAX_DRAFT_KEY_LENGTH: usize = 64;
+
+// 下書きの一覧を取得
+pub async fn get_drafts(State(pool): State<MySqlPool>) -> impl IntoResponse {
+    let diary_service = DiaryService::new(&pool);
+
+    match diary_service.get_drafts().await {
+        Ok(drafts) => (StatusCode::OK, Json(drafts)).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to fetch drafts: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching drafts: {:?}", e)).into_response()
+        }
+    }
+}
+
+// キーで下書きを取得
+pub async fn get_draft(State(pool): State<MySqlPool>, Path(key): Path<String>) -> impl IntoResponse {
+    if !is_valid_key(&key) {
+        return (StatusCode::BAD_REQUEST, "Invalid draft key").into_response();
+    }
+    let diary_service = DiaryService::new(&pool);
+
+    match diary_service.get_draft(&key).await {
+        Ok(Some(draft)) => (StatusCode::OK, Json(draft)).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "Draft not found").into_response(),
+        Err(e) => {
+            tracing::error!("Failed to fetch draft: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching draft: {:?}", e)).into_response()
+        }
+    }
+}
+
+// 下書きを自動保存（同じキーへの保存は上書きになる）
+pub async fn save_draft(
+    State(pool): State<MySqlPool>,
+    Path(key): Path<String>,
+    Json(request): Json<SaveDraftRequest>,
+) -> impl IntoResponse {
+    if !is_valid_key(&key) {
+        return (StatusCode::BAD_REQUEST, "Invalid draft key").into_response();
+    }
+    let diary_service = DiaryService::new(&pool);
+
+    match diary_service.save_draft(&key, &request).await {
+        Ok(Some(draft)) => (StatusCode::OK, Json(draft)).into_response(),
+        Ok(None) => (StatusCode::CONFLICT, "Draft already published").into_response(),
+        Err(e) => {
+            tracing::error!("Failed to save draft: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error saving draft: {:?}", e)).into_response()
+        }
+    }
+}
+
+// 下書きを公開
+pub async fn publish_draft(State(pool): State<MySqlPool>, Path(key): Path<String>) -> impl IntoResponse {
+    if !is_valid_key(&key) {
+        return (StatusCode::BAD_REQUEST, "Invalid draft key").into_response();
+    }
+    let diary_service = DiaryService::new(&pool);
+
+    match diary_service.publish_draft(&key).await {
+        Ok(Some(entry)) => (StatusCode::OK, Json(entry)).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "Draft not found").into_response(),
+        Err(e) => {
+            tracing::error!("Failed to publish draft: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error publishing draft: {:?}", e)).into_response()
+        }
+    }
+}
+
+// 下書きを削除
+pub async fn delete_draft(State(pool): State<MySqlPool>, Path(key): Path<String>) -> impl IntoResponse {
+    if !is_valid_key(&key) {
+        return (StatusCode::BAD_REQUEST, "Invalid draft key").into_response();
+    }
+    let diary_service = DiaryService::new(&pool);
+
+    match diary_service.delete_draft(&key).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (StatusCode::NOT_FOUND, "Draft not found").into_response(),
+        Err(e) => {
+            tracing::error!("Failed to delete draft: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error deleting draft: {:?}", e)).into_response()
+        }
+    }
+}
+
+// 下書きのキーは英数字・`-`・`_` の1〜64文字（クライアントが生成したUUIDなど）
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= MAX_DRAFT_KEY_LENGTH
+        && key.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
@@ -0,0 +1,50 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::{
+    http::HeaderMap,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+};
+use futures::stream::{self, Stream, StreamExt};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+use crate::events::{self, PublishedEvent};
+
+// エントリやタグの変更をServer-Sent Eventsで配信
+// 再接続時にブラウザが送る Last-Event-ID より後のイベントは、保持している範囲で再送する
+// 保持している範囲より古い場合は `reset` イベントを送り、クライアントに読み込み直してもらう
+pub async fn get_events(headers: HeaderMap) -> impl IntoResponse {
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+
+    let (missed, receiver) = events::subscribe(last_event_id);
+    let stream = stream::iter(missed).chain(live_events(receiver)).map(to_sse_event);
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+fn live_events(receiver: Receiver<Arc<PublishedEvent>>) -> impl Stream<Item = Arc<PublishedEvent>> {
+    stream::unfold(receiver, |mut receiver| async move {
+        match receiver.recv().await {
+            Ok(event) => Some((event, receiver)),
+            // 配信が追いつかなかった場合は切断し、クライアントの再接続（Last-Event-ID による再送）に任せる
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("Closing event stream that lagged behind by {} events", skipped);
+                None
+            }
+            Err(RecvError::Closed) => None,
+        }
+    })
+}
+
+fn to_sse_event(event: Arc<PublishedEvent>) -> Result<Event, Infallible> {
+    Ok(Event::default()
+        .id(event.id.to_string())
+        .event(event.name)
+        .data(&event.data))
+}
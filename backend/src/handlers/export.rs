@@ -0,0 +1,184 @@
+use std::fmt::Debug;
+use std::future::Future;
+
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use futures::{stream, StreamExt};
+use sqlx::MySqlPool; // State抽出のために保持
+use tokio::io::DuplexStream;
+use tokio_util::io::ReaderStream;
+
+// サービスとそのエラー型をインポート
+use crate::models::book::BookQuery;
+use crate::services::book_service::{BookService, BookServiceError};
+use crate::services::export_service::ExportService;
+
+// ZIPを書き込むパイプのバッファサイズ
+const EXPORT_BUFFER_SIZE: usize = 64 * 1024;
+
+// すべてのエントリをMarkdownのZIPアーカイブとしてダウンロード
+pub async fn get_markdown_export(State(pool): State<MySqlPool>) -> impl IntoResponse {
+    let filename = format!("diary-{}.zip", chrono::Utc::now().format("%Y%m%d"));
+    stream_export("markdown archive", "application/zip", &filename, |writer| async move {
+        ExportService::new(&pool).write_markdown_zip(writer).await
+    })
+    .await
+}
+
+// すべてのエントリを Movable Type 形式でダウンロード
+pub async fn get_movable_type_export(State(pool): State<MySqlPool>) -> impl IntoResponse {
+    let filename = format!("diary-{}.txt", chrono::Utc::now().format("%Y%m%d"));
+    stream_export("movable type", "text/plain; charset=utf-8", &filename, |writer| async move {
+        ExportService::new(&pool).write_movable_type(writer).await
+    })
+    .await
+}
+
+// 期間（とタグ）を指定して、エントリをEPUBの書籍としてダウンロード
+pub async fn get_epub_export(
+    State(pool): State<MySqlPool>,
+    Query(query): Query<BookQuery>,
+) -> impl IntoResponse {
+    // 条件の誤りはステータスコードで返せるよう、書き出しの前にエントリを読み込む
+    let book_service = BookService::new(&pool);
+    let book = match book_service.load_book(&query).await {
+        Ok(book) => book,
+        Err(BookServiceError::NotFound) => {
+            return (StatusCode::NOT_FOUND, "Tag not found").into_response();
+        }
+        Err(BookServiceError::InvalidRange) => {
+            return (StatusCode::BAD_REQUEST, "`from` must not be after `to`").into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Error loading entries: {:?}", e),
+            )
+                .into_response();
+        }
+    };
+
+    let filename = format!(
+        "diary-{}-{}.epub",
+        query.from.format("%Y%m%d"),
+        query.to.format("%Y%m%d")
+    );
+    stream_export("epub", "application/epub+zip", &filename, |writer| async move {
+        book.write_epub(writer).await
+    })
+    .await
+}
+
+// 書き出しとレスポンスの送信をパイプでつなぎ、全体をメモリに載せずにストリーミングします。
+// 最初のデータが書き出されるまでヘッダーを送らないため、書き出しの開始前の失敗は500を返します。
+// ヘッダーの送信後に失敗した場合は、ボディをエラーで中断します
+// （正常に終えると、途中までのファイルが完全なものに見えてしまうため）。
+async fn stream_export<F, Fut, E>(label: &'static str, content_type: &str, filename: &str, write: F) -> Response
+where
+    F: FnOnce(DuplexStream) -> Fut,
+    Fut: Future<Output = Result<(), E>> + Send + 'static,
+    E: Debug + Send + 'static,
+{
+    let (writer, reader) = tokio::io::duplex(EXPORT_BUFFER_SIZE);
+    let task = tokio::spawn(write(writer));
+    let mut chunks = ReaderStream::new(reader);
+
+    let first = match chunks.next().await {
+        Some(Ok(chunk)) => chunk,
+        Some(Err(e)) => {
+            tracing::error!("Failed to export {}: {:?}", label, e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, format!("Error exporting {}", label)).into_response();
+        }
+        // 何も書き出さずに終了した
+        None => {
+            return match task.await {
+                Ok(Ok(())) => attachment(content_type, filename, Body::empty()),
+                Ok(Err(e)) => {
+                    tracing::error!("Failed to export {}: {:?}", label, e);
+                    (StatusCode::INTERNAL_SERVER_ERROR, format!("Error exporting {}: {:?}", label, e)).into_response()
+                }
+                Err(e) => {
+                    tracing::error!("Export task for {} panicked: {:?}", label, e);
+                    (StatusCode::INTERNAL_SERVER_ERROR, format!("Error exporting {}", label)).into_response()
+                }
+            };
+        }
+    };
+
+    // 書き出しが終わった後に結果を確認し、失敗していればボディをエラーで終える
+    let finished = stream::once(async move {
+        let failed = match task.await {
+            Ok(Ok(())) => return None,
+            Ok(Err(e)) => format!("{:?}", e),
+            Err(e) => format!("task panicked: {:?}", e),
+        };
+        tracing::error!("Failed to export {}: {}", label, failed);
+        Some(Err(std::io::Error::other(format!("failed to export {}", label))))
+    })
+    .filter_map(|result| async move { result });
+    let body = Body::from_stream(stream::once(async move { Ok(first) }).chain(chunks).chain(finished));
+    attachment(content_type, filename, body)
+}
+
+// ダウンロードさせるファイルのレスポンスを作ります
+fn attachment(content_type: &str, filename: &str, body: Body) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::AsyncWriteExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn returns_error_status_when_export_fails_before_writing() {
+        let response =
+            stream_export("test", "text/plain", "test.txt", |_writer| async { Err::<(), _>("database is down") }).await;
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn aborts_body_when_export_fails_after_writing() {
+        let response = stream_export("test", "text/plain", "test.txt", |mut writer| async move {
+            writer.write_all(b"first part").await.unwrap();
+            writer.flush().await.unwrap();
+            tokio::task::yield_now().await;
+            Err::<(), _>("database is down")
+        })
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(axum::body::to_bytes(response.into_body(), usize::MAX).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn streams_whole_export() {
+        let response = stream_export("test", "text/plain", "test.txt", |mut writer| async move {
+            writer.write_all(b"first part, ").await.unwrap();
+            writer.write_all(b"second part").await.unwrap();
+            Ok::<(), ()>(())
+        })
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_DISPOSITION], "attachment; filename=\"test.txt\"");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body.as_ref(), b"first part, second part");
+    }
+}
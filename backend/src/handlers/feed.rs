@@ -0,0 +1,87 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use sqlx::MySqlPool; // State抽出のために保持
+
+// サービスとそのエラー型をインポート
+use crate::services::feed_service::{Feed, FeedService, FeedServiceError};
+
+// Atomフィードを取得
+pub async fn get_atom_feed(State(pool): State<MySqlPool>, headers: HeaderMap) -> impl IntoResponse {
+    let feed_service = FeedService::new(&pool);
+
+    match feed_service.atom_feed().await {
+        Ok(feed) => feed_response(&headers, feed),
+        Err(e) => {
+            tracing::error!("Failed to build atom feed: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error building feed: {:?}", e)).into_response()
+        }
+    }
+}
+
+// JSON Feedを取得
+pub async fn get_json_feed(State(pool): State<MySqlPool>, headers: HeaderMap) -> impl IntoResponse {
+    let feed_service = FeedService::new(&pool);
+
+    match feed_service.json_feed().await {
+        Ok(feed) => feed_response(&headers, feed),
+        Err(e) => {
+            tracing::error!("Failed to build json feed: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error building feed: {:?}", e)).into_response()
+        }
+    }
+}
+
+// タグ別のAtomフィードを取得
+pub async fn get_tag_atom_feed(
+    State(pool): State<MySqlPool>,
+    Path(tag_id): Path<i32>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let feed_service = FeedService::new(&pool);
+
+    match feed_service.tag_atom_feed(tag_id).await {
+        Ok(feed) => feed_response(&headers, feed),
+        Err(FeedServiceError::NotFound) => (StatusCode::NOT_FOUND, "Tag not found").into_response(),
+        Err(e) => {
+            tracing::error!("Failed to build tag atom feed: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error building feed: {:?}", e)).into_response()
+        }
+    }
+}
+
+// If-None-Match を評価し、変更がなければ304を返す
+// エントリの日時では削除・編集・開封などの変更を表せないため、Last-Modified は返さず本文から求めた ETag だけで判定する
+fn feed_response(headers: &HeaderMap, feed: Feed) -> Response {
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| etag_matches(value, &feed.etag));
+    let response_headers = [
+        (header::ETAG, feed.etag),
+        (header::CACHE_CONTROL, "no-cache".to_string()),
+    ];
+
+    let mut response = if not_modified {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        ([(header::CONTENT_TYPE, feed.content_type)], feed.body).into_response()
+    };
+    for (name, value) in response_headers {
+        if let Ok(value) = value.parse() {
+            response.headers_mut().insert(name, value);
+        }
+    }
+    response
+}
+
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.trim() == "*"
+        || if_none_match
+            .split(',')
+            .map(|candidate| candidate.trim().trim_start_matches("W/"))
+            .any(|candidate| candidate == etag)
+}
+
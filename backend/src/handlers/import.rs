@@ -0,0 +1,46 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use sqlx::MySqlPool; // State抽出のために保持
+
+// サービスとそのエラー型をインポート
+use crate::services::diary_service::DiaryService;
+use crate::importers::{self, ImportError};
+
+// 外部形式のファイル（またはそれを含むZIP）をアップロードしてインポート
+// 形式は movable-type / day-one / journey / markdown
+pub async fn import_entries(
+    State(pool): State<MySqlPool>,
+    Path(format): Path<String>,
+    body: Bytes,
+) -> impl IntoResponse {
+    let Some(importer) = importers::importer_for(&format) else {
+        return (StatusCode::NOT_FOUND, format!("Unknown import format: {}", format)).into_response();
+    };
+
+    let parsed = match importers::unpack_upload(&format, body.to_vec(), importer.extensions()).await {
+        Ok(files) => importer.parse(&files),
+        Err(e) => Err(e),
+    };
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(ImportError::InvalidFormat(message)) => {
+            tracing::warn!("Rejected {} import: {}", format, message);
+            return (StatusCode::BAD_REQUEST, message).into_response();
+        }
+    };
+
+    let diary_service = DiaryService::new(&pool);
+
+    match diary_service.import_entries(parsed).await {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to import entries: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error importing entries: {:?}", e)).into_response()
+        }
+    }
+}
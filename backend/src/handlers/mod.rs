@@ -1,2 +1,16 @@
+pub mod attachment;
+pub mod backup;
+pub mod capture;
+pub mod comment;
 pub mod diary;
+pub mod draft;
+pub mod events;
+pub mod export;
+pub mod feed;
+pub mod import;
+pub mod prompt;
+pub mod reaction;
+pub mod reminder;
+pub mod stats;
 pub mod tag;
+pub mod webhook;
@@ -0,0 +1,99 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use sqlx::MySqlPool; // State抽出のために保持
+
+// サービスとそのエラー型をインポート
+use crate::services::prompt_service::{PromptService, PromptServiceError};
+use crate::models::prompt::{CreatePromptRequest, PromptQuery, UpdatePromptRequest};
+
+// お題の一覧を取得（`language` で絞り込み）
+pub async fn get_prompts(
+    State(pool): State<MySqlPool>,
+    Query(query): Query<PromptQuery>,
+) -> impl IntoResponse {
+    let prompt_service = PromptService::new(&pool);
+
+    match prompt_service.get_prompts(query.language.as_deref()).await {
+        Ok(prompts) => (StatusCode::OK, Json(prompts)).into_response(),
+        Err(PromptServiceError::InvalidRequest(message)) => (StatusCode::BAD_REQUEST, message).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to fetch prompts: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching prompts: {:?}", e)).into_response()
+        }
+    }
+}
+
+// お題を登録
+pub async fn create_prompt(
+    State(pool): State<MySqlPool>,
+    Json(request): Json<CreatePromptRequest>,
+) -> impl IntoResponse {
+    let prompt_service = PromptService::new(&pool);
+
+    match prompt_service.create_prompt(&request).await {
+        Ok(prompt) => (StatusCode::CREATED, Json(prompt)).into_response(),
+        Err(PromptServiceError::InvalidRequest(message)) => (StatusCode::BAD_REQUEST, message).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to create prompt: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error creating prompt: {:?}", e)).into_response()
+        }
+    }
+}
+
+// お題を更新（指定した項目だけを変更する）
+pub async fn update_prompt(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<i32>,
+    Json(request): Json<UpdatePromptRequest>,
+) -> impl IntoResponse {
+    let prompt_service = PromptService::new(&pool);
+
+    match prompt_service.update_prompt(id, &request).await {
+        Ok(prompt) => (StatusCode::OK, Json(prompt)).into_response(),
+        Err(PromptServiceError::NotFound) => (StatusCode::NOT_FOUND, "Prompt not found").into_response(),
+        Err(PromptServiceError::InvalidRequest(message)) => (StatusCode::BAD_REQUEST, message).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to update prompt: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error updating prompt: {:?}", e)).into_response()
+        }
+    }
+}
+
+// お題を削除
+pub async fn delete_prompt(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    let prompt_service = PromptService::new(&pool);
+
+    match prompt_service.delete_prompt(id).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(PromptServiceError::NotFound) => (StatusCode::NOT_FOUND, "Prompt not found").into_response(),
+        Err(e) => {
+            tracing::error!("Failed to delete prompt: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error deleting prompt: {:?}", e)).into_response()
+        }
+    }
+}
+
+// 今日のお題を取得（同じ日には同じお題を返す）
+pub async fn get_today_prompt(
+    State(pool): State<MySqlPool>,
+    Query(query): Query<PromptQuery>,
+) -> impl IntoResponse {
+    let prompt_service = PromptService::new(&pool);
+
+    match prompt_service.get_today_prompt(query.language.as_deref()).await {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(PromptServiceError::NotFound) => (StatusCode::NOT_FOUND, "No prompts available").into_response(),
+        Err(PromptServiceError::InvalidRequest(message)) => (StatusCode::BAD_REQUEST, message).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to fetch today's prompt: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching today's prompt: {:?}", e)).into_response()
+        }
+    }
+}
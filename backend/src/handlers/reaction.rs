@@ -0,0 +1,78 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use sqlx::MySqlPool; // State抽出のために保持
+
+// サービスとそのエラー型をインポート
+use crate::services::reaction_service::{ReactionService, ReactionServiceError};
+use crate::models::reaction::CreateReactionRequest;
+
+// reaction.emoji カラムの長さ
+const MAX_EMOJI_LENGTH: usize = 32;
+
+// エントリのリアクション一覧を取得
+pub async fn get_reactions(
+    State(pool): State<MySqlPool>,
+    Path(entry_id): Path<i32>,
+) -> impl IntoResponse {
+    let reaction_service = ReactionService::new(&pool);
+
+    match reaction_service.get_reactions(entry_id).await {
+        Ok(reactions) => (StatusCode::OK, Json(reactions)).into_response(),
+        Err(ReactionServiceError::NotFound) => {
+            (StatusCode::NOT_FOUND, "Entry not found").into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch reactions: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching reactions: {:?}", e)).into_response()
+        }
+    }
+}
+
+// エントリにリアクションを追加
+pub async fn create_reaction(
+    State(pool): State<MySqlPool>,
+    Path(entry_id): Path<i32>,
+    Json(request): Json<CreateReactionRequest>,
+) -> impl IntoResponse {
+    // ハンドラー層での基本的なバリデーション
+    let emoji = request.emoji.trim();
+    if emoji.is_empty() || emoji.chars().count() > MAX_EMOJI_LENGTH {
+        return (StatusCode::BAD_REQUEST, "Invalid emoji").into_response();
+    }
+
+    let reaction_service = ReactionService::new(&pool);
+
+    match reaction_service.create_reaction(entry_id, &request).await {
+        Ok(reaction) => (StatusCode::CREATED, Json(reaction)).into_response(),
+        Err(ReactionServiceError::NotFound) => {
+            (StatusCode::NOT_FOUND, "Entry not found").into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to create reaction: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error creating reaction: {:?}", e)).into_response()
+        }
+    }
+}
+
+// リアクションを削除
+pub async fn delete_reaction(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    let reaction_service = ReactionService::new(&pool);
+
+    match reaction_service.delete_reaction(id).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(ReactionServiceError::NotFound) => {
+            (StatusCode::NOT_FOUND, "Reaction not found").into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to delete reaction: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error deleting reaction: {:?}", e)).into_response()
+        }
+    }
+}
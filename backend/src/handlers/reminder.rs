@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use sqlx::MySqlPool; // State抽出のために保持
+
+// サービスとそのエラー型をインポート
+use crate::services::reminder_service::{ReminderService, ReminderServiceError};
+use crate::models::reminder::{CreateReminderRequest, UpdateReminderRequest};
+
+// リマインダーの一覧を取得
+pub async fn get_reminders(State(pool): State<MySqlPool>) -> impl IntoResponse {
+    let reminder_service = ReminderService::new(&pool);
+
+    match reminder_service.get_reminders().await {
+        Ok(reminders) => (StatusCode::OK, Json(reminders)).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to fetch reminders: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching reminders: {:?}", e)).into_response()
+        }
+    }
+}
+
+// リマインダーを登録
+pub async fn create_reminder(
+    State(pool): State<MySqlPool>,
+    Json(request): Json<CreateReminderRequest>,
+) -> impl IntoResponse {
+    let reminder_service = ReminderService::new(&pool);
+
+    match reminder_service.create_reminder(&request).await {
+        Ok(reminder) => (StatusCode::CREATED, Json(reminder)).into_response(),
+        Err(ReminderServiceError::InvalidRequest(message)) => (StatusCode::BAD_REQUEST, message).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to create reminder: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error creating reminder: {:?}", e)).into_response()
+        }
+    }
+}
+
+// リマインダーの設定を更新（指定した項目だけを変更する）
+pub async fn update_reminder(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<i32>,
+    Json(request): Json<UpdateReminderRequest>,
+) -> impl IntoResponse {
+    let reminder_service = ReminderService::new(&pool);
+
+    match reminder_service.update_reminder(id, &request).await {
+        Ok(reminder) => (StatusCode::OK, Json(reminder)).into_response(),
+        Err(ReminderServiceError::NotFound) => (StatusCode::NOT_FOUND, "Reminder not found").into_response(),
+        Err(ReminderServiceError::InvalidRequest(message)) => (StatusCode::BAD_REQUEST, message).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to update reminder: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error updating reminder: {:?}", e)).into_response()
+        }
+    }
+}
+
+// リマインダーを削除
+pub async fn delete_reminder(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    let reminder_service = ReminderService::new(&pool);
+
+    match reminder_service.delete_reminder(id).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(ReminderServiceError::NotFound) => (StatusCode::NOT_FOUND, "Reminder not found").into_response(),
+        Err(e) => {
+            tracing::error!("Failed to delete reminder: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error deleting reminder: {:?}", e)).into_response()
+        }
+    }
+}
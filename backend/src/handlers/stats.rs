@@ -0,0 +1,46 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use sqlx::MySqlPool; // State抽出のために保持
+
+// サービスとそのエラー型をインポート
+use crate::services::stats_service::{StatsService, StatsServiceError};
+use crate::models::stats::ActivityQuery;
+
+// 書いた記録の統計（連続記録・平均・よく使うタグ・曜日と時間帯）を取得
+pub async fn get_stats(State(pool): State<MySqlPool>) -> impl IntoResponse {
+    let stats_service = StatsService::new(&pool);
+
+    match stats_service.get_stats().await {
+        Ok(stats) => (StatusCode::OK, Json(stats)).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to fetch stats: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching stats: {:?}", e)).into_response()
+        }
+    }
+}
+
+// 日ごとのエントリ数と文字数を取得（ヒートマップ用）
+pub async fn get_activity(
+    State(pool): State<MySqlPool>,
+    Query(query): Query<ActivityQuery>,
+) -> impl IntoResponse {
+    let stats_service = StatsService::new(&pool);
+
+    match stats_service.get_activity(&query).await {
+        Ok(activity) => (StatusCode::OK, Json(activity)).into_response(),
+        Err(StatsServiceError::NotFound) => (StatusCode::NOT_FOUND, "Tag not found").into_response(),
+        Err(StatsServiceError::InvalidRange) => (
+            StatusCode::BAD_REQUEST,
+            "`from` must not be after `to`, and the range must be at most 366 days",
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to fetch activity: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching activity: {:?}", e)).into_response()
+        }
+    }
+}
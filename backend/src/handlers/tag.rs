@@ -13,8 +13,6 @@ use crate::services::diary_service::DiaryService; // get_entries_by_tagで使用
 // リクエスト/レスポンス関連のモデルと構造体を保持
  // get_entries_by_tagのレスポンスで使用
 use crate::models::tag::{CreateTagRequest, Tag}; // Tagはレスポンスで使用、CreateTagRequestはcreate_tagで使用
-// diaryハンドラーのレスポンス型をインポート (get_entries_by_tagで使用)
-use crate::handlers::diary::EntriesResponse;
 
 
 #[derive(Debug, Deserialize)]
@@ -92,17 +90,9 @@ pub async fn get_entries_by_tag(
     // DiaryServiceをインスタンス化
     let diary_service = DiaryService::new(&pool);
 
-    // サービス層のメソッドを呼び出し
-    match diary_service.get_entries_by_tag(tag_id, page, limit).await {
-        Ok((entries, total_pages)) => {
-            // レスポンス構造体を構築
-            let response = EntriesResponse { // handlers::diary::EntriesResponse を使用
-                entries,
-                total_pages,
-                current_page: page,
-            };
-            (StatusCode::OK, Json(response)).into_response()
-        }
+    // サービス層のメソッドを呼び出し（開封前のタイムカプセルは封じて返す）
+    match diary_service.get_entries_by_tag_with_details(tag_id, page, limit).await {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
         Err(e) => {
             tracing::error!("Failed to fetch entries by tag: {:?}", e);
             (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching entries by tag: {:?}", e)).into_response()
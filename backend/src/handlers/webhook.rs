@@ -0,0 +1,104 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use sqlx::MySqlPool; // State抽出のために保持
+
+// サービスとそのエラー型をインポート
+use crate::services::webhook_service::{WebhookService, WebhookServiceError};
+use crate::models::webhook::{CreateWebhookRequest, UpdateWebhookRequest};
+
+#[derive(Debug, Deserialize)]
+pub struct PaginationQuery {
+    page: Option<u32>,
+}
+
+// Webhookの一覧を取得
+pub async fn get_webhooks(State(pool): State<MySqlPool>) -> impl IntoResponse {
+    let webhook_service = WebhookService::new(&pool);
+
+    match webhook_service.get_webhooks().await {
+        Ok(webhooks) => (StatusCode::OK, Json(webhooks)).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to fetch webhooks: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching webhooks: {:?}", e)).into_response()
+        }
+    }
+}
+
+// Webhookを登録
+pub async fn create_webhook(
+    State(pool): State<MySqlPool>,
+    Json(request): Json<CreateWebhookRequest>,
+) -> impl IntoResponse {
+    let webhook_service = WebhookService::new(&pool);
+
+    match webhook_service.create_webhook(&request).await {
+        Ok(webhook) => (StatusCode::CREATED, Json(webhook)).into_response(),
+        Err(WebhookServiceError::InvalidRequest(message)) => (StatusCode::BAD_REQUEST, message).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to create webhook: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error creating webhook: {:?}", e)).into_response()
+        }
+    }
+}
+
+// Webhookの設定を更新（指定した項目だけを変更する）
+pub async fn update_webhook(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<i32>,
+    Json(request): Json<UpdateWebhookRequest>,
+) -> impl IntoResponse {
+    let webhook_service = WebhookService::new(&pool);
+
+    match webhook_service.update_webhook(id, &request).await {
+        Ok(webhook) => (StatusCode::OK, Json(webhook)).into_response(),
+        Err(WebhookServiceError::NotFound) => (StatusCode::NOT_FOUND, "Webhook not found").into_response(),
+        Err(WebhookServiceError::InvalidRequest(message)) => (StatusCode::BAD_REQUEST, message).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to update webhook: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error updating webhook: {:?}", e)).into_response()
+        }
+    }
+}
+
+// Webhookを削除
+pub async fn delete_webhook(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    let webhook_service = WebhookService::new(&pool);
+
+    match webhook_service.delete_webhook(id).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(WebhookServiceError::NotFound) => (StatusCode::NOT_FOUND, "Webhook not found").into_response(),
+        Err(e) => {
+            tracing::error!("Failed to delete webhook: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error deleting webhook: {:?}", e)).into_response()
+        }
+    }
+}
+
+// Webhookの配信ログを取得（新しい順）
+pub async fn get_deliveries(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<i32>,
+    Query(pagination): Query<PaginationQuery>,
+) -> impl IntoResponse {
+    let page = pagination.page.unwrap_or(1);
+    let limit = 20; // 1ページあたりの件数
+
+    let webhook_service = WebhookService::new(&pool);
+
+    match webhook_service.get_deliveries(id, page, limit).await {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(WebhookServiceError::NotFound) => (StatusCode::NOT_FOUND, "Webhook not found").into_response(),
+        Err(e) => {
+            tracing::error!("Failed to fetch webhook deliveries: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error fetching webhook deliveries: {:?}", e)).into_response()
+        }
+    }
+}
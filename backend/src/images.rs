@@ -0,0 +1,132 @@
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, ImageResult};
+
+/// 生成するサムネイルの長辺の長さ（px）
+pub const THUMBNAIL_SIZES: [u32; 3] = [320, 800, 1600];
+
+// 画像を保存し直すときのJPEGの品質
+const JPEG_QUALITY: u8 = 90;
+// サムネイルのJPEGの品質
+const THUMBNAIL_JPEG_QUALITY: u8 = 80;
+
+/// サムネイルの形式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThumbnailFormat {
+    WebP,
+    Jpeg,
+}
+
+impl ThumbnailFormat {
+    pub const ALL: [ThumbnailFormat; 2] = [ThumbnailFormat::WebP, ThumbnailFormat::Jpeg];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ThumbnailFormat::WebP => "webp",
+            ThumbnailFormat::Jpeg => "jpg",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ThumbnailFormat::WebP => "image/webp",
+            ThumbnailFormat::Jpeg => "image/jpeg",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.extension() == extension)
+    }
+}
+
+/// 向きを補正して読み込んだ画像
+pub struct DecodedImage {
+    pub image: DynamicImage,
+    pub format: ImageFormat,
+}
+
+impl DecodedImage {
+    pub fn content_type(&self) -> &'static str {
+        self.format.to_mime_type()
+    }
+}
+
+/// ブラウザで開かせる（`Content-Disposition: inline` で返す）添付ファイルの種類
+pub const INLINE_CONTENT_TYPES: [&str; 4] = ["image/jpeg", "image/png", "image/webp", "image/gif"];
+
+/// 添付ファイルの種類を内容から判定します。
+/// 判定できるのは `INLINE_CONTENT_TYPES` の画像だけで、それ以外は `application/octet-stream` とします。
+/// アップロード時に送られた種類はSVGなどを画像と偽れるため使いません。
+pub fn sniff_content_type(data: &[u8]) -> &'static str {
+    match image::guess_format(data) {
+        Ok(format @ (ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP | ImageFormat::Gif)) => {
+            format.to_mime_type()
+        }
+        _ => "application/octet-stream",
+    }
+}
+
+/// 処理の対象とする画像の形式を内容から判定します。
+/// GIF（アニメーションを壊さないため）やSVGなどは対象外です。
+pub fn detect_format(data: &[u8]) -> Option<ImageFormat> {
+    image::guess_format(data)
+        .ok()
+        .filter(|format| matches!(format, ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP))
+}
+
+/// 画像を読み込み、EXIFの向き（Orientation）を画素に反映します。
+pub fn decode(data: &[u8], format: ImageFormat) -> ImageResult<DecodedImage> {
+    let mut decoder = ImageReader::with_format(Cursor::new(data), format).into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+    Ok(DecodedImage { image, format })
+}
+
+/// 画像を元の形式で保存し直します。
+/// 画素だけを書き出すため、位置情報を含むEXIFなどのメタデータは取り除かれます。
+pub fn encode_without_metadata(decoded: &DecodedImage) -> ImageResult<Vec<u8>> {
+    let mut buffer = Vec::new();
+    match decoded.format {
+        ImageFormat::Jpeg => {
+            let encoder = JpegEncoder::new_with_quality(&mut buffer, JPEG_QUALITY);
+            DynamicImage::ImageRgb8(decoded.image.to_rgb8()).write_with_encoder(encoder)?;
+        }
+        ImageFormat::WebP => {
+            let encoder = WebPEncoder::new_lossless(&mut buffer);
+            DynamicImage::ImageRgba8(decoded.image.to_rgba8()).write_with_encoder(encoder)?;
+        }
+        _ => {
+            let encoder = PngEncoder::new(&mut buffer);
+            decoded.image.write_with_encoder(encoder)?;
+        }
+    }
+    Ok(buffer)
+}
+
+/// 長辺が `max_size` に収まるよう縮小したサムネイルを生成し、内容と幅・高さを返します。
+/// 元の画像の方が小さい場合は拡大しません。
+pub fn thumbnail(image: &DynamicImage, max_size: u32, format: ThumbnailFormat) -> ImageResult<(Vec<u8>, u32, u32)> {
+    let resized = if image.width() > max_size || image.height() > max_size {
+        image.thumbnail(max_size, max_size)
+    } else {
+        image.clone()
+    };
+
+    let mut buffer = Vec::new();
+    match format {
+        ThumbnailFormat::WebP => {
+            // image クレートのWebPエンコーダーは可逆圧縮のみに対応している
+            let encoder = WebPEncoder::new_lossless(&mut buffer);
+            DynamicImage::ImageRgba8(resized.to_rgba8()).write_with_encoder(encoder)?;
+        }
+        ThumbnailFormat::Jpeg => {
+            let encoder = JpegEncoder::new_with_quality(&mut buffer, THUMBNAIL_JPEG_QUALITY);
+            DynamicImage::ImageRgb8(resized.to_rgb8()).write_with_encoder(encoder)?;
+        }
+    }
+    Ok((buffer, resized.width(), resized.height()))
+}
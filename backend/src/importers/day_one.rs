@@ -0,0 +1,102 @@
+//! Day One のJSONエクスポート（`Journal.json`、またはそれを含むZIP）の読み込み
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+use crate::importers::{normalize_tags, title_from_content, ImportError, ImportFile, ImportedEntry, Importer, ParsedImport};
+use crate::models::import::ImportIssue;
+
+#[derive(Deserialize)]
+struct DayOneExport {
+    entries: Vec<DayOneEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DayOneEntry {
+    #[serde(default)]
+    text: String,
+    creation_date: Option<String>,
+    time_zone: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+pub struct DayOneImporter {
+    // エントリにタイムゾーンがない場合に使うタイムゾーン
+    pub fallback_timezone: FixedOffset,
+}
+
+impl Importer for DayOneImporter {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn parse(&self, files: &[ImportFile]) -> Result<ParsedImport, ImportError> {
+        let mut parsed = ParsedImport::default();
+
+        for file in files {
+            let export: DayOneExport = serde_json::from_slice(&file.data).map_err(|err| {
+                ImportError::InvalidFormat(format!("{}: not a Day One export: {}", file.name, err))
+            })?;
+
+            for entry in export.entries {
+                let content = strip_moments(&entry.text);
+                let title = title_from_content(&content);
+                let datetime = entry.creation_date.as_deref().and_then(|date| {
+                    parse_creation_date(date, entry.time_zone.as_deref(), self.fallback_timezone)
+                });
+
+                let Some(datetime) = datetime else {
+                    parsed.skipped.push(ImportIssue {
+                        title,
+                        datetime: None,
+                        reason: "missing or invalid creationDate".to_string(),
+                    });
+                    continue;
+                };
+                if content.is_empty() {
+                    parsed.skipped.push(ImportIssue {
+                        title,
+                        datetime: Some(datetime),
+                        reason: "empty entry".to_string(),
+                    });
+                    continue;
+                }
+
+                parsed.entries.push(ImportedEntry {
+                    title,
+                    content,
+                    datetime,
+                    tags: normalize_tags(&entry.tags),
+                });
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+// creationDate は通常UTC（`Z` 付き）だが、オフセットがない場合はエントリのタイムゾーンで解釈する
+fn parse_creation_date(value: &str, time_zone: Option<&str>, fallback: FixedOffset) -> Option<NaiveDateTime> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime.naive_utc());
+    }
+
+    let local = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S").ok()?;
+    match time_zone.and_then(|name| name.parse::<Tz>().ok()) {
+        Some(tz) => tz.from_local_datetime(&local).earliest().map(|datetime| datetime.naive_utc()),
+        None => fallback.from_local_datetime(&local).single().map(|datetime| datetime.naive_utc()),
+    }
+}
+
+// 写真などの添付（dayone-moment:// への参照）は取り込まないため、本文から取り除く
+fn strip_moments(text: &str) -> String {
+    text.lines()
+        .filter(|line| !line.trim_start().starts_with("![](dayone-moment:"))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
@@ -0,0 +1,82 @@
+//! Journey のJSONエクスポート（エントリごとのJSONファイル、またはそれらを含むZIP）の読み込み
+
+use chrono::DateTime;
+use serde::Deserialize;
+
+use crate::importers::{normalize_tags, title_from_content, ImportError, ImportFile, ImportedEntry, Importer, ParsedImport};
+use crate::models::import::ImportIssue;
+
+// 1ファイルに1エントリ、または複数エントリの配列
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JourneyFile {
+    Single(JourneyEntry),
+    Multiple(Vec<JourneyEntry>),
+}
+
+#[derive(Deserialize)]
+struct JourneyEntry {
+    #[serde(default)]
+    text: String,
+    // エポックからのミリ秒（UTC）
+    date_journal: Option<i64>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+pub struct JourneyImporter;
+
+impl Importer for JourneyImporter {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn parse(&self, files: &[ImportFile]) -> Result<ParsedImport, ImportError> {
+        let mut parsed = ParsedImport::default();
+
+        for file in files {
+            let journey_file: JourneyFile = serde_json::from_slice(&file.data).map_err(|err| {
+                ImportError::InvalidFormat(format!("{}: not a Journey export: {}", file.name, err))
+            })?;
+            let entries = match journey_file {
+                JourneyFile::Single(entry) => vec![entry],
+                JourneyFile::Multiple(entries) => entries,
+            };
+
+            for entry in entries {
+                let content = entry.text.trim().to_string();
+                let title = title_from_content(&content);
+                let datetime = entry
+                    .date_journal
+                    .and_then(DateTime::from_timestamp_millis)
+                    .map(|datetime| datetime.naive_utc());
+
+                let Some(datetime) = datetime else {
+                    parsed.skipped.push(ImportIssue {
+                        title,
+                        datetime: None,
+                        reason: "missing or invalid date_journal".to_string(),
+                    });
+                    continue;
+                };
+                if content.is_empty() {
+                    parsed.skipped.push(ImportIssue {
+                        title,
+                        datetime: Some(datetime),
+                        reason: "empty entry".to_string(),
+                    });
+                    continue;
+                }
+
+                parsed.entries.push(ImportedEntry {
+                    title,
+                    content,
+                    datetime,
+                    tags: normalize_tags(&entry.tags),
+                });
+            }
+        }
+
+        Ok(parsed)
+    }
+}
@@ -0,0 +1,201 @@
+//! フロントマター付きのMarkdownファイル（Obsidian や 手書きのMarkdown日記）の読み込み
+//!
+//! 日時はフロントマターの `date` / `datetime` / `created`、なければファイル名の先頭の
+//! `YYYY-MM-DD` から取得します。タグはフロントマターの `tags` と本文中の `#タグ` を使います。
+
+use std::path::Path;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone};
+use serde::Deserialize;
+use serde_yaml::Value;
+
+use crate::importers::{normalize_tags, title_from_content, ImportError, ImportFile, ImportedEntry, Importer, ParsedImport};
+use crate::models::import::ImportIssue;
+
+const FRONT_MATTER_DELIMITER: &str = "---";
+// 日付のみ・日時（秒あり/なし）の順に試す
+const DATETIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+];
+
+#[derive(Default, Deserialize)]
+struct FrontMatter {
+    title: Option<String>,
+    date: Option<Value>,
+    datetime: Option<Value>,
+    created: Option<Value>,
+    tags: Option<Value>,
+}
+
+pub struct MarkdownImporter {
+    // 日時にオフセットがない場合のタイムゾーン
+    pub timezone: FixedOffset,
+}
+
+impl Importer for MarkdownImporter {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["md", "markdown"]
+    }
+
+    fn parse(&self, files: &[ImportFile]) -> Result<ParsedImport, ImportError> {
+        let mut parsed = ParsedImport::default();
+
+        for file in files {
+            let text = String::from_utf8_lossy(&file.data).replace("\r\n", "\n");
+            let (front_matter, body) = split_front_matter(&text);
+            let front_matter: FrontMatter = match front_matter {
+                Some(yaml) => serde_yaml::from_str(yaml).unwrap_or_else(|err| {
+                    tracing::warn!("Ignoring invalid front matter in {}: {}", file.name, err);
+                    FrontMatter::default()
+                }),
+                None => FrontMatter::default(),
+            };
+
+            let content = body.trim().to_string();
+            let title = front_matter
+                .title
+                .clone()
+                .unwrap_or_else(|| title_from_content(&content));
+            let datetime = [&front_matter.datetime, &front_matter.date, &front_matter.created]
+                .into_iter()
+                .flatten()
+                .find_map(|value| parse_datetime(value, self.timezone))
+                .or_else(|| date_from_filename(&file.name, self.timezone));
+
+            let Some(datetime) = datetime else {
+                parsed.skipped.push(ImportIssue {
+                    title,
+                    datetime: None,
+                    reason: format!("{}: no date in front matter or file name", file.name),
+                });
+                continue;
+            };
+            if content.is_empty() {
+                parsed.skipped.push(ImportIssue {
+                    title,
+                    datetime: Some(datetime),
+                    reason: format!("{}: empty file", file.name),
+                });
+                continue;
+            }
+
+            let mut tags = front_matter.tags.as_ref().map(tags_from_value).unwrap_or_default();
+            tags.extend(inline_tags(&content));
+
+            parsed.entries.push(ImportedEntry {
+                title,
+                content,
+                datetime,
+                tags: normalize_tags(tags),
+            });
+        }
+
+        Ok(parsed)
+    }
+}
+
+// 先頭の `---` で囲まれた部分をフロントマターとして切り出す
+fn split_front_matter(text: &str) -> (Option<&str>, &str) {
+    let Some(rest) = text.strip_prefix(FRONT_MATTER_DELIMITER).and_then(|rest| rest.strip_prefix('\n')) else {
+        return (None, text);
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == FRONT_MATTER_DELIMITER {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    // 閉じる区切りがない場合はフロントマターとみなさない
+    (None, text)
+}
+
+fn parse_datetime(value: &Value, timezone: FixedOffset) -> Option<NaiveDateTime> {
+    let value = match value {
+        Value::String(value) => value.trim(),
+        _ => return None,
+    };
+
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime.naive_utc());
+    }
+    let local = DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+    to_utc(local, timezone)
+}
+
+// `2024-05-01.md` や `2024-05-01 散歩.md` のようなファイル名から日付を取得する
+fn date_from_filename(name: &str, timezone: FixedOffset) -> Option<NaiveDateTime> {
+    let stem = Path::new(name).file_stem()?.to_str()?;
+    let prefix = stem.get(..10)?;
+    let date = NaiveDate::parse_from_str(prefix, "%Y-%m-%d").ok()?;
+    to_utc(date.and_hms_opt(0, 0, 0)?, timezone)
+}
+
+fn to_utc(local: NaiveDateTime, timezone: FixedOffset) -> Option<NaiveDateTime> {
+    timezone
+        .from_local_datetime(&local)
+        .single()
+        .map(|datetime| datetime.naive_utc())
+}
+
+// `tags: [a, b]` と `tags: a, b` のどちらの書き方も受け付ける
+fn tags_from_value(value: &Value) -> Vec<String> {
+    match value {
+        Value::Sequence(items) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::String(tag) => Some(tag.trim_start_matches('#').to_string()),
+                Value::Number(number) => Some(number.to_string()),
+                _ => None,
+            })
+            .collect(),
+        Value::String(tags) => tags
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .map(|tag| tag.trim_start_matches('#').to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 本文中の `#タグ` を取り出します。
+/// 見出し（`# 見出し`）、コードブロック内、数字だけのもの（`#1` など）は対象外です。
+fn inline_tags(content: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut in_code_block = false;
+
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        for word in line.split_whitespace() {
+            let Some(tag) = word.strip_prefix('#') else {
+                continue;
+            };
+            let tag = tag.trim_end_matches(|c: char| {
+                c.is_ascii_punctuation() && c != '/' && c != '_' && c != '-' || "、。」』）".contains(c)
+            });
+            if tag.is_empty() || tag.starts_with('#') || tag.chars().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            tags.push(tag.to_string());
+        }
+    }
+
+    tags
+}
@@ -0,0 +1,233 @@
+pub mod day_one;
+pub mod journey;
+pub mod markdown;
+pub mod movable_type;
+
+use async_zip::base::read::mem::ZipFileReader;
+use chrono::NaiveDateTime;
+use futures::AsyncReadExt;
+
+use crate::config;
+use crate::models::import::ImportIssue;
+
+// tag.name カラムの長さ
+const MAX_TAG_CHARS: usize = 50;
+// ZIPファイルの先頭のシグネチャ
+const ZIP_SIGNATURE: &[u8] = b"PK\x03\x04";
+// ZIPに含められるエントリの数（ディレクトリや対象外のファイルも数える）
+const MAX_ZIP_ENTRIES: usize = 50_000;
+// ZIPから取り出すファイルの合計サイズ（展開後）
+const MAX_UNPACKED_SIZE: usize = 512 * 1024 * 1024;
+
+/// 外部形式から読み込んだエントリ
+#[derive(Debug)]
+pub struct ImportedEntry {
+    // 重複や失敗の報告に使う見出し
+    pub title: String,
+    pub content: String,
+    // UTCの日時
+    pub datetime: NaiveDateTime,
+    pub tags: Vec<String>,
+}
+
+/// 外部形式の解析結果
+#[derive(Debug, Default)]
+pub struct ParsedImport {
+    pub entries: Vec<ImportedEntry>,
+    pub skipped: Vec<ImportIssue>,
+}
+
+/// インポートするファイル（アップロードされたファイル、またはZIP内の各ファイル）
+pub struct ImportFile {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    // ファイルの内容が形式に合わない
+    InvalidFormat(String),
+}
+
+/// 外部形式のインポーター
+/// 新しい形式に対応する場合は、この trait を実装して `importer_for` に登録します。
+pub trait Importer: Send + Sync {
+    /// 読み込む対象のファイルの拡張子（ZIPやディレクトリから探すときに使う）
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// ファイルを解析してエントリに変換します。
+    fn parse(&self, files: &[ImportFile]) -> Result<ParsedImport, ImportError>;
+}
+
+/// 形式名（URLで指定する名前）に対応するインポーターを返します。
+pub fn importer_for(format: &str) -> Option<Box<dyn Importer>> {
+    match format {
+        "movable-type" => Some(Box::new(movable_type::MovableTypeImporter {
+            timezone: config::diary_timezone(),
+        })),
+        "day-one" => Some(Box::new(day_one::DayOneImporter {
+            fallback_timezone: config::diary_timezone(),
+        })),
+        "journey" => Some(Box::new(journey::JourneyImporter)),
+        "markdown" => Some(Box::new(markdown::MarkdownImporter {
+            timezone: config::diary_timezone(),
+        })),
+        _ => None,
+    }
+}
+
+/// アップロードされたデータをインポート対象のファイルに展開します。
+/// ZIPの場合は `extensions` に一致するファイルだけを取り出し、それ以外はそのまま1ファイルとして扱います。
+/// ZIPのエントリ数と展開後の合計サイズには上限があり、超える場合は `InvalidFormat` を返します。
+pub async fn unpack_upload(
+    name: &str,
+    data: Vec<u8>,
+    extensions: &[&str],
+) -> Result<Vec<ImportFile>, ImportError> {
+    if !data.starts_with(ZIP_SIGNATURE) {
+        return Ok(vec![ImportFile {
+            name: name.to_string(),
+            data,
+        }]);
+    }
+    unpack_zip(data, extensions, MAX_ZIP_ENTRIES, MAX_UNPACKED_SIZE).await
+}
+
+// ZIPから `extensions` に一致するファイルを取り出します（上限はテストで差し替えられるよう引数で受け取る）
+async fn unpack_zip(
+    data: Vec<u8>,
+    extensions: &[&str],
+    max_entries: usize,
+    max_unpacked_size: usize,
+) -> Result<Vec<ImportFile>, ImportError> {
+    let invalid_zip = |err: async_zip::error::ZipError| ImportError::InvalidFormat(format!("invalid zip archive: {}", err));
+    let too_large = || {
+        ImportError::InvalidFormat(format!(
+            "zip archive is too large when unpacked (limit: {} bytes)",
+            max_unpacked_size
+        ))
+    };
+    let reader = ZipFileReader::new(data).await.map_err(invalid_zip)?;
+    if reader.file().entries().len() > max_entries {
+        return Err(ImportError::InvalidFormat(format!(
+            "zip archive has too many entries (limit: {})",
+            max_entries
+        )));
+    }
+
+    let mut files = Vec::new();
+    let mut remaining = max_unpacked_size;
+    for (index, entry) in reader.file().entries().iter().enumerate() {
+        let Ok(filename) = entry.filename().as_str() else {
+            continue;
+        };
+        let matches_extension = extensions
+            .iter()
+            .any(|extension| filename.to_lowercase().ends_with(&format!(".{}", extension)));
+        if entry.dir().unwrap_or(false) || !matches_extension {
+            continue;
+        }
+        // 宣言されたサイズで先に判定し、宣言が偽られていても読み込む量は残りの上限までに抑える
+        if entry.uncompressed_size() > remaining as u64 {
+            return Err(too_large());
+        }
+
+        let filename = filename.to_string();
+        let mut data = Vec::new();
+        let mut entry_reader = reader.reader_with_entry(index).await.map_err(invalid_zip)?;
+        (&mut entry_reader)
+            .take(remaining as u64 + 1)
+            .read_to_end(&mut data)
+            .await
+            .map_err(|err| ImportError::InvalidFormat(format!("invalid zip archive: {}", err)))?;
+        if data.len() > remaining {
+            return Err(too_large());
+        }
+        if entry_reader.compute_hash() != entry.crc32() {
+            return Err(ImportError::InvalidFormat(format!(
+                "invalid zip archive: CRC mismatch in {}",
+                filename
+            )));
+        }
+        remaining -= data.len();
+        files.push(ImportFile { name: filename, data });
+    }
+
+    // ZIP内の順序に依存しないよう、ファイル名順に並べる
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(files)
+}
+
+/// タグ名を正規化します（前後の空白を除き、空のもの・重複したものを取り除き、長すぎるものは切り詰めます）。
+pub fn normalize_tags<I, S>(tags: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag: String = tag.as_ref().trim().chars().take(MAX_TAG_CHARS).collect();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// 本文の1行目から報告用の見出しを作ります。
+pub fn title_from_content(content: &str) -> String {
+    const MAX_TITLE_CHARS: usize = 40;
+
+    content
+        .lines()
+        .map(|line| line.trim().trim_start_matches('#').trim())
+        .find(|line| !line.is_empty())
+        .map(|line| line.chars().take(MAX_TITLE_CHARS).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::{Compression, ZipEntryBuilder};
+
+    use super::*;
+
+    async fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = ZipFileWriter::new(Vec::new());
+        for (name, data) in files {
+            let entry = ZipEntryBuilder::new((*name).into(), Compression::Deflate);
+            writer.write_entry_whole(entry, data).await.unwrap();
+        }
+        writer.close().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn unpacks_matching_files_in_name_order() {
+        let data = zip(&[("b.txt", b"second"), ("photo.jpg", b"skipped"), ("a.txt", b"first")]).await;
+
+        let files = unpack_upload("upload.zip", data, &["txt"]).await.unwrap();
+
+        let names: Vec<_> = files.iter().map(|file| file.name.as_str()).collect();
+        assert_eq!(names, ["a.txt", "b.txt"]);
+        assert_eq!(files[0].data, b"first");
+    }
+
+    #[tokio::test]
+    async fn rejects_archives_with_too_many_entries() {
+        let data = zip(&[("a.txt", b"a"), ("b.txt", b"b"), ("c.jpg", b"c")]).await;
+
+        assert!(unpack_zip(data, &["txt"], 2, 1024).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_archives_too_large_when_unpacked() {
+        // 圧縮すると小さくなるが、展開すると上限を超える
+        let large = vec![b'a'; 4096];
+        let data = zip(&[("a.txt", &large), ("b.txt", &large)]).await;
+
+        assert!(data.len() < 1024);
+        assert!(unpack_zip(data.clone(), &["txt"], 10, 8192).await.is_ok());
+        assert!(unpack_zip(data, &["txt"], 10, 8191).await.is_err());
+    }
+}
@@ -0,0 +1,260 @@
+//! Movable Type のエクスポート形式（はてなブログ・はてなダイアリーが出力する形式）の読み書き
+//!
+//! 記事は `--------` の行で、記事内のセクションは `-----` の行で区切られます。
+//! 先頭のセクションは `KEY: value` 形式のメタデータで、以降のセクションは
+//! `BODY:` や `EXTENDED BODY:` などの見出し行から始まります。
+//! 本文中の区切りと同じ行は、書き出すときに先頭に空白を1つ加え、読み込むときに取り除きます。
+
+use chrono::{FixedOffset, NaiveDateTime, TimeZone};
+
+use crate::importers::{normalize_tags, ImportError, ImportFile, ImportedEntry, Importer, ParsedImport};
+use crate::models::{entry::Entry, import::ImportIssue, tag::Tag};
+
+const RECORD_SEPARATOR: &str = "--------";
+const SECTION_SEPARATOR: &str = "-----";
+// 12時間表記（Movable Type 標準）と24時間表記の両方を受け付ける
+const DATE_FORMATS: [&str; 2] = ["%m/%d/%Y %I:%M:%S %p", "%m/%d/%Y %H:%M:%S"];
+const EXPORT_DATE_FORMAT: &str = "%m/%d/%Y %I:%M:%S %p";
+
+pub struct MovableTypeImporter {
+    // エクスポート元のブログのタイムゾーン
+    pub timezone: FixedOffset,
+}
+
+impl Importer for MovableTypeImporter {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["txt", "mt", "log"]
+    }
+
+    fn parse(&self, files: &[ImportFile]) -> Result<ParsedImport, ImportError> {
+        let mut parsed = ParsedImport::default();
+        for file in files {
+            let text = String::from_utf8_lossy(&file.data);
+            let file_parsed = parse(&text, self.timezone);
+            parsed.entries.extend(file_parsed.entries);
+            parsed.skipped.extend(file_parsed.skipped);
+        }
+        Ok(parsed)
+    }
+}
+
+/// Movable Type 形式のテキストを解析します。
+/// `DATE` はブログのローカル時刻なので、`timezone` でUTCに変換します。
+fn parse(text: &str, timezone: FixedOffset) -> ParsedImport {
+    let text = text.replace("\r\n", "\n");
+    let mut parsed = ParsedImport::default();
+
+    for record in split_by_line(&text, RECORD_SEPARATOR) {
+        if record.trim().is_empty() {
+            continue;
+        }
+
+        let mut sections = split_by_line(record, SECTION_SEPARATOR).into_iter();
+        let metadata = sections.next().unwrap_or_default();
+
+        let mut title = String::new();
+        let mut date = None;
+        let mut status = None;
+        let mut categories = Vec::new();
+        for line in metadata.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "TITLE" => title = value.to_string(),
+                "DATE" => date = Some(value.to_string()),
+                "STATUS" => status = Some(value.to_string()),
+                "CATEGORY" | "PRIMARY CATEGORY" => categories.push(value.to_string()),
+                _ => {}
+            }
+        }
+
+        let mut body = String::new();
+        let mut extended_body = String::new();
+        for section in sections {
+            let section = section.trim_start_matches('\n');
+            let (heading, content) = section.split_once('\n').unwrap_or((section, ""));
+            match heading.trim() {
+                "BODY:" => body = section_text(content),
+                "EXTENDED BODY:" => extended_body = section_text(content),
+                // コメントやトラックバックなどは取り込まない
+                _ => {}
+            }
+        }
+
+        let datetime = date.as_deref().and_then(|date| parse_date(date, timezone));
+        let skip = |reason: &str| ImportIssue {
+            title: title.clone(),
+            datetime,
+            reason: reason.to_string(),
+        };
+
+        if status.as_deref().is_some_and(|status| status.eq_ignore_ascii_case("draft")) {
+            parsed.skipped.push(skip("draft posts are not imported"));
+            continue;
+        }
+        let Some(datetime) = datetime else {
+            parsed.skipped.push(skip("missing or invalid DATE"));
+            continue;
+        };
+        if title.is_empty() && body.is_empty() && extended_body.is_empty() {
+            parsed.skipped.push(skip("empty post"));
+            continue;
+        }
+
+        parsed.entries.push(ImportedEntry {
+            content: compose_content(&title, &body, &extended_body),
+            title,
+            datetime,
+            tags: normalize_tags(categories),
+        });
+    }
+
+    parsed
+}
+
+/// エントリを Movable Type 形式の1記事分のテキストに変換します。
+/// 本文の1行目が `# ` で始まる場合はタイトルとして扱います（インポート時の逆変換）。
+pub fn render_entry(entry: &Entry, tags: &[Tag], timezone: FixedOffset) -> String {
+    let (title, body) = split_title(&entry.content);
+    let date = timezone.from_utc_datetime(&entry.datetime);
+
+    let mut record = String::new();
+    record.push_str(&format!("TITLE: {}\n", title));
+    record.push_str(&format!("BASENAME: {}\n", entry.id));
+    record.push_str("STATUS: Publish\n");
+    record.push_str("CONVERT BREAKS: 0\n");
+    record.push_str(&format!("DATE: {}\n", date.format(EXPORT_DATE_FORMAT)));
+    for tag in tags {
+        record.push_str(&format!("CATEGORY: {}\n", tag.name));
+    }
+    record.push_str(SECTION_SEPARATOR);
+    record.push_str("\nBODY:\n");
+    record.push_str(&escape_separators(body.trim_end()));
+    record.push('\n');
+    record.push_str(SECTION_SEPARATOR);
+    record.push('\n');
+    record.push_str(RECORD_SEPARATOR);
+    record.push('\n');
+    record
+}
+
+// 区切りと同じ行（と、すでにエスケープした形の行）の先頭に空白を1つ加え、本文の途中で区切られないようにする
+fn escape_separators(body: &str) -> String {
+    map_lines(body, |line| is_escaped_separator(line).then(|| format!(" {}", line)))
+}
+
+// `escape_separators` の逆変換
+fn unescape_separators(body: &str) -> String {
+    map_lines(body, |line| {
+        let unescaped = line.strip_prefix(' ')?;
+        is_escaped_separator(unescaped).then(|| unescaped.to_string())
+    })
+}
+
+// 空白を除くと区切りになる行（先頭の空白は0個以上）
+fn is_escaped_separator(line: &str) -> bool {
+    let separator = line.trim_start_matches(' ');
+    separator == RECORD_SEPARATOR || separator == SECTION_SEPARATOR
+}
+
+// `convert` が返した行だけを置き換える
+fn map_lines(text: &str, convert: impl Fn(&str) -> Option<String>) -> String {
+    text.split('\n')
+        .map(|line| convert(line).unwrap_or_else(|| line.to_string()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// セクションの見出しに続く本文（前後の空行を除き、区切りのエスケープを戻す）
+fn section_text(content: &str) -> String {
+    unescape_separators(content.trim_start_matches('\n').trim_end())
+}
+
+// タイトルは見出しとして本文の先頭に置く
+fn compose_content(title: &str, body: &str, extended_body: &str) -> String {
+    let mut content = String::new();
+    if !title.is_empty() {
+        content.push_str(&format!("# {}\n\n", title));
+    }
+    content.push_str(body);
+    if !extended_body.is_empty() {
+        content.push_str("\n\n");
+        content.push_str(extended_body);
+    }
+    content.trim_end().to_string()
+}
+
+fn split_title(content: &str) -> (&str, &str) {
+    let (first_line, rest) = content.split_once('\n').unwrap_or((content, ""));
+    match first_line.strip_prefix("# ") {
+        Some(title) => (title.trim(), rest.trim_start_matches('\n')),
+        None => ("", content),
+    }
+}
+
+fn parse_date(value: &str, timezone: FixedOffset) -> Option<NaiveDateTime> {
+    let local = DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())?;
+    timezone
+        .from_local_datetime(&local)
+        .single()
+        .map(|datetime| datetime.naive_utc())
+}
+
+// 区切り文字だけの行でテキストを分割する
+fn split_by_line<'t>(text: &'t str, separator: &str) -> Vec<&'t str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if line.trim_end_matches('\n') == separator {
+            parts.push(&text[start..offset]);
+            start = offset + line.len();
+        }
+        offset += line.len();
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::models::entry::EntryStatus;
+
+    fn entry(content: &str) -> Entry {
+        Entry {
+            id: 1,
+            content: content.to_string(),
+            datetime: NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_hms_opt(12, 0, 0).unwrap(),
+            unlock_at: None,
+            status: EntryStatus::Published,
+            draft_key: None,
+            draft_tags: None,
+            publish_at: None,
+        }
+    }
+
+    #[test]
+    fn round_trips_bodies_containing_separator_lines() {
+        let timezone = FixedOffset::east_opt(9 * 3600).unwrap();
+        let content = "# 区切り\n\n前半\n-----\n中盤\n--------\n後半\n -----\n  --------\n---- 見出しではない";
+        let text = format!(
+            "{}{}",
+            render_entry(&entry(content), &[], timezone),
+            render_entry(&entry("次の記事"), &[], timezone)
+        );
+
+        let parsed = parse(&text, timezone);
+
+        assert!(parsed.skipped.is_empty());
+        let contents: Vec<_> = parsed.entries.iter().map(|entry| entry.content.as_str()).collect();
+        assert_eq!(contents, [content, "次の記事"]);
+        assert_eq!(parsed.entries[0].datetime, entry(content).datetime);
+    }
+}
@@ -1,11 +1,24 @@
+mod cli;
+mod config;
 mod db;
+mod events;
 mod handlers;
+mod images;
+mod importers;
+mod markdown;
 mod models;
+mod notifiers;
 mod repositories; // 追加
 mod routes;
 mod services; // 追加
+#[cfg(feature = "smtp")]
+mod smtp;
+mod state;
+mod storage;
 
 use axum::http::{HeaderName, HeaderValue, Method};
+use clap::Parser;
+use sqlx::MySqlPool;
 use std::net::SocketAddr;
 use tower_http::cors::CorsLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -15,6 +28,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 環境変数の読み込み
     dotenv::dotenv().ok();
 
+    // コマンドライン引数の解析
+    let cli = cli::Cli::parse();
+
     // ロギングの初期化
     tracing_subscriber::registry()
         .with(tracing_subscriber::EnvFilter::new(
@@ -38,6 +54,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // データベース接続プールの作成
     let pool = db::connection::create_pool(&database_url).await?;
 
+    match cli.command {
+        None | Some(cli::Command::Serve) => serve(pool).await,
+        Some(cli::Command::Backup(command)) => cli::run_backup(&pool, command).await,
+        Some(cli::Command::Import { format, path }) => cli::run_import(&pool, &format, &path).await,
+        Some(cli::Command::Site { out_dir, base_url }) => cli::run_site(&pool, &out_dir, &base_url).await,
+    }
+}
+
+// APIサーバーを起動します
+async fn serve(pool: MySqlPool) -> Result<(), Box<dyn std::error::Error>> {
     let origins: Vec<HeaderValue> = std::env::var("ALLOWED_ORIGINS")
         .unwrap_or_default()
         .split(",")
@@ -47,11 +73,46 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // CORSの設定
     let cors = CorsLayer::new()
         .allow_origin(origins)
-        .allow_methods([Method::GET, Method::POST])
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
         .allow_headers([HeaderName::from_static("content-type")]);
 
+    // 添付ファイルの保存先
+    let storage = storage::from_env().map_err(|e| format!("Failed to configure attachment storage: {:?}", e))?;
+
+    // お題が1件もなければ初期のお題を登録する（スキーマの適用ではデータを登録しないため）
+    match services::prompt_service::PromptService::new(&pool).seed_default_prompts().await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("registered {} default prompts", count),
+        Err(e) => tracing::error!("Failed to register default prompts: {:?}", e),
+    }
+
+    // 前回の起動中に生成し終わらなかったサムネイルを生成し直すタスク
+    services::attachment_service::spawn_thumbnail_recovery(pool.clone(), storage.clone());
+
+    // Webhookの配信キューを送信するタスク
+    services::webhook_service::spawn_worker(pool.clone());
+
+    // タイムカプセルの開封を通知するタスク
+    services::diary_service::spawn_unlock_worker(pool.clone());
+
+    // 予約投稿の公開を通知するタスク
+    services::diary_service::spawn_publish_worker(pool.clone());
+
+    // 日記を書いていない日に通知するリマインダーのタスク
+    services::reminder_service::spawn_scheduler(pool.clone());
+
+    // メールで投稿するためのSMTPサーバー
+    #[cfg(feature = "smtp")]
+    {
+        let smtp_addr = std::env::var("SMTP_ADDR").unwrap_or_else(|_| "0.0.0.0:2525".to_string());
+        let hostname = std::env::var("SMTP_HOSTNAME").unwrap_or_else(|_| "localhost".to_string());
+        let listener = tokio::net::TcpListener::bind(&smtp_addr).await?;
+        tracing::info!("SMTP server listening on {}", smtp_addr);
+        tokio::spawn(smtp::serve(listener, pool.clone(), storage.clone(), hostname));
+    }
+
     // ルーターの作成
-    let app = routes::create_router(pool).layer(cors);
+    let app = routes::create_router(state::AppState { pool, storage }).layer(cors);
 
     // サーバーアドレスの設定
     let addr = SocketAddr::from(([0, 0, 0, 0], 9001));
@@ -0,0 +1,86 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
+
+use ammonia::Builder;
+use pulldown_cmark::{html, Event, Options, Parser};
+use sha2::{Digest, Sha256};
+
+// キャッシュするエントリ数の上限
+const CACHE_CAPACITY: usize = 4096;
+
+// エントリIDごとに、最後に描画した本文のハッシュとHTMLを持つ
+static CACHE: LazyLock<Mutex<HashMap<i32, CachedHtml>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// 描画結果はユーザーの入力を含むため、許可したタグと属性だけを残す
+static SANITIZER: LazyLock<Builder<'static>> = LazyLock::new(|| {
+    let mut builder = Builder::default();
+    // タスクリストのチェックボックス（表示のみ）
+    builder
+        .add_tags(["input"])
+        .add_tag_attribute_values("input", "type", ["checkbox"])
+        .add_tag_attributes("input", ["checked"])
+        .set_tag_attribute_value("input", "disabled", "")
+        // コードブロックの言語（`language-rust` など）
+        .add_tag_attributes("code", ["class"])
+        .attribute_filter(|element, attribute, value| match (element, attribute) {
+            ("code", "class") => value
+                .split_whitespace()
+                .find(|class| class.starts_with("language-"))
+                .map(|class| class.to_string().into()),
+            _ => Some(value.into()),
+        })
+        .add_tag_attributes("th", ["style"])
+        .add_tag_attributes("td", ["style"])
+        .filter_style_properties(HashSet::from(["text-align"]));
+    builder
+});
+
+struct CachedHtml {
+    content_hash: [u8; 32],
+    html: String,
+}
+
+/// 本文をMarkdown（CommonMark と GFM の表・タスクリスト・打ち消し線）としてHTMLに変換し、サニタイズします。
+/// 日記は1行ずつ書かれることが多いため、段落内の改行はそのまま改行（`<br>`）として扱います。
+pub fn render(content: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS | Options::ENABLE_STRIKETHROUGH;
+    let parser = Parser::new_ext(content, options).map(|event| match event {
+        Event::SoftBreak => Event::HardBreak,
+        event => event,
+    });
+
+    let mut unsafe_html = String::with_capacity(content.len() * 3 / 2);
+    html::push_html(&mut unsafe_html, parser);
+    SANITIZER.clean(&unsafe_html).to_string()
+}
+
+/// エントリの本文をHTMLに変換します。
+/// 結果はエントリの版（本文のハッシュ）ごとにキャッシュし、本文が変わった場合だけ描画し直します。
+pub fn render_entry(entry_id: i32, content: &str) -> String {
+    let content_hash: [u8; 32] = Sha256::digest(content.as_bytes()).into();
+
+    if let Some(cached) = CACHE.lock().unwrap().get(&entry_id)
+        && cached.content_hash == content_hash
+    {
+        return cached.html.clone();
+    }
+
+    // 描画中はロックを持たない
+    let html = render(content);
+
+    let mut cache = CACHE.lock().unwrap();
+    if cache.len() >= CACHE_CAPACITY && !cache.contains_key(&entry_id) {
+        // 上限に達したら任意のエントリを1件捨てる
+        if let Some(&evicted) = cache.keys().next() {
+            cache.remove(&evicted);
+        }
+    }
+    cache.insert(
+        entry_id,
+        CachedHtml {
+            content_hash,
+            html: html.clone(),
+        },
+    );
+    html
+}
@@ -0,0 +1,105 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::FromRow;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct Attachment {
+    pub id: i32,
+    pub entry_id: i32,
+    // 保存先でのキー（APIでは返さない）
+    pub storage_key: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size: i64,
+    pub datetime: NaiveDateTime,
+    // 画像の場合の幅と高さ（向きの補正後）
+    #[serde(default)]
+    pub width: Option<i32>,
+    #[serde(default)]
+    pub height: Option<i32>,
+    // 生成済みのサムネイル（バックグラウンドで生成されるため、アップロード直後は空）
+    #[serde(default)]
+    pub thumbnails: Json<Vec<Thumbnail>>,
+}
+
+/// 新しく登録する添付ファイルの情報
+pub struct NewAttachment<'a> {
+    pub entry_id: i32,
+    pub storage_key: &'a str,
+    pub filename: &'a str,
+    pub content_type: &'a str,
+    pub size: i64,
+    pub datetime: NaiveDateTime,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+}
+
+/// 画像の添付ファイルのサムネイル
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Thumbnail {
+    // 長辺の上限（px）
+    pub max_size: u32,
+    // 拡張子（webp / jpg）
+    pub format: String,
+    pub width: u32,
+    pub height: u32,
+    pub size: i64,
+}
+
+/// APIで返す添付ファイルの情報
+#[derive(Debug, Serialize)]
+pub struct AttachmentResponse {
+    pub id: i32,
+    pub entry_id: i32,
+    pub filename: String,
+    pub content_type: String,
+    pub size: i64,
+    pub datetime: NaiveDateTime,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    // ダウンロード用のパス
+    pub url: String,
+    pub thumbnails: Vec<ThumbnailResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ThumbnailResponse {
+    pub max_size: u32,
+    pub format: String,
+    pub width: u32,
+    pub height: u32,
+    pub url: String,
+}
+
+impl From<Attachment> for AttachmentResponse {
+    fn from(attachment: Attachment) -> Self {
+        let thumbnails = attachment
+            .thumbnails
+            .0
+            .into_iter()
+            .map(|thumbnail| ThumbnailResponse {
+                url: format!(
+                    "/api/attachments/{}/thumbnails/{}.{}",
+                    attachment.id, thumbnail.max_size, thumbnail.format
+                ),
+                max_size: thumbnail.max_size,
+                format: thumbnail.format,
+                width: thumbnail.width,
+                height: thumbnail.height,
+            })
+            .collect();
+        Self {
+            url: format!("/api/attachments/{}", attachment.id),
+            id: attachment.id,
+            entry_id: attachment.entry_id,
+            filename: attachment.filename,
+            content_type: attachment.content_type,
+            size: attachment.size,
+            datetime: attachment.datetime,
+            width: attachment.width,
+            height: attachment.height,
+            thumbnails,
+        }
+    }
+}
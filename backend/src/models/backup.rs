@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    attachment::Attachment,
+    capture::CaptureTokenRow,
+    comment::Comment,
+    entry::{EntryRow, EntryView},
+    prompt::{DailyPrompt, EntryPrompt, Prompt},
+    reaction::Reaction,
+    reminder::Reminder,
+    tag::{EntryTag, Tag},
+    webhook::Webhook,
+};
+
+/// 現在のバックアップ形式のバージョン
+/// テーブルを追加した場合は、古いバックアップも読めるよう `#[serde(default)]` を付けて追加します。
+/// 2: お題・Webhook・リマインダー・キャプチャのトークン・ランダム表示の記録を追加
+pub const BACKUP_VERSION: u32 = 2;
+
+/// 日記全体のバックアップ（JSON形式）
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Backup {
+    pub version: u32,
+    pub exported_at: NaiveDateTime,
+    pub entries: Vec<EntryRow>,
+    pub tags: Vec<Tag>,
+    pub entry_tags: Vec<EntryTag>,
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+    #[serde(default)]
+    pub reactions: Vec<Reaction>,
+    // ファイル本体は含まない（保存先を別途バックアップする）
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    #[serde(default)]
+    pub prompts: Vec<Prompt>,
+    #[serde(default)]
+    pub daily_prompts: Vec<DailyPrompt>,
+    #[serde(default)]
+    pub entry_prompts: Vec<EntryPrompt>,
+    #[serde(default)]
+    pub entry_views: Vec<EntryView>,
+    // 署名の鍵を含む
+    #[serde(default)]
+    pub webhooks: Vec<Webhook>,
+    #[serde(default)]
+    pub reminders: Vec<Reminder>,
+    // トークン本体は含まない（ハッシュだけ）
+    #[serde(default)]
+    pub capture_tokens: Vec<CaptureTokenRow>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RestoreQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// リストアの結果（ドライランの場合は実行した場合の予定）
+#[derive(Debug, Default, Serialize)]
+pub struct RestoreReport {
+    pub dry_run: bool,
+    pub tables: BTreeMap<&'static str, TableReport>,
+    pub conflicts: Vec<RestoreConflict>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct TableReport {
+    // 追加された（ドライランでは追加される）行数
+    pub inserted: usize,
+    // 同じ内容の行がすでに存在したためスキップした行数
+    pub unchanged: usize,
+    // 既存データと衝突したためスキップした行数
+    pub conflicted: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestoreConflict {
+    pub table: &'static str,
+    pub key: String,
+    pub reason: String,
+}
+
+impl RestoreReport {
+    pub fn conflict(&mut self, table: &'static str, key: String, reason: impl Into<String>) {
+        self.tables.entry(table).or_default().conflicted += 1;
+        self.conflicts.push(RestoreConflict {
+            table,
+            key,
+            reason: reason.into(),
+        });
+    }
+
+    pub fn table(&mut self, table: &'static str) -> &mut TableReport {
+        self.tables.entry(table).or_default()
+    }
+}
@@ -0,0 +1,28 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+/// 本文の組み方向
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WritingMode {
+    /// 横書き（左から右へページをめくる）
+    #[default]
+    Horizontal,
+    /// 縦書き（右から左へページをめくる）
+    Vertical,
+}
+
+/// 書籍形式のエクスポートの条件
+#[derive(Debug, Deserialize)]
+pub struct BookQuery {
+    /// 対象期間の初日（日記のタイムゾーンの日付）
+    pub from: NaiveDate,
+    /// 対象期間の最終日（この日を含む）
+    pub to: NaiveDate,
+    /// 指定した場合は、このタグが付いたエントリだけを収録する
+    pub tag: Option<i32>,
+    /// 書名（省略時は日記のタイトルと期間から決める）
+    pub title: Option<String>,
+    #[serde(default)]
+    pub writing_mode: WritingMode,
+}
@@ -0,0 +1,66 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct CaptureToken {
+    pub id: i32,
+    pub name: String,
+    pub datetime: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+}
+
+/// トークンのハッシュを含む行（バックアップで使う）
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct CaptureTokenRow {
+    pub id: i32,
+    pub name: String,
+    pub token_hash: String,
+    pub datetime: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCaptureTokenRequest {
+    pub name: String,
+}
+
+// トークン本体は発行時のレスポンスにだけ含める
+#[derive(Debug, Serialize)]
+pub struct CreatedCaptureToken {
+    pub id: i32,
+    pub name: String,
+    pub token: String,
+    pub datetime: NaiveDateTime,
+}
+
+// JSONまたはフォームで送られた投稿
+#[derive(Debug, Deserialize)]
+pub struct CaptureRequest {
+    #[serde(alias = "text", alias = "body")]
+    pub content: String,
+    #[serde(default)]
+    pub tags: Option<CaptureTags>,
+}
+
+// タグはJSONでは配列、フォームではカンマ区切りの文字列で受け付ける
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum CaptureTags {
+    List(Vec<String>),
+    Text(String),
+}
+
+impl CaptureTags {
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            CaptureTags::List(tags) => tags,
+            CaptureTags::Text(text) => text.split(',').map(|tag| tag.trim().to_string()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CaptureResponse {
+    pub id: i32,
+}
@@ -0,0 +1,23 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct Comment {
+    pub id: i32,
+    pub entry_id: i32,
+    pub author: String,
+    pub content: String,
+    pub datetime: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCommentRequest {
+    pub author: String,
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateCommentRequest {
+    pub content: String,
+}
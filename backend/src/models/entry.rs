@@ -1,15 +1,118 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 use sqlx::FromRow;
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+use crate::config;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRow)]
 pub struct Entry {
     pub id: i32,
     pub content: String,
     pub datetime: NaiveDateTime,
+    // タイムカプセルの開封日時（UTC）
+    #[serde(default)]
+    pub unlock_at: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub status: EntryStatus,
+    // 自動保存で下書きを識別するキー
+    #[serde(default)]
+    pub draft_key: Option<String>,
+    // 下書きに付けるタグの名前（公開するまでタグは作成しない）
+    #[serde(default)]
+    pub draft_tags: Option<Json<Vec<String>>>,
+    // 予約投稿の公開日時（UTC）
+    #[serde(default)]
+    pub publish_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum EntryStatus {
+    // 自動保存中の下書き（一覧や集計には含めない）
+    Draft,
+    #[default]
+    Published,
+}
+
+impl Entry {
+    /// 開封日時より前のタイムカプセルかどうかを返します。
+    pub fn is_locked(&self, now: NaiveDateTime) -> bool {
+        self.unlock_at.is_some_and(|unlock_at| unlock_at > now)
+    }
+
+    /// 開封前のタイムカプセルの本文を、開封日時を知らせる文に置き換えます。
+    /// 戻り値は置き換えたかどうかです。
+    pub fn seal(&mut self, now: NaiveDateTime) -> bool {
+        let Some(unlock_at) = self.unlock_at.filter(|_| self.is_locked(now)) else {
+            return false;
+        };
+        let local = unlock_at.and_utc().with_timezone(&config::diary_timezone());
+        self.content = format!("🔒 このタイムカプセルは{}に開封されます。", local.format("%Y年%-m月%-d日 %H:%M"));
+        true
+    }
+}
+
+/// 通知の記録を含むエントリの行（バックアップで使う）
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct EntryRow {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub entry: Entry,
+    // タイムカプセルの開封・予約投稿の公開を通知したかどうか（古いバックアップにない場合は日時から決める）
+    #[serde(default)]
+    pub unlock_notified: Option<bool>,
+    #[serde(default)]
+    pub publish_notified: Option<bool>,
+}
+
+/// ランダム表示でエントリを最後に表示した日時（バックアップで使う）
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct EntryView {
+    pub entry_id: i32,
+    pub viewed_at: NaiveDateTime,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CreateEntryRequest {
     pub content: String,
 }
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RandomMode {
+    // 条件に合うエントリから等しい確率で選ぶ
+    #[default]
+    Random,
+    // 最近ランダム表示していないエントリを優先して選ぶ
+    Rediscover,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RandomEntryQuery {
+    pub tag: Option<i32>,
+    // 期間（日記のタイムゾーンの日付、両端を含む）
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    #[serde(default)]
+    pub mode: RandomMode,
+}
+
+// 下書きの自動保存のリクエスト
+#[derive(Debug, Deserialize)]
+pub struct SaveDraftRequest {
+    pub content: String,
+    #[serde(default)]
+    pub tags: Vec<String>, // タグ名のリスト
+}
+
+#[derive(Debug, Serialize)]
+pub struct Draft {
+    pub key: String,
+    pub content: String,
+    // 公開するときに付けるタグの名前
+    pub tags: Vec<String>,
+    // 最後に保存した日時（UTC）
+    pub updated_at: NaiveDateTime,
+}
@@ -0,0 +1,19 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+/// インポートの結果
+#[derive(Debug, Default, Serialize)]
+pub struct ImportReport {
+    pub imported: usize,
+    // 既存のエントリ（またはインポート元の先行する記事）と重複したためスキップした記事
+    pub duplicates: Vec<ImportIssue>,
+    // 日時がない、本文が空などの理由で取り込めなかった記事
+    pub skipped: Vec<ImportIssue>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportIssue {
+    pub title: String,
+    pub datetime: Option<NaiveDateTime>,
+    pub reason: String,
+}
@@ -1,2 +1,13 @@
+pub mod attachment;
+pub mod backup;
+pub mod book;
+pub mod capture;
+pub mod comment;
 pub mod entry;
+pub mod import;
+pub mod prompt;
+pub mod reaction;
+pub mod reminder;
+pub mod stats;
 pub mod tag;
+pub mod webhook;
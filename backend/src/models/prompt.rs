@@ -0,0 +1,56 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct Prompt {
+    pub id: i32,
+    pub text: String,
+    // `ja` または `en`
+    pub language: String,
+    pub enabled: bool,
+    pub datetime: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePromptRequest {
+    pub text: String,
+    pub language: String,
+}
+
+// 指定した項目だけを更新する
+#[derive(Debug, Deserialize)]
+pub struct UpdatePromptRequest {
+    pub text: Option<String>,
+    pub language: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+// お題の一覧・今日のお題の言語での絞り込み
+#[derive(Debug, Deserialize)]
+pub struct PromptQuery {
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TodayPromptResponse {
+    // 日記のタイムゾーンでの今日の日付
+    pub date: NaiveDate,
+    pub prompt: Prompt,
+}
+
+/// 日ごとに選んだお題（バックアップで使う）
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct DailyPrompt {
+    pub date: NaiveDate,
+    // 言語を指定せずに選んだ場合は空文字列
+    pub language: String,
+    pub prompt_id: i32,
+}
+
+/// エントリが答えたお題（バックアップで使う）
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct EntryPrompt {
+    pub entry_id: i32,
+    pub prompt_id: i32,
+}
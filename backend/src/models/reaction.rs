@@ -0,0 +1,23 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct Reaction {
+    pub id: i32,
+    pub entry_id: i32,
+    pub emoji: String,
+    pub datetime: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReactionRequest {
+    pub emoji: String,
+}
+
+// エントリ一覧で返す絵文字ごとのリアクション数
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReactionCount {
+    pub emoji: String,
+    pub count: i64,
+}
@@ -0,0 +1,50 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// リマインダーの通知方法
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum ReminderChannel {
+    // 登録済みのWebhookに `reminder.due` を送る
+    Webhook,
+    // メールを送る
+    Email,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct Reminder {
+    pub id: i32,
+    pub name: String,
+    // 日記のタイムゾーンでの時刻
+    pub remind_at: NaiveTime,
+    pub channel: ReminderChannel,
+    pub webhook_id: Option<i32>,
+    pub email: Option<String>,
+    pub enabled: bool,
+    pub last_checked_on: Option<NaiveDate>,
+    pub last_sent_at: Option<NaiveDateTime>,
+    pub last_error: Option<String>,
+    pub datetime: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReminderRequest {
+    pub name: String,
+    pub remind_at: NaiveTime,
+    pub channel: ReminderChannel,
+    pub webhook_id: Option<i32>,
+    pub email: Option<String>,
+}
+
+// 指定した項目だけを更新する
+#[derive(Debug, Deserialize)]
+pub struct UpdateReminderRequest {
+    pub name: Option<String>,
+    pub remind_at: Option<NaiveTime>,
+    pub channel: Option<ReminderChannel>,
+    pub webhook_id: Option<i32>,
+    pub email: Option<String>,
+    pub enabled: Option<bool>,
+}
@@ -0,0 +1,80 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// 書き続けている記録（連続日数）
+#[derive(Debug, Default, Serialize)]
+pub struct Streak {
+    pub days: i64,
+    // 連続が始まった日と最後に書いた日（記録がない場合は null）
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagUsage {
+    pub id: i32,
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct WeekdayCount {
+    // 0 が月曜日
+    pub weekday: u32,
+    pub name: &'static str,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct HourCount {
+    pub hour: u32,
+    pub count: i64,
+}
+
+/// 日・週・月ごとのエントリ数
+#[derive(Debug, Serialize)]
+pub struct PeriodCount {
+    // `2024-05-01`（日）、`2024-W18`（ISO週）、`2024-05`（月）
+    pub period: String,
+    pub count: i64,
+}
+
+/// 書いた記録の統計（日付・曜日・時刻は日記のタイムゾーン）
+#[derive(Debug, Serialize)]
+pub struct StatsResponse {
+    pub total_entries: i64,
+    pub total_characters: i64,
+    pub average_length: f64,
+    // エントリを書いた日数
+    pub days_written: i64,
+    pub first_entry_date: Option<NaiveDate>,
+    pub current_streak: Streak,
+    pub longest_streak: Streak,
+    // 日・ISO週（月曜日から）・月ごとのエントリ数（古い順、エントリのない期間は含まない）
+    pub entries_per_day: Vec<PeriodCount>,
+    pub entries_per_week: Vec<PeriodCount>,
+    pub entries_per_month: Vec<PeriodCount>,
+    pub most_used_tags: Vec<TagUsage>,
+    pub busiest_weekday: Option<WeekdayCount>,
+    pub busiest_hour: Option<HourCount>,
+    // 曜日（月曜日から）・時（0時から）ごとのエントリ数
+    pub entries_by_weekday: Vec<WeekdayCount>,
+    pub entries_by_hour: Vec<HourCount>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityQuery {
+    // 期間（日記のタイムゾーンの日付、両端を含む）。省略時は今日までの1年間
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub tag: Option<i32>,
+}
+
+/// 日ごとの活動量。`counts` と `characters` は `from` の日から1日ずつ並べた配列です。
+#[derive(Debug, Serialize)]
+pub struct ActivityResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub counts: Vec<i64>,
+    pub characters: Vec<i64>,
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRow)]
 pub struct Tag {
     pub id: i32,
     pub name: String,
@@ -12,13 +12,15 @@ pub struct CreateTagRequest {
     pub name: String,
 }
 
+// 現在のハンドラーは Tag をそのまま返しているが、レスポンスの型として残している
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TagResponse {
     pub id: i32,
     pub name: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRow)]
 pub struct EntryTag {
     pub entry_id: i32,
     pub tag_id: i32,
@@ -29,4 +31,13 @@ pub struct EntryTag {
 pub struct EntryWithTags {
     pub content: String,
     pub tags: Vec<String>, // タグ名のリスト
+    // 答えたお題のID（作成時だけ使う）
+    #[serde(default)]
+    pub prompt_id: Option<i32>,
+    // タイムカプセルの開封日時（UTC、作成時だけ使う）
+    #[serde(default)]
+    pub unlock_at: Option<chrono::NaiveDateTime>,
+    // 予約投稿の公開日時（UTC、作成時だけ使う）。この日時までは一覧などに含めない
+    #[serde(default)]
+    pub publish_at: Option<chrono::NaiveDateTime>,
 }
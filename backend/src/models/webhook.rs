@@ -0,0 +1,93 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, FromRow};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct Webhook {
+    pub id: i32,
+    pub url: String,
+    pub secret: String,
+    pub event_types: Json<Vec<String>>,
+    pub enabled: bool,
+    pub datetime: NaiveDateTime,
+}
+
+// 署名の鍵は登録時にだけ受け取り、レスポンスには含めない
+#[derive(Debug, Serialize)]
+pub struct WebhookResponse {
+    pub id: i32,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub enabled: bool,
+    pub datetime: NaiveDateTime,
+}
+
+impl From<Webhook> for WebhookResponse {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            id: webhook.id,
+            url: webhook.url,
+            event_types: webhook.event_types.0,
+            enabled: webhook.enabled,
+            datetime: webhook.datetime,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub secret: String,
+    pub event_types: Vec<String>,
+}
+
+// 指定した項目だけを更新する
+#[derive(Debug, Deserialize)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub secret: Option<String>,
+    pub event_types: Option<Vec<String>>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+// 配信ログとして返す配信の状態
+#[derive(Debug, Serialize, FromRow)]
+pub struct WebhookDelivery {
+    pub id: i32,
+    pub webhook_id: i32,
+    pub event_type: String,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: NaiveDateTime,
+    pub last_attempt_at: Option<NaiveDateTime>,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub datetime: NaiveDateTime,
+}
+
+// 送信待ちの配信（送信に必要な購読の情報を含む）
+#[derive(Debug, FromRow)]
+pub struct PendingDelivery {
+    pub id: i32,
+    pub event_type: String,
+    pub payload: String,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebhookDeliveriesResponse {
+    pub deliveries: Vec<WebhookDelivery>,
+    pub total_pages: u32,
+    pub current_page: u32,
+}
@@ -0,0 +1,301 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+use crate::services::feed_service;
+
+use super::{Notifier, NotifyError, ReminderNotice};
+
+// 1通の送信（接続から QUIT まで）のタイムアウト
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+// base64 にした本文の1行の長さ
+const BODY_LINE_LENGTH: usize = 76;
+
+/// SMTPサーバーにメールを渡して通知します。
+/// 認証とTLSには対応していないため、ローカルのメールサーバー（中継）を指定します。
+pub struct EmailNotifier {
+    // SMTPサーバーのアドレス（`localhost:25` など）
+    addr: String,
+    // 送信者のアドレス
+    from: String,
+    // EHLO で名乗るホスト名
+    hostname: String,
+}
+
+impl EmailNotifier {
+    pub fn new(addr: String, from: String, hostname: String) -> Self {
+        Self { addr, from, hostname }
+    }
+
+    /// 環境変数の設定からメールの送信手段を作成します。
+    /// `REMINDER_SMTP_ADDR` が設定されていない場合は `None` を返します。
+    pub fn from_env() -> Option<Self> {
+        let addr = std::env::var("REMINDER_SMTP_ADDR").ok().filter(|addr| !addr.trim().is_empty())?;
+        let hostname = std::env::var("SMTP_HOSTNAME").unwrap_or_else(|_| "localhost".to_string());
+        let from = std::env::var("REMINDER_MAIL_FROM").unwrap_or_else(|_| format!("diary@{}", hostname));
+        Some(Self::new(addr.trim().to_string(), from, hostname))
+    }
+
+    async fn send(&self, to: &str, message: &str) -> Result<(), NotifyError> {
+        let stream = TcpStream::connect(&self.addr)
+            .await
+            .map_err(|e| NotifyError::Backend(format!("failed to connect to {}: {}", self.addr, e)))?;
+        let (reader, writer) = stream.into_split();
+        send_message(reader, writer, &self.hostname, &self.from, to, message).await
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    async fn notify(&self, notice: &ReminderNotice<'_>) -> Result<(), NotifyError> {
+        let Some(to) = notice.reminder.email.as_deref() else {
+            return Err(NotifyError::InvalidTarget("email is not set".to_string()));
+        };
+        let message = build_message(&self.from, to, &self.hostname, notice);
+        tokio::time::timeout(SEND_TIMEOUT, self.send(to, &message))
+            .await
+            .map_err(|_| NotifyError::Backend("timed out sending mail".to_string()))?
+    }
+}
+
+// SMTPでメールを1通渡します
+async fn send_message<R, W>(
+    reader: R,
+    mut writer: W,
+    hostname: &str,
+    from: &str,
+    to: &str,
+    message: &str,
+) -> Result<(), NotifyError>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut reader = BufReader::new(reader);
+    expect_reply(&mut reader, 2).await?;
+    command(&mut reader, &mut writer, &format!("EHLO {}", hostname), 2).await?;
+    command(&mut reader, &mut writer, &format!("MAIL FROM:<{}>", from), 2).await?;
+    command(&mut reader, &mut writer, &format!("RCPT TO:<{}>", to), 2).await?;
+    command(&mut reader, &mut writer, "DATA", 3).await?;
+    // 本文は base64 のため、`.` で始まる行はない
+    command(&mut reader, &mut writer, &format!("{}\r\n.", message), 2).await?;
+    // 受け付けられた後のため、QUIT の失敗は無視する
+    let _ = command(&mut reader, &mut writer, "QUIT", 2).await;
+    Ok(())
+}
+
+// コマンドを送り、応答のコードが `class`xx であることを確認します
+async fn command<R, W>(reader: &mut BufReader<R>, writer: &mut W, line: &str, class: u16) -> Result<(), NotifyError>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let written = async {
+        writer.write_all(line.as_bytes()).await?;
+        writer.write_all(b"\r\n").await?;
+        writer.flush().await
+    };
+    written.await.map_err(|e| NotifyError::Backend(format!("failed to write to SMTP server: {}", e)))?;
+    expect_reply(reader, class).await
+}
+
+// 応答（複数行の場合は最後の行まで）を読み込み、コードが `class`xx であることを確認します
+async fn expect_reply<R>(reader: &mut BufReader<R>, class: u16) -> Result<(), NotifyError>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut reply = String::new();
+    loop {
+        let mut line = String::new();
+        let read = reader
+            .read_line(&mut line)
+            .await
+            .map_err(|e| NotifyError::Backend(format!("failed to read from SMTP server: {}", e)))?;
+        if read == 0 {
+            return Err(NotifyError::Backend("SMTP server closed the connection".to_string()));
+        }
+        reply.push_str(line.trim_end());
+        // `250-...` は続きの行があり、`250 ...` が最後の行
+        if line.as_bytes().get(3) != Some(&b'-') {
+            break;
+        }
+        reply.push(' ');
+    }
+
+    match reply.get(..3).and_then(|code| code.parse::<u16>().ok()) {
+        Some(code) if code / 100 == class => Ok(()),
+        _ => Err(NotifyError::Backend(format!("unexpected SMTP reply: {}", reply))),
+    }
+}
+
+// 通知のメール（ヘッダーと本文、改行は CRLF）を組み立てます
+fn build_message(from: &str, to: &str, hostname: &str, notice: &ReminderNotice<'_>) -> String {
+    let subject = format!("{}の日記がまだありません", notice.date.format("%Y年%-m月%-d日"));
+    let body = format!(
+        "{}さん\r\n\r\n{}の日記はまだ書かれていません。\r\n今日のことを少しだけでも残しておきませんか？\r\n\r\n{}\r\n",
+        notice.reminder.name,
+        notice.date.format("%Y年%-m月%-d日"),
+        feed_service::site_url(),
+    );
+    let encoded = STANDARD.encode(body.as_bytes());
+    let body_lines: Vec<&str> = encoded
+        .as_bytes()
+        .chunks(BODY_LINE_LENGTH)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap())
+        .collect();
+
+    format!(
+        "From: {from}\r\n\
+         To: {to}\r\n\
+         Subject: =?UTF-8?B?{subject}?=\r\n\
+         Date: {date}\r\n\
+         Message-ID: <reminder-{id}-{day}@{hostname}>\r\n\
+         MIME-Version: 1.0\r\n\
+         Content-Type: text/plain; charset=UTF-8\r\n\
+         Content-Transfer-Encoding: base64\r\n\
+         \r\n\
+         {body}",
+        from = from,
+        to = to,
+        subject = STANDARD.encode(subject.as_bytes()),
+        date = chrono::Utc::now().to_rfc2822(),
+        id = notice.reminder.id,
+        day = notice.date.format("%Y%m%d"),
+        hostname = hostname,
+        body = body_lines.join("\r\n"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+    use tokio::io::{duplex, DuplexStream};
+
+    use super::*;
+    use crate::models::reminder::{Reminder, ReminderChannel};
+
+    // SMTPサーバーの代わりに応答し、受け取ったコマンドと本文を返します。
+    // `RCPT` には `rcpt_reply` で応答します
+    async fn mock_server(stream: DuplexStream, rcpt_reply: &str) -> (Vec<String>, String) {
+        let (reader, mut writer) = tokio::io::split(stream);
+        let mut reader = BufReader::new(reader);
+        let mut commands = Vec::new();
+        let mut data = String::new();
+
+        writer.write_all(b"220 mail.test ESMTP\r\n").await.unwrap();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await.unwrap() == 0 {
+                break;
+            }
+            let command = line.trim_end().to_string();
+            let reply = match command.split(' ').next().unwrap() {
+                "EHLO" => "250-mail.test\r\n250-8BITMIME\r\n250 SIZE 1000000",
+                "MAIL" => "250 OK",
+                "RCPT" => rcpt_reply,
+                "DATA" => {
+                    writer.write_all(b"354 Go ahead\r\n").await.unwrap();
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).await.unwrap();
+                        if line == ".\r\n" {
+                            break;
+                        }
+                        data.push_str(&line);
+                    }
+                    "250 Queued"
+                }
+                "QUIT" => "221 Bye",
+                _ => "502 Unknown command",
+            };
+            commands.push(command);
+            writer.write_all(format!("{}\r\n", reply).as_bytes()).await.unwrap();
+            if reply.starts_with("221") || reply.starts_with('5') {
+                break;
+            }
+        }
+        (commands, data)
+    }
+
+    async fn send_to_mock_server(rcpt_reply: &str, message: &str) -> (Result<(), NotifyError>, Vec<String>, String) {
+        let (client, server) = duplex(64 * 1024);
+        let (reader, writer) = tokio::io::split(client);
+        let sent = send_message(reader, writer, "diary.test", "diary@diary.test", "taro@example.com", message);
+        let (result, (commands, data)) = tokio::join!(sent, mock_server(server, rcpt_reply));
+        (result, commands, data)
+    }
+
+    fn reminder() -> Reminder {
+        Reminder {
+            id: 3,
+            name: "太郎".to_string(),
+            remind_at: NaiveTime::from_hms_opt(21, 0, 0).unwrap(),
+            channel: ReminderChannel::Email,
+            webhook_id: None,
+            email: Some("taro@example.com".to_string()),
+            enabled: true,
+            last_checked_on: None,
+            last_sent_at: None,
+            last_error: None,
+            datetime: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_message_over_smtp() {
+        let reminder = reminder();
+        let notice = ReminderNotice { reminder: &reminder, date: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap() };
+        let message = build_message("diary@diary.test", "taro@example.com", "diary.test", &notice);
+
+        let (result, commands, data) = send_to_mock_server("250 OK", &message).await;
+
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(
+            commands,
+            vec![
+                "EHLO diary.test",
+                "MAIL FROM:<diary@diary.test>",
+                "RCPT TO:<taro@example.com>",
+                "DATA",
+                "QUIT",
+            ]
+        );
+        assert_eq!(data, format!("{}\r\n", message));
+
+        let (headers, body) = data.split_once("\r\n\r\n").unwrap();
+        assert!(headers.contains("To: taro@example.com\r\n"));
+        assert!(headers.contains("Message-ID: <reminder-3-20240102@diary.test>"));
+        let subject = headers.lines().find_map(|line| line.strip_prefix("Subject: =?UTF-8?B?")).unwrap();
+        let subject = STANDARD.decode(subject.trim_end_matches("?=")).unwrap();
+        assert_eq!(String::from_utf8(subject).unwrap(), "2024年1月2日の日記がまだありません");
+        assert!(body.lines().all(|line| line.len() <= BODY_LINE_LENGTH));
+        let body = STANDARD.decode(body.replace("\r\n", "")).unwrap();
+        assert!(String::from_utf8(body).unwrap().starts_with("太郎さん\r\n\r\n2024年1月2日の日記はまだ書かれていません。"));
+    }
+
+    #[tokio::test]
+    async fn fails_when_recipient_is_rejected() {
+        let (result, commands, data) = send_to_mock_server("550 No such user", "Subject: test\r\n\r\nbody").await;
+
+        match result {
+            Err(NotifyError::Backend(message)) => assert_eq!(message, "unexpected SMTP reply: 550 No such user"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(commands.last().unwrap(), "RCPT TO:<taro@example.com>");
+        assert!(data.is_empty());
+    }
+
+    #[tokio::test]
+    async fn fails_when_server_closes_connection() {
+        let (client, server) = duplex(1024);
+        drop(server);
+        let (reader, writer) = tokio::io::split(client);
+
+        let result = send_message(reader, writer, "diary.test", "diary@diary.test", "taro@example.com", "").await;
+
+        assert!(matches!(result, Err(NotifyError::Backend(message)) if message == "SMTP server closed the connection"));
+    }
+}
@@ -0,0 +1,75 @@
+pub mod email;
+pub mod webhook;
+
+use std::fmt;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::MySqlPool;
+
+use crate::models::reminder::{Reminder, ReminderChannel};
+
+use self::email::EmailNotifier;
+use self::webhook::WebhookNotifier;
+
+/// リマインダーの通知で発生するエラー
+#[derive(Debug)]
+pub enum NotifyError {
+    // 通知方法が設定されていない（メールの送信先のSMTPサーバーなど）
+    NotConfigured(&'static str),
+    // リマインダーに送信先が指定されていない、または送信先が存在しない
+    InvalidTarget(String),
+    // 送信に失敗した
+    Backend(String),
+}
+
+impl fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotifyError::NotConfigured(name) => write!(f, "{} is not configured", name),
+            NotifyError::InvalidTarget(message) | NotifyError::Backend(message) => f.write_str(message),
+        }
+    }
+}
+
+/// 日記を書いていない日の通知
+pub struct ReminderNotice<'a> {
+    pub reminder: &'a Reminder,
+    // 日記のタイムゾーンでの日付
+    pub date: NaiveDate,
+}
+
+/// リマインダーの通知方法
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// 通知を送ります。
+    async fn notify(&self, notice: &ReminderNotice<'_>) -> Result<(), NotifyError>;
+}
+
+/// 通知方法ごとの送信手段
+pub struct Notifiers {
+    webhook: WebhookNotifier,
+    // `REMINDER_SMTP_ADDR` を設定した場合だけ使える
+    email: Option<EmailNotifier>,
+}
+
+impl Notifiers {
+    /// 環境変数の設定から送信手段を作成します。
+    pub fn from_env(pool: MySqlPool) -> Self {
+        Self {
+            webhook: WebhookNotifier::new(pool),
+            email: EmailNotifier::from_env(),
+        }
+    }
+
+    /// 通知方法に対応する送信手段を返します。
+    pub fn get(&self, channel: ReminderChannel) -> Result<&dyn Notifier, NotifyError> {
+        match channel {
+            ReminderChannel::Webhook => Ok(&self.webhook),
+            ReminderChannel::Email => match &self.email {
+                Some(email) => Ok(email),
+                None => Err(NotifyError::NotConfigured("REMINDER_SMTP_ADDR")),
+            },
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+use crate::services::webhook_service::{self, WebhookServiceError};
+
+use super::{Notifier, NotifyError, ReminderNotice};
+
+/// Webhookで送るイベントの種類
+pub const REMINDER_EVENT_TYPE: &str = "reminder.due";
+
+/// 登録済みのWebhookに `reminder.due` イベントを送ります。
+/// 配信はWebhookの配信キューに追加するため、署名と失敗時の再送は通常のイベントと同じです。
+pub struct WebhookNotifier {
+    pool: MySqlPool,
+}
+
+#[derive(Serialize)]
+struct ReminderEvent<'a> {
+    reminder_id: i32,
+    name: &'a str,
+    date: chrono::NaiveDate,
+}
+
+impl WebhookNotifier {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, notice: &ReminderNotice<'_>) -> Result<(), NotifyError> {
+        let Some(webhook_id) = notice.reminder.webhook_id else {
+            return Err(NotifyError::InvalidTarget("webhook_id is not set".to_string()));
+        };
+        let data = serde_json::to_string(&ReminderEvent {
+            reminder_id: notice.reminder.id,
+            name: &notice.reminder.name,
+            date: notice.date,
+        })
+        .map_err(|e| NotifyError::Backend(e.to_string()))?;
+
+        match webhook_service::enqueue_delivery(&self.pool, webhook_id, REMINDER_EVENT_TYPE, &data).await {
+            Ok(()) => Ok(()),
+            Err(WebhookServiceError::NotFound) => Err(NotifyError::InvalidTarget(format!(
+                "webhook {} does not exist or is disabled",
+                webhook_id
+            ))),
+            Err(e) => Err(NotifyError::Backend(format!("failed to enqueue webhook delivery: {:?}", e))),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use sqlx::types::Json;
+use sqlx::{Executor, MySql, MySqlPool, QueryBuilder, Result};
+use crate::models::attachment::{Attachment, NewAttachment, Thumbnail};
+
+pub struct AttachmentRepository<'a> {
+    pool: &'a MySqlPool,
+}
+
+impl<'a> AttachmentRepository<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// 指定されたエントリの添付ファイルを古い順に取得します。
+    pub async fn find_attachments_for_entry(&self, entry_id: i32) -> Result<Vec<Attachment>> {
+        sqlx::query_as::<_, Attachment>(
+            r#"
+            SELECT id, entry_id, storage_key, filename, content_type, size, datetime, width, height, thumbnails
+            FROM attachment
+            WHERE entry_id = ?
+            ORDER BY id
+            "#,
+        )
+        .bind(entry_id)
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// 複数エントリの添付ファイルをまとめて取得します。
+    /// 添付ファイルのないエントリはマップに含まれません。
+    pub async fn find_attachments_for_entries<'e, E>(
+        &self,
+        executor: E,
+        entry_ids: &[i32],
+    ) -> Result<HashMap<i32, Vec<Attachment>>>
+    where
+        E: Executor<'e, Database = MySql>,
+    {
+        if entry_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut query = QueryBuilder::<MySql>::new(
+            "SELECT id, entry_id, storage_key, filename, content_type, size, datetime, width, height, thumbnails FROM attachment WHERE entry_id IN (",
+        );
+        let mut separated = query.separated(", ");
+        for id in entry_ids {
+            separated.push_bind(*id);
+        }
+        separated.push_unseparated(") ORDER BY id");
+
+        let rows: Vec<Attachment> = query.build_query_as().fetch_all(executor).await?;
+        let mut attachments: HashMap<i32, Vec<Attachment>> = HashMap::new();
+        for attachment in rows {
+            attachments.entry(attachment.entry_id).or_default().push(attachment);
+        }
+        Ok(attachments)
+    }
+
+    /// IDで添付ファイルを取得します。
+    pub async fn find_attachment(&self, id: i32) -> Result<Option<Attachment>> {
+        sqlx::query_as::<_, Attachment>(
+            "SELECT id, entry_id, storage_key, filename, content_type, size, datetime, width, height, thumbnails FROM attachment WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    /// `before` より前に登録された画像のうち、サムネイルが記録されていない添付ファイルをID順に取得します。
+    /// 画像かどうかは、保存時に記録した幅の有無で判断します。
+    pub async fn find_images_without_thumbnails(&self, before: NaiveDateTime) -> Result<Vec<Attachment>> {
+        sqlx::query_as::<_, Attachment>(
+            r#"
+            SELECT id, entry_id, storage_key, filename, content_type, size, datetime, width, height, thumbnails
+            FROM attachment
+            WHERE width IS NOT NULL AND JSON_LENGTH(thumbnails) = 0 AND datetime < ?
+            ORDER BY id
+            "#,
+        )
+        .bind(before)
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// 添付ファイルの情報を登録します。
+    /// 戻り値は作成された添付ファイルのIDです。
+    pub async fn create_attachment(&self, attachment: &NewAttachment<'_>) -> Result<i32> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO attachment (entry_id, storage_key, filename, content_type, size, datetime, width, height)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(attachment.entry_id)
+        .bind(attachment.storage_key)
+        .bind(attachment.filename)
+        .bind(attachment.content_type)
+        .bind(attachment.size)
+        .bind(attachment.datetime)
+        .bind(attachment.width)
+        .bind(attachment.height)
+        .execute(self.pool)
+        .await?;
+        Ok(result.last_insert_id() as i32)
+    }
+
+    /// 生成したサムネイルの一覧を記録します。
+    /// 戻り値は更新対象が存在したかどうかです。
+    pub async fn update_thumbnails(&self, id: i32, thumbnails: &[Thumbnail]) -> Result<bool> {
+        let result = sqlx::query("UPDATE attachment SET thumbnails = ? WHERE id = ?")
+            .bind(Json(thumbnails))
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        // MySQLは値が変わらない場合に0件を返すため、存在確認は別に行う
+        if result.rows_affected() > 0 {
+            return Ok(true);
+        }
+        Ok(self.find_attachment(id).await?.is_some())
+    }
+
+    /// 添付ファイルの情報を削除します。
+    /// 戻り値は削除対象が存在したかどうかです。
+    pub async fn delete_attachment(&self, id: i32) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM attachment WHERE id = ?")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,386 @@
+use sqlx::{MySql, MySqlPool, QueryBuilder, Result, Transaction};
+use crate::models::{
+    attachment::Attachment,
+    capture::CaptureTokenRow,
+    comment::Comment,
+    entry::{EntryRow, EntryView},
+    prompt::{DailyPrompt, EntryPrompt, Prompt},
+    reaction::Reaction,
+    reminder::Reminder,
+    tag::{EntryTag, Tag},
+    webhook::Webhook,
+};
+
+// 1回のINSERTでまとめて書き込む行数
+const INSERT_CHUNK_SIZE: usize = 500;
+
+pub struct BackupRepository<'a> {
+    pool: &'a MySqlPool,
+}
+
+impl<'a> BackupRepository<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// すべてのエントリをID順に取得します。
+    pub async fn find_all_entries(&self) -> Result<Vec<EntryRow>> {
+        sqlx::query_as::<_, EntryRow>(concat!(
+            "SELECT id, content, datetime, unlock_at, unlock_notified, status, draft_key, draft_tags, publish_at, ",
+            "publish_notified ",
+            "FROM entry ORDER BY id"
+        ))
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// すべてのタグをID順に取得します。
+    pub async fn find_all_tags(&self) -> Result<Vec<Tag>> {
+        sqlx::query_as::<_, Tag>("SELECT id, name FROM tag ORDER BY id")
+            .fetch_all(self.pool)
+            .await
+    }
+
+    /// エントリとタグの関連をすべて取得します。
+    pub async fn find_all_entry_tags(&self) -> Result<Vec<EntryTag>> {
+        sqlx::query_as::<_, EntryTag>("SELECT entry_id, tag_id FROM entry_tag ORDER BY entry_id, tag_id")
+            .fetch_all(self.pool)
+            .await
+    }
+
+    /// すべてのコメントをID順に取得します。
+    pub async fn find_all_comments(&self) -> Result<Vec<Comment>> {
+        sqlx::query_as::<_, Comment>(
+            "SELECT id, entry_id, author, content, datetime FROM comment ORDER BY id",
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// すべてのリアクションをID順に取得します。
+    pub async fn find_all_reactions(&self) -> Result<Vec<Reaction>> {
+        sqlx::query_as::<_, Reaction>("SELECT id, entry_id, emoji, datetime FROM reaction ORDER BY id")
+            .fetch_all(self.pool)
+            .await
+    }
+
+    /// すべての添付ファイルの情報をID順に取得します。
+    pub async fn find_all_attachments(&self) -> Result<Vec<Attachment>> {
+        sqlx::query_as::<_, Attachment>(
+            "SELECT id, entry_id, storage_key, filename, content_type, size, datetime, width, height, thumbnails FROM attachment ORDER BY id",
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// すべてのお題をID順に取得します。
+    pub async fn find_all_prompts(&self) -> Result<Vec<Prompt>> {
+        sqlx::query_as::<_, Prompt>("SELECT id, text, language, enabled, datetime FROM prompt ORDER BY id")
+            .fetch_all(self.pool)
+            .await
+    }
+
+    /// 日ごとに選んだお題をすべて取得します。
+    pub async fn find_all_daily_prompts(&self) -> Result<Vec<DailyPrompt>> {
+        sqlx::query_as::<_, DailyPrompt>("SELECT date, language, prompt_id FROM daily_prompt ORDER BY date, language")
+            .fetch_all(self.pool)
+            .await
+    }
+
+    /// エントリとお題の関連をすべて取得します。
+    pub async fn find_all_entry_prompts(&self) -> Result<Vec<EntryPrompt>> {
+        sqlx::query_as::<_, EntryPrompt>("SELECT entry_id, prompt_id FROM entry_prompt ORDER BY entry_id")
+            .fetch_all(self.pool)
+            .await
+    }
+
+    /// ランダム表示の記録をすべて取得します。
+    pub async fn find_all_entry_views(&self) -> Result<Vec<EntryView>> {
+        sqlx::query_as::<_, EntryView>("SELECT entry_id, viewed_at FROM entry_view ORDER BY entry_id")
+            .fetch_all(self.pool)
+            .await
+    }
+
+    /// すべてのWebhookの購読をID順に取得します。
+    pub async fn find_all_webhooks(&self) -> Result<Vec<Webhook>> {
+        sqlx::query_as::<_, Webhook>(
+            "SELECT id, url, secret, event_types, enabled, datetime FROM webhook ORDER BY id",
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// すべてのリマインダーをID順に取得します。
+    pub async fn find_all_reminders(&self) -> Result<Vec<Reminder>> {
+        sqlx::query_as::<_, Reminder>(
+            r#"
+            SELECT id, name, remind_at, channel, webhook_id, email, enabled,
+                   last_checked_on, last_sent_at, last_error, datetime
+            FROM reminder
+            ORDER BY id
+            "#,
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// すべてのキャプチャのトークン（ハッシュ）をID順に取得します。
+    pub async fn find_all_capture_tokens(&self) -> Result<Vec<CaptureTokenRow>> {
+        sqlx::query_as::<_, CaptureTokenRow>(
+            "SELECT id, name, token_hash, datetime, last_used_at FROM capture_token ORDER BY id",
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// IDを保ったままエントリを追加します（トランザクション内）。
+    /// 開封日時を過ぎたタイムカプセルと公開日時を過ぎた予約投稿は、通知済みとして追加します。
+    pub async fn insert_entries_tx(tx: &mut Transaction<'_, MySql>, entries: &[&EntryRow]) -> Result<()> {
+        let now = chrono::Utc::now().naive_utc();
+        for chunk in entries.chunks(INSERT_CHUNK_SIZE) {
+            let mut query = QueryBuilder::<MySql>::new(
+                concat!(
+                    "INSERT INTO entry (id, content, datetime, unlock_at, unlock_notified, status, draft_key, draft_tags, ",
+                    "publish_at, publish_notified) "
+                ),
+            );
+            query.push_values(chunk, |mut row, entry_row| {
+                let entry = &entry_row.entry;
+                // 通知の記録がない古いバックアップでは、過ぎた日時の分を通知済みとして扱う
+                let unlock_notified = entry_row
+                    .unlock_notified
+                    .unwrap_or_else(|| entry.unlock_at.is_some_and(|unlock_at| unlock_at <= now));
+                let publish_notified = entry_row
+                    .publish_notified
+                    .unwrap_or_else(|| entry.publish_at.is_some_and(|publish_at| publish_at <= now));
+                row.push_bind(entry.id)
+                    .push_bind(&entry.content)
+                    .push_bind(entry.datetime)
+                    .push_bind(entry.unlock_at)
+                    .push_bind(unlock_notified)
+                    .push_bind(entry.status)
+                    .push_bind(&entry.draft_key)
+                    .push_bind(&entry.draft_tags)
+                    .push_bind(entry.publish_at)
+                    .push_bind(publish_notified);
+            });
+            query.build().execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+
+    /// IDを保ったままタグを追加します（トランザクション内）。
+    pub async fn insert_tags_tx(tx: &mut Transaction<'_, MySql>, tags: &[&Tag]) -> Result<()> {
+        for chunk in tags.chunks(INSERT_CHUNK_SIZE) {
+            let mut query = QueryBuilder::<MySql>::new("INSERT INTO tag (id, name) ");
+            query.push_values(chunk, |mut row, tag| {
+                row.push_bind(tag.id).push_bind(&tag.name);
+            });
+            query.build().execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+
+    /// エントリとタグの関連を追加します（トランザクション内）。
+    pub async fn insert_entry_tags_tx(
+        tx: &mut Transaction<'_, MySql>,
+        entry_tags: &[&EntryTag],
+    ) -> Result<()> {
+        for chunk in entry_tags.chunks(INSERT_CHUNK_SIZE) {
+            let mut query = QueryBuilder::<MySql>::new("INSERT INTO entry_tag (entry_id, tag_id) ");
+            query.push_values(chunk, |mut row, entry_tag| {
+                row.push_bind(entry_tag.entry_id).push_bind(entry_tag.tag_id);
+            });
+            query.build().execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+
+    /// IDを保ったままコメントを追加します（トランザクション内）。
+    pub async fn insert_comments_tx(
+        tx: &mut Transaction<'_, MySql>,
+        comments: &[&Comment],
+    ) -> Result<()> {
+        for chunk in comments.chunks(INSERT_CHUNK_SIZE) {
+            let mut query = QueryBuilder::<MySql>::new(
+                "INSERT INTO comment (id, entry_id, author, content, datetime) ",
+            );
+            query.push_values(chunk, |mut row, comment| {
+                row.push_bind(comment.id)
+                    .push_bind(comment.entry_id)
+                    .push_bind(&comment.author)
+                    .push_bind(&comment.content)
+                    .push_bind(comment.datetime);
+            });
+            query.build().execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+
+    /// IDを保ったままリアクションを追加します（トランザクション内）。
+    pub async fn insert_reactions_tx(
+        tx: &mut Transaction<'_, MySql>,
+        reactions: &[&Reaction],
+    ) -> Result<()> {
+        for chunk in reactions.chunks(INSERT_CHUNK_SIZE) {
+            let mut query =
+                QueryBuilder::<MySql>::new("INSERT INTO reaction (id, entry_id, emoji, datetime) ");
+            query.push_values(chunk, |mut row, reaction| {
+                row.push_bind(reaction.id)
+                    .push_bind(reaction.entry_id)
+                    .push_bind(&reaction.emoji)
+                    .push_bind(reaction.datetime);
+            });
+            query.build().execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+
+    /// IDを保ったまま添付ファイルの情報を追加します（トランザクション内）。
+    pub async fn insert_attachments_tx(
+        tx: &mut Transaction<'_, MySql>,
+        attachments: &[&Attachment],
+    ) -> Result<()> {
+        for chunk in attachments.chunks(INSERT_CHUNK_SIZE) {
+            let mut query = QueryBuilder::<MySql>::new(
+                "INSERT INTO attachment (id, entry_id, storage_key, filename, content_type, size, datetime, width, height, thumbnails) ",
+            );
+            query.push_values(chunk, |mut row, attachment| {
+                row.push_bind(attachment.id)
+                    .push_bind(attachment.entry_id)
+                    .push_bind(&attachment.storage_key)
+                    .push_bind(&attachment.filename)
+                    .push_bind(&attachment.content_type)
+                    .push_bind(attachment.size)
+                    .push_bind(attachment.datetime)
+                    .push_bind(attachment.width)
+                    .push_bind(attachment.height)
+                    .push_bind(&attachment.thumbnails);
+            });
+            query.build().execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+
+    /// IDを保ったままお題を追加します（トランザクション内）。
+    pub async fn insert_prompts_tx(tx: &mut Transaction<'_, MySql>, prompts: &[&Prompt]) -> Result<()> {
+        for chunk in prompts.chunks(INSERT_CHUNK_SIZE) {
+            let mut query = QueryBuilder::<MySql>::new("INSERT INTO prompt (id, text, language, enabled, datetime) ");
+            query.push_values(chunk, |mut row, prompt| {
+                row.push_bind(prompt.id)
+                    .push_bind(&prompt.text)
+                    .push_bind(&prompt.language)
+                    .push_bind(prompt.enabled)
+                    .push_bind(prompt.datetime);
+            });
+            query.build().execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+
+    /// 日ごとに選んだお題を追加します（トランザクション内）。
+    pub async fn insert_daily_prompts_tx(
+        tx: &mut Transaction<'_, MySql>,
+        daily_prompts: &[&DailyPrompt],
+    ) -> Result<()> {
+        for chunk in daily_prompts.chunks(INSERT_CHUNK_SIZE) {
+            let mut query = QueryBuilder::<MySql>::new("INSERT INTO daily_prompt (date, language, prompt_id) ");
+            query.push_values(chunk, |mut row, daily_prompt| {
+                row.push_bind(daily_prompt.date)
+                    .push_bind(&daily_prompt.language)
+                    .push_bind(daily_prompt.prompt_id);
+            });
+            query.build().execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+
+    /// エントリとお題の関連を追加します（トランザクション内）。
+    pub async fn insert_entry_prompts_tx(
+        tx: &mut Transaction<'_, MySql>,
+        entry_prompts: &[&EntryPrompt],
+    ) -> Result<()> {
+        for chunk in entry_prompts.chunks(INSERT_CHUNK_SIZE) {
+            let mut query = QueryBuilder::<MySql>::new("INSERT INTO entry_prompt (entry_id, prompt_id) ");
+            query.push_values(chunk, |mut row, entry_prompt| {
+                row.push_bind(entry_prompt.entry_id).push_bind(entry_prompt.prompt_id);
+            });
+            query.build().execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+
+    /// ランダム表示の記録を追加します（トランザクション内）。
+    pub async fn insert_entry_views_tx(tx: &mut Transaction<'_, MySql>, entry_views: &[&EntryView]) -> Result<()> {
+        for chunk in entry_views.chunks(INSERT_CHUNK_SIZE) {
+            let mut query = QueryBuilder::<MySql>::new("INSERT INTO entry_view (entry_id, viewed_at) ");
+            query.push_values(chunk, |mut row, entry_view| {
+                row.push_bind(entry_view.entry_id).push_bind(entry_view.viewed_at);
+            });
+            query.build().execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+
+    /// IDを保ったままWebhookの購読を追加します（トランザクション内）。
+    pub async fn insert_webhooks_tx(tx: &mut Transaction<'_, MySql>, webhooks: &[&Webhook]) -> Result<()> {
+        for chunk in webhooks.chunks(INSERT_CHUNK_SIZE) {
+            let mut query =
+                QueryBuilder::<MySql>::new("INSERT INTO webhook (id, url, secret, event_types, enabled, datetime) ");
+            query.push_values(chunk, |mut row, webhook| {
+                row.push_bind(webhook.id)
+                    .push_bind(&webhook.url)
+                    .push_bind(&webhook.secret)
+                    .push_bind(&webhook.event_types)
+                    .push_bind(webhook.enabled)
+                    .push_bind(webhook.datetime);
+            });
+            query.build().execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+
+    /// IDを保ったままリマインダーを追加します（トランザクション内）。
+    pub async fn insert_reminders_tx(tx: &mut Transaction<'_, MySql>, reminders: &[&Reminder]) -> Result<()> {
+        for chunk in reminders.chunks(INSERT_CHUNK_SIZE) {
+            let mut query = QueryBuilder::<MySql>::new(
+                "INSERT INTO reminder (id, name, remind_at, channel, webhook_id, email, enabled, last_checked_on, last_sent_at, last_error, datetime) ",
+            );
+            query.push_values(chunk, |mut row, reminder| {
+                row.push_bind(reminder.id)
+                    .push_bind(&reminder.name)
+                    .push_bind(reminder.remind_at)
+                    .push_bind(reminder.channel)
+                    .push_bind(reminder.webhook_id)
+                    .push_bind(&reminder.email)
+                    .push_bind(reminder.enabled)
+                    .push_bind(reminder.last_checked_on)
+                    .push_bind(reminder.last_sent_at)
+                    .push_bind(&reminder.last_error)
+                    .push_bind(reminder.datetime);
+            });
+            query.build().execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+
+    /// IDを保ったままキャプチャのトークンを追加します（トランザクション内）。
+    pub async fn insert_capture_tokens_tx(
+        tx: &mut Transaction<'_, MySql>,
+        capture_tokens: &[&CaptureTokenRow],
+    ) -> Result<()> {
+        for chunk in capture_tokens.chunks(INSERT_CHUNK_SIZE) {
+            let mut query =
+                QueryBuilder::<MySql>::new("INSERT INTO capture_token (id, name, token_hash, datetime, last_used_at) ");
+            query.push_values(chunk, |mut row, capture_token| {
+                row.push_bind(capture_token.id)
+                    .push_bind(&capture_token.name)
+                    .push_bind(&capture_token.token_hash)
+                    .push_bind(capture_token.datetime)
+                    .push_bind(capture_token.last_used_at);
+            });
+            query.build().execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+}
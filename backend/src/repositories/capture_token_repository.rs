@@ -0,0 +1,70 @@
+use sqlx::{MySqlPool, Result};
+use crate::models::capture::CaptureToken;
+
+pub struct CaptureTokenRepository<'a> {
+    pool: &'a MySqlPool,
+}
+
+impl<'a> CaptureTokenRepository<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// すべてのトークンを発行順に取得します（トークン本体は含みません）。
+    pub async fn find_tokens(&self) -> Result<Vec<CaptureToken>> {
+        sqlx::query_as::<_, CaptureToken>(
+            "SELECT id, name, datetime, last_used_at FROM capture_token ORDER BY id",
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// トークンを登録します。
+    /// 戻り値は登録されたトークンのIDです。
+    pub async fn create_token(&self, name: &str, token_hash: &str, datetime: chrono::NaiveDateTime) -> Result<i32> {
+        let result = sqlx::query("INSERT INTO capture_token (name, token_hash, datetime) VALUES (?, ?, ?)")
+            .bind(name)
+            .bind(token_hash)
+            .bind(datetime)
+            .execute(self.pool)
+            .await?;
+        Ok(result.last_insert_id() as i32)
+    }
+
+    /// トークンのハッシュが一致するトークンが存在するかを確認します。
+    #[cfg(feature = "smtp")]
+    pub async fn token_exists(&self, token_hash: &str) -> Result<bool> {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM capture_token WHERE token_hash = ?")
+            .bind(token_hash)
+            .fetch_one(self.pool)
+            .await
+            .map(|count| count > 0)
+    }
+
+    /// トークンのハッシュが一致するトークンを探し、最終利用日時を更新します。
+    /// 戻り値は一致したトークンのIDです。
+    pub async fn use_token(&self, token_hash: &str, now: chrono::NaiveDateTime) -> Result<Option<i32>> {
+        let id = sqlx::query_scalar::<_, i32>("SELECT id FROM capture_token WHERE token_hash = ?")
+            .bind(token_hash)
+            .fetch_optional(self.pool)
+            .await?;
+        if let Some(id) = id {
+            sqlx::query("UPDATE capture_token SET last_used_at = ? WHERE id = ?")
+                .bind(now)
+                .bind(id)
+                .execute(self.pool)
+                .await?;
+        }
+        Ok(id)
+    }
+
+    /// トークンを削除（失効）します。
+    /// 戻り値は削除対象が存在したかどうかです。
+    pub async fn delete_token(&self, id: i32) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM capture_token WHERE id = ?")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
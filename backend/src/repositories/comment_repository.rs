@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use sqlx::{Executor, MySql, MySqlPool, QueryBuilder, Result};
+use crate::models::comment::Comment;
+
+pub struct CommentRepository<'a> {
+    pool: &'a MySqlPool,
+}
+
+impl<'a> CommentRepository<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// 指定されたエントリのコメントを古い順に取得します。
+    pub async fn find_comments_for_entry(&self, entry_id: i32) -> Result<Vec<Comment>> {
+        sqlx::query_as::<_, Comment>(
+            r#"
+            SELECT id, entry_id, author, content, datetime
+            FROM comment
+            WHERE entry_id = ?
+            ORDER BY datetime, id
+            "#,
+        )
+        .bind(entry_id)
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// IDでコメントを取得します。
+    pub async fn find_comment(&self, id: i32) -> Result<Option<Comment>> {
+        sqlx::query_as::<_, Comment>(
+            "SELECT id, entry_id, author, content, datetime FROM comment WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    /// 新しいコメントを作成します。
+    /// 戻り値は作成されたコメントのIDです。
+    pub async fn create_comment(&self, entry_id: i32, author: &str, content: &str) -> Result<i32> {
+        let now = chrono::Utc::now();
+        let result = sqlx::query(
+            "INSERT INTO comment (entry_id, author, content, datetime) VALUES (?, ?, ?, ?)",
+        )
+        .bind(entry_id)
+        .bind(author)
+        .bind(content)
+        .bind(now)
+        .execute(self.pool)
+        .await?;
+        Ok(result.last_insert_id() as i32)
+    }
+
+    /// コメントの本文を更新します。
+    /// 戻り値は更新対象が存在したかどうかです。
+    pub async fn update_comment(&self, id: i32, content: &str) -> Result<bool> {
+        let result = sqlx::query("UPDATE comment SET content = ? WHERE id = ?")
+            .bind(content)
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        // MySQLは値が変わらない場合に0件を返すため、存在確認は別に行う
+        if result.rows_affected() > 0 {
+            return Ok(true);
+        }
+        Ok(self.find_comment(id).await?.is_some())
+    }
+
+    /// コメントを削除します。
+    /// 戻り値は削除対象が存在したかどうかです。
+    pub async fn delete_comment(&self, id: i32) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM comment WHERE id = ?")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 複数エントリのコメント数をまとめて取得します。
+    /// コメントのないエントリはマップに含まれません。
+    pub async fn count_comments_for_entries<'e, E>(&self, executor: E, entry_ids: &[i32]) -> Result<HashMap<i32, i64>>
+    where
+        E: Executor<'e, Database = MySql>,
+    {
+        if entry_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut query = QueryBuilder::<MySql>::new(
+            "SELECT entry_id, COUNT(*) FROM comment WHERE entry_id IN (",
+        );
+        let mut separated = query.separated(", ");
+        for id in entry_ids {
+            separated.push_bind(*id);
+        }
+        separated.push_unseparated(") GROUP BY entry_id");
+
+        let rows: Vec<(i32, i64)> = query.build_query_as().fetch_all(executor).await?;
+        Ok(rows.into_iter().collect())
+    }
+}
@@ -1,7 +1,84 @@
-use sqlx::{MySqlPool, Result};
-use crate::models::entry::Entry;
+use chrono::{NaiveDate, NaiveDateTime};
+use futures::stream::BoxStream;
+use sqlx::types::Json;
+use sqlx::{MySql, MySqlPool, QueryBuilder, Result};
+use crate::models::entry::{Entry, EntryStatus};
  // create_entry_with_tags で使うため
 
+// 公開済みのエントリの条件（下書きを除く）。公開日時前の予約投稿を含みます。
+// 引数はテーブル名または別名です。
+macro_rules! published {
+    ($table:literal) => {
+        concat!($table, ".status = 'published'")
+    };
+}
+
+// 一覧や集計の対象にする、公開中のエントリの条件（下書きと公開日時前の予約投稿を除く）。
+// 引数はテーブル名または別名で、すべての読み出しでこの条件を使います。
+// 公開日時はDBの時刻と比べるため、公開の通知（`find_due_scheduled_ids`）と判定がずれません。
+macro_rules! visible {
+    ($table:literal) => {
+        concat!(
+            published!($table), " AND (", $table, ".publish_at IS NULL OR ", $table, ".publish_at <= UTC_TIMESTAMP())"
+        )
+    };
+}
+
+// `Entry` として読み出す列
+macro_rules! entry_columns {
+    ($table:literal) => {
+        concat!(
+            $table, ".id, ", $table, ".content, ", $table, ".datetime, ",
+            $table, ".unlock_at, ", $table, ".status, ", $table, ".draft_key, ", $table, ".draft_tags, ",
+            $table, ".publish_at"
+        )
+    };
+}
+
+/// ランダムに選ぶエントリの条件（日時はUTC）
+#[derive(Debug, Default)]
+pub struct RandomEntryFilter {
+    pub tag_id: Option<i32>,
+    // `from` 以上 `to` 未満
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+    // この日時以降にランダム表示したエントリを除く
+    pub not_viewed_since: Option<NaiveDateTime>,
+    // この日時に開封前のタイムカプセルを除く
+    pub unlocked_at: Option<NaiveDateTime>,
+}
+
+impl RandomEntryFilter {
+    fn push_conditions<'q>(&'q self, query: &mut QueryBuilder<'q, MySql>) {
+        if let Some(tag_id) = self.tag_id {
+            query.push(" JOIN entry_tag et ON et.entry_id = e.id AND et.tag_id = ");
+            query.push_bind(tag_id);
+        }
+        if self.not_viewed_since.is_some() {
+            query.push(" LEFT JOIN entry_view v ON v.entry_id = e.id");
+        }
+        query.push(concat!(" WHERE ", visible!("e")));
+        if let Some(from) = self.from {
+            query.push(" AND e.datetime >= ");
+            query.push_bind(from);
+        }
+        if let Some(to) = self.to {
+            query.push(" AND e.datetime < ");
+            query.push_bind(to);
+        }
+        if let Some(since) = self.not_viewed_since {
+            query.push(" AND (v.viewed_at IS NULL OR v.viewed_at < ");
+            query.push_bind(since);
+            query.push(")");
+        }
+        if let Some(now) = self.unlocked_at {
+            query.push(" AND (e.unlock_at IS NULL OR e.unlock_at <= ");
+            query.push_bind(now);
+            query.push(")");
+        }
+    }
+}
+
 pub struct DiaryRepository<'a> {
     pool: &'a MySqlPool,
 }
@@ -13,48 +90,272 @@ impl<'a> DiaryRepository<'a> {
 
     /// 指定されたページのエントリを取得します。
     pub async fn find_entries(&self, limit: u32, offset: u32) -> Result<Vec<Entry>> {
-        sqlx::query_as::<_, Entry>(
-            "SELECT id, content, datetime FROM entry ORDER BY id DESC LIMIT ? OFFSET ?",
-        )
+        sqlx::query_as::<_, Entry>(concat!(
+            "SELECT ", entry_columns!("entry"), " FROM entry WHERE ", visible!("entry"),
+            " ORDER BY id DESC LIMIT ? OFFSET ?"
+        ))
         .bind(limit)
         .bind(offset)
         .fetch_all(self.pool)
         .await
     }
 
+    /// すべてのエントリを古い順にストリームで取得します。
+    /// 全件をメモリに載せずに処理するエクスポート用です。
+    pub fn stream_all_entries(&self) -> BoxStream<'a, Result<Entry>> {
+        sqlx::query_as::<_, Entry>(concat!(
+            "SELECT ", entry_columns!("entry"), " FROM entry WHERE ", visible!("entry"), " ORDER BY datetime, id"
+        ))
+        .fetch(self.pool)
+    }
+
+    /// IDでエントリを取得します。
+    pub async fn find_entry(&self, id: i32) -> Result<Option<Entry>> {
+        sqlx::query_as::<_, Entry>(concat!(
+            "SELECT ", entry_columns!("entry"), " FROM entry WHERE id = ? AND ", visible!("entry")
+        ))
+        .bind(id)
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    /// IDでエントリを取得します（トランザクション内）。同じトランザクションでの変更を含みます。
+    pub async fn find_entry_tx(tx: &mut sqlx::Transaction<'_, sqlx::MySql>, id: i32) -> Result<Option<Entry>> {
+        sqlx::query_as::<_, Entry>(concat!(
+            "SELECT ", entry_columns!("entry"), " FROM entry WHERE id = ? AND ", visible!("entry")
+        ))
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+    }
+
     /// エントリの総数を取得します。
     pub async fn count_entries(&self) -> Result<i64> {
-        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM entry")
+        sqlx::query_scalar::<_, i64>(concat!("SELECT COUNT(*) FROM entry WHERE ", visible!("entry")))
             .fetch_one(self.pool)
             .await
     }
 
-    /// 新しいエントリを作成します（タグなし）。
-    pub async fn create_simple_entry(&self, content: &str) -> Result<u64> {
+    /// 指定されたIDのエントリが存在するかを確認します。
+    pub async fn entry_exists(&self, id: i32) -> Result<bool> {
+        sqlx::query_scalar::<_, i64>(concat!("SELECT COUNT(*) FROM entry WHERE id = ? AND ", visible!("entry")))
+            .bind(id)
+            .fetch_one(self.pool)
+            .await
+            .map(|count| count > 0)
+    }
+
+    /// エントリを削除します。タグとの関連やコメントなどは外部キーにより一緒に削除されます。
+    /// 下書きは対象にしません（`delete_draft` を使います）。公開日時前の予約投稿は削除できます。
+    /// 戻り値は削除対象が存在したかどうかです。
+    pub async fn delete_entry_tx(tx: &mut sqlx::Transaction<'_, sqlx::MySql>, id: i32) -> Result<bool> {
+        let result = sqlx::query(concat!("DELETE FROM entry WHERE id = ? AND ", published!("entry")))
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 新しいエントリを作成します（タグなし、トランザクション内）。
+    pub async fn create_simple_entry_tx(tx: &mut sqlx::Transaction<'_, sqlx::MySql>, content: &str) -> Result<u64> {
         let now = chrono::Utc::now();
         let result = sqlx::query("INSERT INTO entry (content, datetime) VALUES (?, ?)")
             .bind(content)
             .bind(now)
-            .execute(self.pool)
+            .execute(&mut **tx)
             .await?;
         Ok(result.last_insert_id())
     }
 
     /// 新しいエントリと関連するタグを作成します（トランザクション内）。
     /// 戻り値は作成されたエントリのIDです。
+    /// 日時にはインポート元の日時などを指定できます（UTC）。
+    /// `unlock_at` を指定した場合は、その日時まで本文を隠すタイムカプセルになります。
+    /// `publish_at` を指定した場合は、その日時まで一覧などに含めない予約投稿になります。
     pub async fn create_entry_with_tags_tx(
         tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
         content: &str,
+        datetime: NaiveDateTime,
+        unlock_at: Option<NaiveDateTime>,
+        publish_at: Option<NaiveDateTime>,
     ) -> Result<i32> {
-        let now = chrono::Utc::now();
-        let result = sqlx::query("INSERT INTO entry (content, datetime) VALUES (?, ?)")
+        let result = sqlx::query("INSERT INTO entry (content, datetime, unlock_at, publish_at) VALUES (?, ?, ?, ?)")
             .bind(content)
-            .bind(now)
+            .bind(datetime)
+            .bind(unlock_at)
+            .bind(publish_at)
             .execute(&mut **tx) // Dereference tx to get &mut MySqlConnection
             .await?;
         Ok(result.last_insert_id() as i32)
     }
 
+    /// エントリの本文を更新します（トランザクション内）。公開日時前の予約投稿も更新できます。
+    /// 戻り値は更新対象が存在したかどうかです。
+    pub async fn update_entry_content_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        id: i32,
+        content: &str,
+    ) -> Result<bool> {
+        // MySQLは値が変わらない更新を影響行数に数えないため、先に存在を確認する
+        let exists = sqlx::query_scalar::<_, i64>(concat!(
+            "SELECT COUNT(*) FROM entry WHERE id = ? AND ", published!("entry"), " FOR UPDATE"
+        ))
+        .bind(id)
+        .fetch_one(&mut **tx)
+        .await?
+            > 0;
+        if !exists {
+            return Ok(false);
+        }
+        sqlx::query("UPDATE entry SET content = ? WHERE id = ?")
+            .bind(content)
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(true)
+    }
+
+    /// 本文のハッシュ（SHA-256の16進表記）が一致し、日時が期間内（`from` 以上 `to` 未満、UTC）の
+    /// 公開済みのエントリを探します（トランザクション内）。
+    /// インポート時の重複検出に使います。下書きは対象にしません。
+    pub async fn find_duplicate_entry_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        content_hash: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> Result<Option<i32>> {
+        sqlx::query_scalar::<_, i32>(concat!(
+            "SELECT id FROM entry WHERE content_hash = ? AND datetime >= ? AND datetime < ? AND ",
+            published!("entry"),
+            " LIMIT 1"
+        ))
+        .bind(content_hash)
+        .bind(from)
+        .bind(to)
+        .fetch_optional(&mut **tx)
+        .await
+    }
+
+    /// 指定された期間（`from` 以上 `to` 未満、UTC）のエントリを古い順に取得します。
+    /// `tag_id` を指定した場合は、そのタグが付いたエントリだけを取得します。
+    pub async fn find_entries_between(
+        &self,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        tag_id: Option<i32>,
+    ) -> Result<Vec<Entry>> {
+        let mut query = QueryBuilder::<MySql>::new(concat!("SELECT ", entry_columns!("e"), " FROM entry e"));
+        if let Some(tag_id) = tag_id {
+            query.push(" JOIN entry_tag et ON e.id = et.entry_id AND et.tag_id = ");
+            query.push_bind(tag_id);
+        }
+        query.push(concat!(" WHERE ", visible!("e"), " AND e.datetime >= "));
+        query.push_bind(from);
+        query.push(" AND e.datetime < ");
+        query.push_bind(to);
+        query.push(" ORDER BY e.datetime, e.id");
+
+        query.build_query_as::<Entry>().fetch_all(self.pool).await
+    }
+
+    /// 開封日時を過ぎ、まだ開封を通知していないタイムカプセルのIDを開封日時の順に取得します。
+    pub async fn find_opened_capsule_ids(&self, now: NaiveDateTime, limit: u32) -> Result<Vec<i32>> {
+        sqlx::query_scalar::<_, i32>(concat!(
+            r#"
+            SELECT id FROM entry
+            WHERE unlock_at IS NOT NULL AND unlock_at <= ? AND NOT unlock_notified AND "#,
+            visible!("entry"),
+            r#"
+            ORDER BY unlock_at, id
+            LIMIT ?
+            "#,
+        ))
+        .bind(now)
+        .bind(limit)
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// タイムカプセルの開封を通知済みにします（トランザクション内）。
+    /// 戻り値は通知済みにできたかどうかです。複数のプロセスが同時に処理しても、通知済みにできるのは1つだけです。
+    pub async fn mark_unlock_notified_tx(tx: &mut sqlx::Transaction<'_, sqlx::MySql>, id: i32) -> Result<bool> {
+        let result = sqlx::query("UPDATE entry SET unlock_notified = TRUE WHERE id = ? AND NOT unlock_notified")
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 期間（`from` 以上 `to` 未満、UTC）にエントリがあるかどうかを確認します。
+    pub async fn entry_exists_between(&self, from: NaiveDateTime, to: NaiveDateTime) -> Result<bool> {
+        sqlx::query_scalar::<_, i64>(
+            concat!(
+                "SELECT CAST(EXISTS (SELECT 1 FROM entry WHERE datetime >= ? AND datetime < ? AND ",
+                visible!("entry"),
+                ") AS SIGNED)"
+            ),
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_one(self.pool)
+        .await
+        .map(|exists| exists > 0)
+    }
+
+    /// 複数の期間（それぞれ `from` 以上 `to` 未満、UTC）のいずれかに含まれるエントリを新しい順に取得します。
+    pub async fn find_entries_in_ranges(&self, ranges: &[(NaiveDateTime, NaiveDateTime)]) -> Result<Vec<Entry>> {
+        if ranges.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // 期間ごとの条件にすることで datetime のインデックスを使える
+        let mut query = QueryBuilder::<MySql>::new(concat!(
+            "SELECT ", entry_columns!("entry"), " FROM entry WHERE ", visible!("entry"), " AND ("
+        ));
+        for (i, (from, to)) in ranges.iter().enumerate() {
+            if i > 0 {
+                query.push(" OR ");
+            }
+            query.push("(datetime >= ");
+            query.push_bind(*from);
+            query.push(" AND datetime < ");
+            query.push_bind(*to);
+            query.push(")");
+        }
+        query.push(")");
+        query.push(" ORDER BY datetime DESC, id DESC");
+
+        query.build_query_as::<Entry>().fetch_all(self.pool).await
+    }
+
+    /// 最も古いエントリの日時を取得します。
+    pub async fn find_first_entry_datetime(&self) -> Result<Option<NaiveDateTime>> {
+        sqlx::query_scalar::<_, Option<NaiveDateTime>>(concat!("SELECT MIN(datetime) FROM entry WHERE ", visible!("entry")))
+            .fetch_one(self.pool)
+            .await
+    }
+
+    /// エントリが存在する年月を新しい順に取得します。
+    /// 年月は `timezone`（`+09:00` のような形式）での日時で判定します。
+    pub async fn find_entry_months(&self, timezone: &str) -> Result<Vec<(i64, i64)>> {
+        sqlx::query_as::<_, (i64, i64)>(concat!(
+            r#"
+            SELECT DISTINCT
+                CAST(YEAR(CONVERT_TZ(datetime, '+00:00', ?)) AS SIGNED) AS year,
+                CAST(MONTH(CONVERT_TZ(datetime, '+00:00', ?)) AS SIGNED) AS month
+            FROM entry
+            WHERE "#,
+            visible!("entry"),
+            r#"
+            ORDER BY year DESC, month DESC
+            "#,
+        ))
+        .bind(timezone)
+        .bind(timezone)
+        .fetch_all(self.pool)
+        .await
+    }
+
     /// 指定されたタグIDに関連付けられたエントリを取得します。
     pub async fn find_entries_by_tag(
         &self,
@@ -62,16 +363,19 @@ impl<'a> DiaryRepository<'a> {
         limit: u32,
         offset: u32,
     ) -> Result<Vec<Entry>> {
-        sqlx::query_as::<_, Entry>(
+        sqlx::query_as::<_, Entry>(concat!(
+            "SELECT ",
+            entry_columns!("e"),
             r#"
-            SELECT e.id, e.content, e.datetime
             FROM entry e
             JOIN entry_tag et ON e.id = et.entry_id
-            WHERE et.tag_id = ?
+            WHERE et.tag_id = ? AND "#,
+            visible!("e"),
+            r#"
             ORDER BY e.datetime DESC
             LIMIT ? OFFSET ?
             "#,
-        )
+        ))
         .bind(tag_id)
         .bind(limit)
         .bind(offset)
@@ -81,16 +385,316 @@ impl<'a> DiaryRepository<'a> {
 
     /// 指定されたタグIDに関連付けられたエントリの総数を取得します。
     pub async fn count_entries_by_tag(&self, tag_id: i32) -> Result<i64> {
-        sqlx::query_scalar::<_, i64>(
+        sqlx::query_scalar::<_, i64>(concat!(
             r#"
             SELECT COUNT(*)
             FROM entry e
             JOIN entry_tag et ON e.id = et.entry_id
-            WHERE et.tag_id = ?
-            "#,
-        )
+            WHERE et.tag_id = ? AND "#,
+            visible!("e"),
+        ))
         .bind(tag_id)
         .fetch_one(self.pool)
         .await
     }
+
+    /// エントリの件数と、本文の文字数の合計を取得します。
+    pub async fn sum_entry_lengths(&self) -> Result<(i64, i64)> {
+        sqlx::query_as::<_, (i64, i64)>(
+            concat!(
+                "SELECT COUNT(*), CAST(COALESCE(SUM(CHAR_LENGTH(content)), 0) AS SIGNED) FROM entry WHERE ",
+                visible!("entry")
+            ),
+        )
+        .fetch_one(self.pool)
+        .await
+    }
+
+    /// エントリのある日付（日記のタイムゾーン）を古い順に取得します。
+    pub async fn find_entry_dates(&self, timezone: &str) -> Result<Vec<NaiveDate>> {
+        sqlx::query_scalar::<_, NaiveDate>(concat!(
+            r#"
+            SELECT DISTINCT DATE(CONVERT_TZ(datetime, '+00:00', ?)) AS day
+            FROM entry
+            WHERE "#,
+            visible!("entry"),
+            r#"
+            ORDER BY day
+            "#,
+        ))
+        .bind(timezone)
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// 日記のタイムゾーンで期間ごとに区切ったエントリ数を、期間の古い順に取得します。
+    /// `format` は期間の名前を作る `DATE_FORMAT` の書式で、名前の順が期間の順になるものを指定します。
+    /// エントリのない期間は含みません。
+    pub async fn count_entries_by_period(&self, timezone: &str, format: &str) -> Result<Vec<(String, i64)>> {
+        sqlx::query_as::<_, (String, i64)>(concat!(
+            r#"
+            SELECT DATE_FORMAT(CONVERT_TZ(datetime, '+00:00', ?), ?) AS period, COUNT(*)
+            FROM entry
+            WHERE "#,
+            visible!("entry"),
+            r#"
+            GROUP BY period
+            ORDER BY period
+            "#,
+        ))
+        .bind(timezone)
+        .bind(format)
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// 曜日（0 が月曜日、日記のタイムゾーン）ごとのエントリ数を取得します。
+    pub async fn count_entries_by_weekday(&self, timezone: &str) -> Result<Vec<(i64, i64)>> {
+        sqlx::query_as::<_, (i64, i64)>(concat!(
+            r#"
+            SELECT CAST(WEEKDAY(CONVERT_TZ(datetime, '+00:00', ?)) AS SIGNED) AS weekday, COUNT(*)
+            FROM entry
+            WHERE "#,
+            visible!("entry"),
+            r#"
+            GROUP BY weekday
+            "#,
+        ))
+        .bind(timezone)
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// 時（0〜23、日記のタイムゾーン）ごとのエントリ数を取得します。
+    pub async fn count_entries_by_hour(&self, timezone: &str) -> Result<Vec<(i64, i64)>> {
+        sqlx::query_as::<_, (i64, i64)>(concat!(
+            r#"
+            SELECT CAST(HOUR(CONVERT_TZ(datetime, '+00:00', ?)) AS SIGNED) AS hour, COUNT(*)
+            FROM entry
+            WHERE "#,
+            visible!("entry"),
+            r#"
+            GROUP BY hour
+            "#,
+        ))
+        .bind(timezone)
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// 付いているエントリが多い順にタグと件数を取得します。
+    pub async fn count_top_tags(&self, limit: u32) -> Result<Vec<(i32, String, i64)>> {
+        sqlx::query_as::<_, (i32, String, i64)>(concat!(
+            r#"
+            SELECT t.id, t.name, COUNT(*) AS count
+            FROM entry_tag et
+            JOIN tag t ON t.id = et.tag_id
+            JOIN entry e ON e.id = et.entry_id AND "#,
+            visible!("e"),
+            r#"
+            GROUP BY t.id, t.name
+            ORDER BY count DESC, t.name
+            LIMIT ?
+            "#,
+        ))
+        .bind(limit)
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// 指定された期間（`from` 以上 `to` 未満、UTC）のエントリ数と本文の文字数を、日記のタイムゾーンの日付ごとに取得します。
+    /// `tag_id` を指定した場合は、そのタグが付いたエントリだけを数えます。
+    pub async fn count_entries_by_date(
+        &self,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        timezone: &str,
+        tag_id: Option<i32>,
+    ) -> Result<Vec<(NaiveDate, i64, i64)>> {
+        let mut query = QueryBuilder::<MySql>::new("SELECT DATE(CONVERT_TZ(e.datetime, '+00:00', ");
+        query.push_bind(timezone);
+        query.push(
+            ")) AS day, COUNT(*), CAST(COALESCE(SUM(CHAR_LENGTH(e.content)), 0) AS SIGNED) FROM entry e",
+        );
+        if let Some(tag_id) = tag_id {
+            query.push(" JOIN entry_tag et ON et.entry_id = e.id AND et.tag_id = ");
+            query.push_bind(tag_id);
+        }
+        // 範囲の条件は datetime のインデックスを使えるよう、列を加工せずに比べる
+        query.push(concat!(" WHERE ", visible!("e"), " AND e.datetime >= "));
+        query.push_bind(from);
+        query.push(" AND e.datetime < ");
+        query.push_bind(to);
+        query.push(" GROUP BY day ORDER BY day");
+
+        query.build_query_as().fetch_all(self.pool).await
+    }
+
+    /// 条件に合うエントリのIDの最小値と最大値を取得します。該当するエントリがない場合は `None` です。
+    pub async fn find_random_candidate_id_range(&self, filter: &RandomEntryFilter) -> Result<Option<(i32, i32)>> {
+        let mut query = QueryBuilder::<MySql>::new("SELECT MIN(e.id), MAX(e.id) FROM entry e");
+        filter.push_conditions(&mut query);
+        let (min, max) = query
+            .build_query_as::<(Option<i32>, Option<i32>)>()
+            .fetch_one(self.pool)
+            .await?;
+        Ok(min.zip(max))
+    }
+
+    /// 条件に合うエントリのうち、IDが `from_id` 以上で最も小さいエントリを取得します。
+    /// 主キーの範囲で探すため、件数を数えて読み飛ばす（`OFFSET`）よりも速く、全件の並べ替えも起きません。
+    pub async fn find_random_candidate(&self, filter: &RandomEntryFilter, from_id: i32) -> Result<Option<Entry>> {
+        let mut query = QueryBuilder::<MySql>::new(concat!("SELECT ", entry_columns!("e"), " FROM entry e"));
+        filter.push_conditions(&mut query);
+        query.push(" AND e.id >= ");
+        query.push_bind(from_id);
+        query.push(" ORDER BY e.id LIMIT 1");
+        query.build_query_as::<Entry>().fetch_optional(self.pool).await
+    }
+
+    /// エントリをランダム表示した日時を記録します。
+    pub async fn record_entry_view(&self, entry_id: i32, viewed_at: NaiveDateTime) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO entry_view (entry_id, viewed_at) VALUES (?, ?)
+            ON DUPLICATE KEY UPDATE viewed_at = VALUES(viewed_at)
+            "#,
+        )
+        .bind(entry_id)
+        .bind(viewed_at)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// `before` より前の表示の記録を削除します。
+    pub async fn delete_entry_views_before(&self, before: NaiveDateTime) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM entry_view WHERE viewed_at < ?")
+            .bind(before)
+            .execute(self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// 公開日時前の予約投稿を公開日時の順に取得します。
+    pub async fn find_scheduled_entries(&self) -> Result<Vec<Entry>> {
+        sqlx::query_as::<_, Entry>(concat!(
+            "SELECT ", entry_columns!("entry"), " FROM entry WHERE ", published!("entry"),
+            " AND publish_at > UTC_TIMESTAMP() ORDER BY publish_at, id"
+        ))
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// 公開日時を過ぎ、まだ公開を通知していない予約投稿のIDを公開日時の順に取得します。
+    pub async fn find_due_scheduled_ids(&self, limit: u32) -> Result<Vec<i32>> {
+        sqlx::query_scalar::<_, i32>(concat!(
+            r#"
+            SELECT id FROM entry
+            WHERE publish_at IS NOT NULL AND NOT publish_notified AND "#,
+            visible!("entry"),
+            r#"
+            ORDER BY publish_at, id
+            LIMIT ?
+            "#,
+        ))
+        .bind(limit)
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// 予約投稿の公開を通知済みにします（トランザクション内）。
+    /// 戻り値は通知済みにできたかどうかです（`mark_unlock_notified_tx` と同じく、通知済みにできるのは1つのプロセスだけです）。
+    pub async fn mark_publish_notified_tx(tx: &mut sqlx::Transaction<'_, sqlx::MySql>, id: i32) -> Result<bool> {
+        let result = sqlx::query("UPDATE entry SET publish_notified = TRUE WHERE id = ? AND NOT publish_notified")
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 下書きを最後に保存した日時の新しい順に取得します。
+    pub async fn find_drafts(&self) -> Result<Vec<Entry>> {
+        sqlx::query_as::<_, Entry>(concat!(
+            "SELECT ", entry_columns!("entry"), " FROM entry WHERE status = 'draft' ORDER BY datetime DESC, id DESC"
+        ))
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// キーで下書きを取得します。
+    pub async fn find_draft(&self, key: &str) -> Result<Option<Entry>> {
+        sqlx::query_as::<_, Entry>(concat!(
+            "SELECT ", entry_columns!("entry"), " FROM entry WHERE draft_key = ? AND status = 'draft'"
+        ))
+        .bind(key)
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    /// キーの下書きを作成、またはすでにあれば本文・タグの名前・保存日時を上書きします（トランザクション内）。
+    /// 同じ内容で何度呼んでも結果は同じです。公開済みのエントリは変更しません。
+    /// 戻り値はキーに対応するエントリのIDと状態です。
+    pub async fn upsert_draft_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        key: &str,
+        content: &str,
+        tags: &[String],
+        datetime: NaiveDateTime,
+    ) -> Result<(i32, EntryStatus)> {
+        sqlx::query(
+            r#"
+            INSERT INTO entry (content, datetime, status, draft_key, draft_tags) VALUES (?, ?, 'draft', ?, ?)
+            ON DUPLICATE KEY UPDATE
+                content = IF(status = 'draft', VALUES(content), content),
+                draft_tags = IF(status = 'draft', VALUES(draft_tags), draft_tags),
+                datetime = IF(status = 'draft', VALUES(datetime), datetime)
+            "#,
+        )
+        .bind(content)
+        .bind(datetime)
+        .bind(key)
+        .bind(Json(tags))
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query_as::<_, (i32, EntryStatus)>("SELECT id, status FROM entry WHERE draft_key = ?")
+            .bind(key)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    /// キーの下書きを公開し、日時を公開した日時にします（トランザクション内）。
+    /// 戻り値は公開したエントリのIDと、下書きに付けていたタグの名前です。下書きがない場合は `None` を返します。
+    pub async fn publish_draft_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        key: &str,
+        datetime: NaiveDateTime,
+    ) -> Result<Option<(i32, Vec<String>)>> {
+        let Some((id, tags)) = sqlx::query_as::<_, (i32, Option<Json<Vec<String>>>)>(
+            "SELECT id, draft_tags FROM entry WHERE draft_key = ? AND status = 'draft' FOR UPDATE",
+        )
+        .bind(key)
+        .fetch_optional(&mut **tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+        sqlx::query("UPDATE entry SET status = 'published', datetime = ?, draft_tags = NULL WHERE id = ?")
+            .bind(datetime)
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(Some((id, tags.map(|tags| tags.0).unwrap_or_default())))
+    }
+
+    /// キーの下書きを削除します。公開済みのエントリは削除しません。
+    /// 戻り値は削除対象が存在したかどうかです。
+    pub async fn delete_draft(&self, key: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM entry WHERE draft_key = ? AND status = 'draft'")
+            .bind(key)
+            .execute(self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
 }
@@ -1,2 +1,10 @@
+pub mod attachment_repository;
+pub mod backup_repository;
+pub mod capture_token_repository;
+pub mod comment_repository;
 pub mod diary_repository;
+pub mod prompt_repository;
+pub mod reaction_repository;
+pub mod reminder_repository;
 pub mod tag_repository;
+pub mod webhook_repository;
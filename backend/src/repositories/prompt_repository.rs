@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use sqlx::{Executor, MySql, MySqlPool, QueryBuilder, Result, Transaction};
+use crate::models::prompt::Prompt;
+
+pub struct PromptRepository<'a> {
+    pool: &'a MySqlPool,
+}
+
+impl<'a> PromptRepository<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// お題を登録順に取得します。`language` を指定した場合はその言語のお題だけを取得します。
+    pub async fn find_prompts(&self, language: Option<&str>) -> Result<Vec<Prompt>> {
+        let mut query = QueryBuilder::<MySql>::new("SELECT id, text, language, enabled, datetime FROM prompt");
+        if let Some(language) = language {
+            query.push(" WHERE language = ");
+            query.push_bind(language);
+        }
+        query.push(" ORDER BY id");
+        query.build_query_as::<Prompt>().fetch_all(self.pool).await
+    }
+
+    /// IDでお題を取得します。
+    pub async fn find_prompt(&self, id: i32) -> Result<Option<Prompt>> {
+        sqlx::query_as::<_, Prompt>("SELECT id, text, language, enabled, datetime FROM prompt WHERE id = ?")
+            .bind(id)
+            .fetch_optional(self.pool)
+            .await
+    }
+
+    /// 新しいお題を登録します。
+    /// 戻り値は登録されたお題のIDです。
+    pub async fn create_prompt(&self, text: &str, language: &str, datetime: NaiveDateTime) -> Result<i32> {
+        let result = sqlx::query("INSERT INTO prompt (text, language, enabled, datetime) VALUES (?, ?, TRUE, ?)")
+            .bind(text)
+            .bind(language)
+            .bind(datetime)
+            .execute(self.pool)
+            .await?;
+        Ok(result.last_insert_id() as i32)
+    }
+
+    /// お題が1件も登録されていない場合に限り、指定したお題を登録します。
+    /// 戻り値は登録したお題の数です。
+    pub async fn insert_prompts_if_empty(&self, prompts: &[(&str, &str)], datetime: NaiveDateTime) -> Result<u64> {
+        if prompts.is_empty() {
+            return Ok(0);
+        }
+
+        // 空かどうかの確認と登録を1つの文で行う
+        let mut query = QueryBuilder::<MySql>::new(
+            "INSERT INTO prompt (text, language, enabled, datetime) SELECT t.text, t.language, TRUE, ",
+        );
+        query.push_bind(datetime);
+        query.push(" FROM (");
+        for (index, (text, language)) in prompts.iter().enumerate() {
+            query.push(if index == 0 { "SELECT " } else { " UNION ALL SELECT " });
+            query.push_bind(*text);
+            query.push(" AS text, ");
+            query.push_bind(*language);
+            query.push(" AS language");
+        }
+        query.push(") AS t WHERE NOT EXISTS (SELECT 1 FROM prompt)");
+        Ok(query.build().execute(self.pool).await?.rows_affected())
+    }
+
+    /// お題の内容を保存します。
+    /// 戻り値は更新対象が存在したかどうかです。
+    pub async fn update_prompt(&self, prompt: &Prompt) -> Result<bool> {
+        let result = sqlx::query("UPDATE prompt SET text = ?, language = ?, enabled = ? WHERE id = ?")
+            .bind(&prompt.text)
+            .bind(&prompt.language)
+            .bind(prompt.enabled)
+            .bind(prompt.id)
+            .execute(self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// お題を削除します。日ごとのお題の記録とエントリとの関連も削除されます。
+    /// 戻り値は削除対象が存在したかどうかです。
+    pub async fn delete_prompt(&self, id: i32) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM prompt WHERE id = ?")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 指定した日（と言語）に選んだお題を取得します。
+    pub async fn find_daily_prompt(&self, date: NaiveDate, language: &str) -> Result<Option<Prompt>> {
+        sqlx::query_as::<_, Prompt>(
+            r#"
+            SELECT p.id, p.text, p.language, p.enabled, p.datetime
+            FROM daily_prompt d
+            JOIN prompt p ON p.id = d.prompt_id
+            WHERE d.date = ? AND d.language = ?
+            "#,
+        )
+        .bind(date)
+        .bind(language)
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    /// 今日のお題の候補となる有効なお題のIDをID順に取得します。
+    /// `not_chosen_since` を指定した場合は、その日以降に選んだお題を除きます。
+    pub async fn find_candidate_ids(
+        &self,
+        language: Option<&str>,
+        not_chosen_since: Option<NaiveDate>,
+    ) -> Result<Vec<i32>> {
+        let mut query = QueryBuilder::<MySql>::new("SELECT p.id FROM prompt p WHERE p.enabled = TRUE");
+        if let Some(language) = language {
+            query.push(" AND p.language = ");
+            query.push_bind(language);
+        }
+        if let Some(since) = not_chosen_since {
+            query.push(" AND NOT EXISTS (SELECT 1 FROM daily_prompt d WHERE d.prompt_id = p.id AND d.date >= ");
+            query.push_bind(since);
+            query.push(")");
+        }
+        query.push(" ORDER BY p.id");
+        query.build_query_scalar::<i32>().fetch_all(self.pool).await
+    }
+
+    /// 指定した日（と言語）のお題を記録します。すでに記録がある場合は何もしません。
+    pub async fn insert_daily_prompt(&self, date: NaiveDate, language: &str, prompt_id: i32) -> Result<()> {
+        sqlx::query("INSERT IGNORE INTO daily_prompt (date, language, prompt_id) VALUES (?, ?, ?)")
+            .bind(date)
+            .bind(language)
+            .bind(prompt_id)
+            .execute(self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// エントリが答えたお題を記録します（トランザクション内）。
+    pub async fn associate_entry_with_prompt_tx(
+        tx: &mut Transaction<'_, MySql>,
+        entry_id: i32,
+        prompt_id: i32,
+    ) -> Result<()> {
+        sqlx::query("INSERT INTO entry_prompt (entry_id, prompt_id) VALUES (?, ?)")
+            .bind(entry_id)
+            .bind(prompt_id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    /// 複数のエントリが答えたお題のIDをまとめて取得します。
+    pub async fn find_prompt_ids_for_entries<'e, E>(&self, executor: E, entry_ids: &[i32]) -> Result<HashMap<i32, i32>>
+    where
+        E: Executor<'e, Database = MySql>,
+    {
+        if entry_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut query = QueryBuilder::<MySql>::new("SELECT entry_id, prompt_id FROM entry_prompt WHERE entry_id IN (");
+        let mut separated = query.separated(", ");
+        for id in entry_ids {
+            separated.push_bind(*id);
+        }
+        separated.push_unseparated(")");
+
+        let rows: Vec<(i32, i32)> = query.build_query_as().fetch_all(executor).await?;
+        Ok(rows.into_iter().collect())
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use sqlx::{Executor, MySql, MySqlPool, QueryBuilder, Result};
+use crate::models::reaction::{Reaction, ReactionCount};
+
+pub struct ReactionRepository<'a> {
+    pool: &'a MySqlPool,
+}
+
+impl<'a> ReactionRepository<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// 指定されたエントリのリアクションを古い順に取得します。
+    pub async fn find_reactions_for_entry(&self, entry_id: i32) -> Result<Vec<Reaction>> {
+        sqlx::query_as::<_, Reaction>(
+            r#"
+            SELECT id, entry_id, emoji, datetime
+            FROM reaction
+            WHERE entry_id = ?
+            ORDER BY datetime, id
+            "#,
+        )
+        .bind(entry_id)
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// IDでリアクションを取得します。
+    pub async fn find_reaction(&self, id: i32) -> Result<Option<Reaction>> {
+        sqlx::query_as::<_, Reaction>(
+            "SELECT id, entry_id, emoji, datetime FROM reaction WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    /// 新しいリアクションを作成します。
+    /// 戻り値は作成されたリアクションのIDです。
+    pub async fn create_reaction(&self, entry_id: i32, emoji: &str) -> Result<i32> {
+        let now = chrono::Utc::now();
+        let result = sqlx::query("INSERT INTO reaction (entry_id, emoji, datetime) VALUES (?, ?, ?)")
+            .bind(entry_id)
+            .bind(emoji)
+            .bind(now)
+            .execute(self.pool)
+            .await?;
+        Ok(result.last_insert_id() as i32)
+    }
+
+    /// リアクションを削除します。
+    /// 戻り値は削除対象が存在したかどうかです。
+    pub async fn delete_reaction(&self, id: i32) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM reaction WHERE id = ?")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 複数エントリの絵文字ごとのリアクション数をまとめて取得します。
+    /// リアクションのないエントリはマップに含まれません。
+    pub async fn count_reactions_for_entries<'e, E>(
+        &self,
+        executor: E,
+        entry_ids: &[i32],
+    ) -> Result<HashMap<i32, Vec<ReactionCount>>>
+    where
+        E: Executor<'e, Database = MySql>,
+    {
+        if entry_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut query = QueryBuilder::<MySql>::new(
+            "SELECT entry_id, emoji, COUNT(*) FROM reaction WHERE entry_id IN (",
+        );
+        let mut separated = query.separated(", ");
+        for id in entry_ids {
+            separated.push_bind(*id);
+        }
+        separated.push_unseparated(") GROUP BY entry_id, emoji ORDER BY MIN(id)");
+
+        let rows: Vec<(i32, String, i64)> = query.build_query_as().fetch_all(executor).await?;
+        let mut counts: HashMap<i32, Vec<ReactionCount>> = HashMap::new();
+        for (entry_id, emoji, count) in rows {
+            counts
+                .entry(entry_id)
+                .or_default()
+                .push(ReactionCount { emoji, count });
+        }
+        Ok(counts)
+    }
+}
@@ -0,0 +1,141 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use sqlx::{MySqlPool, Result};
+use crate::models::reminder::Reminder;
+
+pub struct ReminderRepository<'a> {
+    pool: &'a MySqlPool,
+}
+
+impl<'a> ReminderRepository<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// すべてのリマインダーを登録順に取得します。
+    pub async fn find_reminders(&self) -> Result<Vec<Reminder>> {
+        sqlx::query_as::<_, Reminder>(
+            r#"
+            SELECT id, name, remind_at, channel, webhook_id, email, enabled,
+                   last_checked_on, last_sent_at, last_error, datetime
+            FROM reminder
+            ORDER BY id
+            "#,
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// IDでリマインダーを取得します。
+    pub async fn find_reminder(&self, id: i32) -> Result<Option<Reminder>> {
+        sqlx::query_as::<_, Reminder>(
+            r#"
+            SELECT id, name, remind_at, channel, webhook_id, email, enabled,
+                   last_checked_on, last_sent_at, last_error, datetime
+            FROM reminder
+            WHERE id = ?
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    /// 新しいリマインダーを登録します。`id` と、`last_checked_on` 以外の状態の項目は使いません。
+    /// 戻り値は登録されたリマインダーのIDです。
+    pub async fn create_reminder(&self, reminder: &Reminder) -> Result<i32> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO reminder (name, remind_at, channel, webhook_id, email, enabled, last_checked_on, datetime)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&reminder.name)
+        .bind(reminder.remind_at)
+        .bind(reminder.channel)
+        .bind(reminder.webhook_id)
+        .bind(&reminder.email)
+        .bind(reminder.enabled)
+        .bind(reminder.last_checked_on)
+        .bind(reminder.datetime)
+        .execute(self.pool)
+        .await?;
+        Ok(result.last_insert_id() as i32)
+    }
+
+    /// リマインダーの設定を保存します。`last_checked_on` は今までより後の日付の場合だけ更新します。
+    /// 戻り値は更新対象が存在したかどうかです。
+    pub async fn update_reminder(&self, reminder: &Reminder) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE reminder
+            SET name = ?, remind_at = ?, channel = ?, webhook_id = ?, email = ?, enabled = ?,
+                last_checked_on = GREATEST(COALESCE(last_checked_on, ?), COALESCE(?, last_checked_on))
+            WHERE id = ?
+            "#,
+        )
+        .bind(&reminder.name)
+        .bind(reminder.remind_at)
+        .bind(reminder.channel)
+        .bind(reminder.webhook_id)
+        .bind(&reminder.email)
+        .bind(reminder.enabled)
+        .bind(reminder.last_checked_on)
+        .bind(reminder.last_checked_on)
+        .bind(reminder.id)
+        .execute(self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// リマインダーを削除します。
+    /// 戻り値は削除対象が存在したかどうかです。
+    pub async fn delete_reminder(&self, id: i32) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM reminder WHERE id = ?")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 通知する時刻を過ぎ、`today` にまだ確認していない有効なリマインダーを取得します。
+    pub async fn find_due_reminders(&self, today: NaiveDate, now: NaiveTime) -> Result<Vec<Reminder>> {
+        sqlx::query_as::<_, Reminder>(
+            r#"
+            SELECT id, name, remind_at, channel, webhook_id, email, enabled,
+                   last_checked_on, last_sent_at, last_error, datetime
+            FROM reminder
+            WHERE enabled AND remind_at <= ? AND (last_checked_on IS NULL OR last_checked_on < ?)
+            ORDER BY remind_at, id
+            "#,
+        )
+        .bind(now)
+        .bind(today)
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// リマインダーを `today` に確認済みにします。
+    /// 戻り値は確認済みにできたかどうかです。複数のプロセスが同時に確認しても、確認済みにできるのは1つだけです。
+    pub async fn claim_reminder(&self, id: i32, today: NaiveDate) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE reminder SET last_checked_on = ? WHERE id = ? AND (last_checked_on IS NULL OR last_checked_on < ?)",
+        )
+        .bind(today)
+        .bind(id)
+        .bind(today)
+        .execute(self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 通知の結果を記録します。成功した場合は `error` に `None` を渡します。
+    pub async fn record_result(&self, id: i32, sent_at: NaiveDateTime, error: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE reminder SET last_sent_at = ?, last_error = ? WHERE id = ?")
+            .bind(sent_at)
+            .bind(error)
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(())
+    }
+}
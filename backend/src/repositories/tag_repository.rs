@@ -1,4 +1,6 @@
-use sqlx::{MySqlPool, Result, Transaction, MySql};
+use std::collections::HashMap;
+
+use sqlx::{Executor, MySqlPool, QueryBuilder, Result, Transaction, MySql};
 use crate::models::tag::Tag;
 
 pub struct TagRepository<'a> {
@@ -15,42 +17,58 @@ impl<'a> TagRepository<'a> {
         Self { _marker: std::marker::PhantomData }
     }
 
-    /// 指定されたエントリIDに関連付けられたタグを取得します。
-    pub async fn find_tags_for_entry(&self, pool: &MySqlPool, entry_id: i32) -> Result<Vec<Tag>> {
-        sqlx::query_as::<_, Tag>(
+    /// 複数エントリに関連付けられたタグをまとめて取得します。
+    /// タグのないエントリはマップに含まれません。
+    pub async fn find_tags_for_entries<'e, E>(&self, executor: E, entry_ids: &[i32]) -> Result<HashMap<i32, Vec<Tag>>>
+    where
+        E: Executor<'e, Database = MySql>,
+    {
+        if entry_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut query = QueryBuilder::<MySql>::new(
             r#"
-            SELECT t.id, t.name
+            SELECT et.entry_id, t.id, t.name
             FROM tag t
             JOIN entry_tag et ON t.id = et.tag_id
-            WHERE et.entry_id = ?
-            ORDER BY t.name
+            WHERE et.entry_id IN (
             "#,
-        )
-        .bind(entry_id)
-        .fetch_all(pool) // ここではpoolを直接使う
-        .await
+        );
+        let mut separated = query.separated(", ");
+        for id in entry_ids {
+            separated.push_bind(*id);
+        }
+        separated.push_unseparated(") ORDER BY t.name");
+
+        let rows: Vec<(i32, i32, String)> = query.build_query_as().fetch_all(executor).await?;
+        let mut tags: HashMap<i32, Vec<Tag>> = HashMap::new();
+        for (entry_id, id, name) in rows {
+            tags.entry(entry_id).or_default().push(Tag { id, name });
+        }
+        Ok(tags)
     }
 
     /// タグ名でタグを検索し、存在しない場合は作成します（トランザクション内）。
-    /// 戻り値はタグのIDです。
+    /// 戻り値はタグのIDと、新しく作成したかどうかです。
     pub async fn find_or_create_tag_tx(
         tx: &mut Transaction<'_, MySql>,
         tag_name: &str,
-    ) -> Result<i32> {
+    ) -> Result<(i32, bool)> {
         // タグが存在するか確認
         let tag_id = match sqlx::query_scalar::<_, i32>("SELECT id FROM tag WHERE name = ?")
             .bind(tag_name)
             .fetch_optional(&mut **tx) // Dereference tx
             .await?
         {
-            Some(id) => id, // タグが存在する場合
+            Some(id) => (id, false), // タグが存在する場合
             None => {
                 // タグが存在しない場合は作成
                 let result = sqlx::query("INSERT INTO tag (name) VALUES (?)")
                     .bind(tag_name)
                     .execute(&mut **tx) // Dereference tx
                     .await?;
-                result.last_insert_id() as i32
+                (result.last_insert_id() as i32, true)
             }
         };
         Ok(tag_id)
@@ -70,6 +88,23 @@ impl<'a> TagRepository<'a> {
         Ok(())
     }
 
+    /// エントリとタグの関連付けをすべて解除します（トランザクション内）。
+    pub async fn remove_entry_tags_tx(tx: &mut Transaction<'_, MySql>, entry_id: i32) -> Result<()> {
+        sqlx::query("DELETE FROM entry_tag WHERE entry_id = ?")
+            .bind(entry_id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    /// IDでタグを取得します。
+    pub async fn find_tag(&self, pool: &MySqlPool, id: i32) -> Result<Option<Tag>> {
+        sqlx::query_as::<_, Tag>("SELECT id, name FROM tag WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
     /// すべてのタグを取得します。
     pub async fn find_all_tags(&self, pool: &MySqlPool) -> Result<Vec<Tag>> {
         sqlx::query_as::<_, Tag>("SELECT id, name FROM tag ORDER BY name")
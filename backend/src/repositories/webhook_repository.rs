@@ -0,0 +1,225 @@
+use chrono::NaiveDateTime;
+use sqlx::{types::Json, MySql, MySqlPool, QueryBuilder, Result, Transaction};
+use crate::models::webhook::{DeliveryStatus, PendingDelivery, Webhook, WebhookDelivery};
+
+/// 1回の送信の結果
+pub struct DeliveryAttempt<'a> {
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub attempted_at: NaiveDateTime,
+    pub next_attempt_at: NaiveDateTime,
+    pub response_status: Option<i32>,
+    pub error: Option<&'a str>,
+}
+
+pub struct WebhookRepository<'a> {
+    pool: &'a MySqlPool,
+}
+
+impl<'a> WebhookRepository<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// すべてのWebhookを登録順に取得します。
+    pub async fn find_webhooks(&self) -> Result<Vec<Webhook>> {
+        sqlx::query_as::<_, Webhook>(
+            "SELECT id, url, secret, event_types, enabled, datetime FROM webhook ORDER BY id",
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// IDでWebhookを取得します。
+    pub async fn find_webhook(&self, id: i32) -> Result<Option<Webhook>> {
+        sqlx::query_as::<_, Webhook>(
+            "SELECT id, url, secret, event_types, enabled, datetime FROM webhook WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    /// 新しいWebhookを登録します。
+    /// 戻り値は登録されたWebhookのIDです。
+    pub async fn create_webhook(&self, url: &str, secret: &str, event_types: &[String]) -> Result<i32> {
+        let now = chrono::Utc::now().naive_utc();
+        let result = sqlx::query(
+            "INSERT INTO webhook (url, secret, event_types, enabled, datetime) VALUES (?, ?, ?, TRUE, ?)",
+        )
+        .bind(url)
+        .bind(secret)
+        .bind(Json(event_types))
+        .bind(now)
+        .execute(self.pool)
+        .await?;
+        Ok(result.last_insert_id() as i32)
+    }
+
+    /// Webhookの設定を保存します。
+    pub async fn update_webhook(&self, webhook: &Webhook) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE webhook SET url = ?, secret = ?, event_types = ?, enabled = ? WHERE id = ?",
+        )
+        .bind(&webhook.url)
+        .bind(&webhook.secret)
+        .bind(&webhook.event_types)
+        .bind(webhook.enabled)
+        .bind(webhook.id)
+        .execute(self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Webhookを削除します。配信キューと配信ログは外部キーにより一緒に削除されます。
+    /// 戻り値は削除対象が存在したかどうかです。
+    pub async fn delete_webhook(&self, id: i32) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM webhook WHERE id = ?")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// イベントを購読している有効なWebhookすべてに、配信をキューに追加します（トランザクション内）。
+    /// 戻り値は追加した配信の数です。
+    pub async fn enqueue_deliveries_tx(
+        tx: &mut Transaction<'_, MySql>,
+        event_type: &str,
+        payload: &str,
+        now: NaiveDateTime,
+    ) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO webhook_delivery (webhook_id, event_type, payload, status, attempts, next_attempt_at, datetime)
+            SELECT id, ?, ?, 'pending', 0, ?, ?
+            FROM webhook
+            WHERE enabled AND JSON_CONTAINS(event_types, JSON_QUOTE(?))
+            "#,
+        )
+        .bind(event_type)
+        .bind(payload)
+        .bind(now)
+        .bind(now)
+        .bind(event_type)
+        .execute(&mut **tx)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// 購読しているイベントの種類にかかわらず、指定したWebhookへの配信を追加します（リマインダーの通知など）。
+    /// 戻り値は追加できたかどうかです（Webhookが存在しないか無効の場合は追加しません）。
+    pub async fn enqueue_delivery(
+        &self,
+        webhook_id: i32,
+        event_type: &str,
+        payload: &str,
+        now: NaiveDateTime,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO webhook_delivery (webhook_id, event_type, payload, status, attempts, next_attempt_at, datetime)
+            SELECT id, ?, ?, 'pending', 0, ?, ?
+            FROM webhook
+            WHERE id = ? AND enabled
+            "#,
+        )
+        .bind(event_type)
+        .bind(payload)
+        .bind(now)
+        .bind(now)
+        .bind(webhook_id)
+        .execute(self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 送信日時を過ぎた配信を取り出します。
+    /// 取り出した配信の次の送信日時は `lease_until` に延ばすため、送信中に処理が止まってもその日時に再送されます。
+    /// 複数のプロセスが同時に取り出しても、同じ配信を重複して取り出すことはありません。
+    pub async fn claim_due_deliveries(
+        &self,
+        now: NaiveDateTime,
+        lease_until: NaiveDateTime,
+        limit: u32,
+    ) -> Result<Vec<PendingDelivery>> {
+        let mut tx = self.pool.begin().await?;
+        let deliveries = sqlx::query_as::<_, PendingDelivery>(
+            r#"
+            SELECT d.id, d.event_type, d.payload, d.attempts, w.url, w.secret
+            FROM webhook_delivery d
+            JOIN webhook w ON w.id = d.webhook_id
+            WHERE d.status = 'pending' AND d.next_attempt_at <= ?
+            ORDER BY d.next_attempt_at, d.id
+            LIMIT ?
+            FOR UPDATE OF d SKIP LOCKED
+            "#,
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        if !deliveries.is_empty() {
+            let mut query = QueryBuilder::<MySql>::new("UPDATE webhook_delivery SET next_attempt_at = ");
+            query.push_bind(lease_until).push(" WHERE id IN (");
+            let mut separated = query.separated(", ");
+            for delivery in &deliveries {
+                separated.push_bind(delivery.id);
+            }
+            separated.push_unseparated(")");
+            query.build().execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+
+        Ok(deliveries)
+    }
+
+    /// 送信の結果を記録します。
+    pub async fn record_attempt(&self, id: i32, attempt: &DeliveryAttempt<'_>) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_delivery
+            SET status = ?, attempts = ?, last_attempt_at = ?, next_attempt_at = ?, response_status = ?, last_error = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(attempt.status)
+        .bind(attempt.attempts)
+        .bind(attempt.attempted_at)
+        .bind(attempt.next_attempt_at)
+        .bind(attempt.response_status)
+        .bind(attempt.error)
+        .bind(id)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Webhookの配信を新しい順に取得します。
+    pub async fn find_deliveries(&self, webhook_id: i32, limit: u32, offset: u32) -> Result<Vec<WebhookDelivery>> {
+        sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            SELECT id, webhook_id, event_type, status, attempts, next_attempt_at, last_attempt_at,
+                   response_status, last_error, datetime
+            FROM webhook_delivery
+            WHERE webhook_id = ?
+            ORDER BY id DESC
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// Webhookの配信の総数を取得します。
+    pub async fn count_deliveries(&self, webhook_id: i32) -> Result<i64> {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM webhook_delivery WHERE webhook_id = ?")
+            .bind(webhook_id)
+            .fetch_one(self.pool)
+            .await
+    }
+}
@@ -1,22 +1,110 @@
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    routing::{delete, get, post, put},
     Router,
 };
-use sqlx::MySqlPool;
 
-use crate::handlers::{diary, tag};
+use crate::handlers::{attachment, backup, capture, comment, diary, draft, events, export, feed, import, prompt, reaction, reminder, stats, tag, webhook};
+use crate::state::AppState;
 
-pub fn create_router(pool: MySqlPool) -> Router {
+// バックアップのリストアで受け付けるリクエストボディの上限
+const RESTORE_BODY_LIMIT: usize = 256 * 1024 * 1024;
+// インポートで受け付けるリクエストボディの上限
+const IMPORT_BODY_LIMIT: usize = 256 * 1024 * 1024;
+// 添付ファイルのアップロードで受け付けるリクエストボディの上限
+const ATTACHMENT_BODY_LIMIT: usize = 50 * 1024 * 1024;
+
+pub fn create_router(state: AppState) -> Router {
     Router::new()
         // エントリ関連のエンドポイント
         .route("/api/entries", get(diary::get_entries))
         .route("/api/entries/with-tags", post(diary::create_entry))
         .route("/api/entries", post(diary::create_simple_entry)) // 後方互換性のため
         .route("/api/entries/count", get(diary::get_entry_count))
+        .route("/api/entries/on-this-day", get(diary::get_entries_on_this_day))
+        .route("/api/entries/random", get(diary::get_random_entry))
+        .route("/api/entries/scheduled", get(diary::get_scheduled_entries))
+        .route("/api/stats", get(stats::get_stats))
+        .route("/api/activity", get(stats::get_activity))
+        .route("/api/entries/:id", put(diary::update_entry).delete(diary::delete_entry))
+
+        // 下書き関連のエンドポイント
+        .route("/api/drafts", get(draft::get_drafts))
+        .route(
+            "/api/drafts/:key",
+            get(draft::get_draft).put(draft::save_draft).delete(draft::delete_draft),
+        )
+        .route("/api/drafts/:key/publish", post(draft::publish_draft))
         
         // タグ関連のエンドポイント
         .route("/api/tags", get(tag::get_tags).post(tag::create_tag))
         .route("/api/tags/:id/entries", get(tag::get_entries_by_tag))
+        .route("/api/tags/:id/feed.atom", get(feed::get_tag_atom_feed))
+
+        // コメント関連のエンドポイント
+        .route("/api/entries/:id/comments", get(comment::get_comments).post(comment::create_comment))
+        .route("/api/comments/:id", put(comment::update_comment).delete(comment::delete_comment))
+
+        // リアクション関連のエンドポイント
+        .route("/api/entries/:id/reactions", get(reaction::get_reactions).post(reaction::create_reaction))
+        .route("/api/reactions/:id", delete(reaction::delete_reaction))
+
+        // 添付ファイル関連のエンドポイント
+        .route(
+            "/api/entries/:id/attachments",
+            get(attachment::get_attachments)
+                .post(attachment::upload_attachments)
+                .layer(DefaultBodyLimit::max(ATTACHMENT_BODY_LIMIT)),
+        )
+        .route(
+            "/api/attachments/:id",
+            get(attachment::get_attachment).delete(attachment::delete_attachment),
+        )
+        .route("/api/attachments/:id/thumbnails/:name", get(attachment::get_thumbnail))
+
+        // エクスポート
+        .route("/api/export/markdown", get(export::get_markdown_export))
+        .route("/api/export/movable-type", get(export::get_movable_type_export))
+        .route("/api/export/epub", get(export::get_epub_export))
+
+        // インポート
+        .route(
+            "/api/import/:format",
+            post(import::import_entries).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+
+        // バックアップとリストア
+        .route("/api/backup", get(backup::get_backup))
+        .route(
+            "/api/backup/restore",
+            post(backup::restore_backup).layer(DefaultBodyLimit::max(RESTORE_BODY_LIMIT)),
+        )
+
+        // 変更の通知（Server-Sent Events）
+        .route("/api/events", get(events::get_events))
+
+        // クイック投稿（キャプチャ）
+        .route("/api/capture-tokens", get(capture::get_capture_tokens).post(capture::create_capture_token))
+        .route("/api/capture-tokens/:id", delete(capture::delete_capture_token))
+        .route("/api/capture/:token", post(capture::capture))
+
+        // 今日のお題
+        .route("/api/prompts", get(prompt::get_prompts).post(prompt::create_prompt))
+        .route("/api/prompts/today", get(prompt::get_today_prompt))
+        .route("/api/prompts/:id", put(prompt::update_prompt).delete(prompt::delete_prompt))
+
+        // リマインダー
+        .route("/api/reminders", get(reminder::get_reminders).post(reminder::create_reminder))
+        .route("/api/reminders/:id", put(reminder::update_reminder).delete(reminder::delete_reminder))
+
+        // Webhook
+        .route("/api/webhooks", get(webhook::get_webhooks).post(webhook::create_webhook))
+        .route("/api/webhooks/:id", put(webhook::update_webhook).delete(webhook::delete_webhook))
+        .route("/api/webhooks/:id/deliveries", get(webhook::get_deliveries))
+
+        // フィード
+        .route("/feed.atom", get(feed::get_atom_feed))
+        .route("/feed.json", get(feed::get_json_feed))
         
-        .with_state(pool)
+        .with_state(state)
 }
@@ -0,0 +1,412 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use image::DynamicImage;
+use sha2::{Digest, Sha256};
+use sqlx::{MySqlPool, Result, Error as SqlxError};
+
+use crate::images::{self, ThumbnailFormat, THUMBNAIL_SIZES};
+use crate::models::attachment::{Attachment, NewAttachment, Thumbnail};
+use crate::repositories::{
+    attachment_repository::AttachmentRepository,
+    diary_repository::DiaryRepository,
+};
+use crate::storage::{Storage, StorageError};
+
+// ファイル名として保存する最大文字数
+const MAX_FILENAME_CHARS: usize = 255;
+
+// エラー型を定義（サービス層固有のエラーを返す場合）
+#[derive(Debug)]
+pub enum AttachmentServiceError {
+    DatabaseError(()),
+    // 保存先での読み書きに失敗した
+    StorageError(()),
+    // 対象のエントリまたは添付ファイルが存在しない
+    NotFound,
+    // 空のファイルなど、保存できないファイル
+    InvalidFile(String),
+}
+
+// sqlx::ErrorからAttachmentServiceErrorへの変換
+impl From<SqlxError> for AttachmentServiceError {
+    fn from(err: SqlxError) -> Self {
+        tracing::error!("Database error occurred: {:?}", err);
+        AttachmentServiceError::DatabaseError(())
+    }
+}
+
+// StorageErrorからAttachmentServiceErrorへの変換
+impl From<StorageError> for AttachmentServiceError {
+    fn from(err: StorageError) -> Self {
+        match err {
+            StorageError::NotFound => AttachmentServiceError::NotFound,
+            err => {
+                tracing::error!("Storage error occurred: {:?}", err);
+                AttachmentServiceError::StorageError(())
+            }
+        }
+    }
+}
+
+/// アップロードされたファイル
+#[derive(Debug, Clone)]
+pub struct UploadedFile {
+    pub filename: String,
+    pub data: Bytes,
+}
+
+// 保存する前に処理した画像
+struct ProcessedImage {
+    data: Bytes,
+    content_type: &'static str,
+    image: DynamicImage,
+}
+
+pub struct AttachmentService<'a> {
+    pool: &'a MySqlPool,
+    diary_repo: DiaryRepository<'a>,
+    attachment_repo: AttachmentRepository<'a>,
+    storage: &'a Arc<dyn Storage>,
+}
+
+impl<'a> AttachmentService<'a> {
+    pub fn new(pool: &'a MySqlPool, storage: &'a Arc<dyn Storage>) -> Self {
+        Self {
+            pool,
+            diary_repo: DiaryRepository::new(pool),
+            attachment_repo: AttachmentRepository::new(pool),
+            storage,
+        }
+    }
+
+    /// エントリの添付ファイル一覧を取得します。
+    /// タイムカプセルの開封日時前は、エントリの一覧と同じく空の一覧を返します。
+    pub async fn get_attachments(&self, entry_id: i32) -> Result<Vec<Attachment>, AttachmentServiceError> {
+        let entry = self
+            .diary_repo
+            .find_entry(entry_id)
+            .await?
+            .ok_or(AttachmentServiceError::NotFound)?;
+        if entry.is_locked(chrono::Utc::now().naive_utc()) {
+            return Ok(Vec::new());
+        }
+        Ok(self.attachment_repo.find_attachments_for_entry(entry_id).await?)
+    }
+
+    /// ファイルを保存先に置き、エントリの添付ファイルとして登録します。
+    /// JPEG・PNG・WebPの画像は向きを補正し、`keep_metadata` が偽の場合は位置情報などのメタデータを取り除いて保存します。
+    /// サムネイルは登録後にバックグラウンドで生成します（生成中にプロセスが終了した場合は、次の起動時に生成し直します）。
+    pub async fn upload_attachments(
+        &self,
+        entry_id: i32,
+        files: Vec<UploadedFile>,
+        keep_metadata: bool,
+    ) -> Result<Vec<Attachment>, AttachmentServiceError> {
+        if !self.diary_repo.entry_exists(entry_id).await? {
+            return Err(AttachmentServiceError::NotFound);
+        }
+        if let Some(file) = files.iter().find(|file| file.data.is_empty()) {
+            return Err(AttachmentServiceError::InvalidFile(format!("{} is empty", file.filename)));
+        }
+
+        let mut attachments = Vec::with_capacity(files.len());
+        for file in files {
+            let now = chrono::Utc::now().naive_utc();
+            let filename = clean_filename(&file.filename);
+            let processed = process_image(&file, keep_metadata).await?;
+            let (data, content_type) = match &processed {
+                Some(processed) => (processed.data.clone(), processed.content_type),
+                // 送られてきた種類は信用せず、内容から判定する
+                None => (file.data.clone(), images::sniff_content_type(&file.data)),
+            };
+            let key = storage_key(entry_id, &filename, &data, now);
+            self.storage.put(&key, data.clone(), content_type).await?;
+
+            let attachment = NewAttachment {
+                entry_id,
+                storage_key: &key,
+                filename: &filename,
+                content_type,
+                size: data.len() as i64,
+                datetime: now,
+                width: processed.as_ref().map(|processed| processed.image.width() as i32),
+                height: processed.as_ref().map(|processed| processed.image.height() as i32),
+            };
+            let id = match self.attachment_repo.create_attachment(&attachment).await {
+                Ok(id) => id,
+                Err(err) => {
+                    // 登録できなかったファイルは保存先に残さない
+                    delete_key(self.storage.as_ref(), &key).await;
+                    return Err(err.into());
+                }
+            };
+
+            if let Some(processed) = processed {
+                let pool = self.pool.clone();
+                let storage = self.storage.clone();
+                tokio::spawn(async move {
+                    generate_thumbnails(pool, storage, id, key, processed.image).await;
+                });
+            }
+
+            attachments.push(
+                self.attachment_repo
+                    .find_attachment(id)
+                    .await?
+                    .ok_or(AttachmentServiceError::NotFound)?,
+            );
+        }
+
+        Ok(attachments)
+    }
+
+    /// 添付ファイルの情報と内容を取得します。
+    pub async fn get_attachment_file(&self, id: i32) -> Result<(Attachment, Bytes), AttachmentServiceError> {
+        let attachment = self.find_viewable_attachment(id).await?;
+        let data = self.storage.get(&attachment.storage_key).await?;
+        Ok((attachment, data))
+    }
+
+    /// 生成済みのサムネイルの内容を取得します。
+    pub async fn get_thumbnail_file(
+        &self,
+        id: i32,
+        max_size: u32,
+        format: ThumbnailFormat,
+    ) -> Result<Bytes, AttachmentServiceError> {
+        let attachment = self.find_viewable_attachment(id).await?;
+        let generated = attachment
+            .thumbnails
+            .iter()
+            .any(|thumbnail| thumbnail.max_size == max_size && thumbnail.format == format.extension());
+        if !generated {
+            return Err(AttachmentServiceError::NotFound);
+        }
+        Ok(self.storage.get(&thumbnail_key(&attachment.storage_key, max_size, format)).await?)
+    }
+
+    // 内容を返してよい添付ファイルを取得します。
+    // 下書き・公開日時前の予約投稿・開封日時前のタイムカプセルの添付ファイルは、存在しないものとして扱います。
+    async fn find_viewable_attachment(&self, id: i32) -> Result<Attachment, AttachmentServiceError> {
+        let attachment = self
+            .attachment_repo
+            .find_attachment(id)
+            .await?
+            .ok_or(AttachmentServiceError::NotFound)?;
+        match self.diary_repo.find_entry(attachment.entry_id).await? {
+            Some(entry) if !entry.is_locked(chrono::Utc::now().naive_utc()) => Ok(attachment),
+            _ => Err(AttachmentServiceError::NotFound),
+        }
+    }
+
+    /// 添付ファイルを削除します。
+    pub async fn delete_attachment(&self, id: i32) -> Result<(), AttachmentServiceError> {
+        let attachment = self
+            .attachment_repo
+            .find_attachment(id)
+            .await?
+            .ok_or(AttachmentServiceError::NotFound)?;
+        if !self.attachment_repo.delete_attachment(id).await? {
+            return Err(AttachmentServiceError::NotFound);
+        }
+        delete_files(self.storage.as_ref(), &attachment).await;
+        Ok(())
+    }
+}
+
+/// サムネイルが記録されていない画像のサムネイルを生成し直すタスクを起動します。
+/// サムネイルはアップロード後にバックグラウンドで生成するため、生成中にプロセスが終了すると記録されないまま残ります。
+/// 起動時に1回だけ、起動前に登録された画像を1件ずつ処理します。
+pub fn spawn_thumbnail_recovery(pool: MySqlPool, storage: Arc<dyn Storage>) {
+    let started_at = chrono::Utc::now().naive_utc();
+    tokio::spawn(async move {
+        let attachments = match AttachmentRepository::new(&pool).find_images_without_thumbnails(started_at).await {
+            Ok(attachments) => attachments,
+            Err(err) => {
+                tracing::error!("Failed to find attachments without thumbnails: {:?}", err);
+                return;
+            }
+        };
+        if !attachments.is_empty() {
+            tracing::info!("regenerating thumbnails for {} attachments", attachments.len());
+        }
+        for attachment in attachments {
+            regenerate_thumbnails(&pool, &storage, attachment).await;
+        }
+    });
+}
+
+// 保存先の画像を読み込み直して、サムネイルを生成します
+async fn regenerate_thumbnails(pool: &MySqlPool, storage: &Arc<dyn Storage>, attachment: Attachment) {
+    let data = match storage.get(&attachment.storage_key).await {
+        Ok(data) => data,
+        Err(err) => {
+            tracing::error!("Failed to read attachment {} for thumbnails: {:?}", attachment.id, err);
+            return;
+        }
+    };
+    let Some(format) = images::detect_format(&data) else {
+        return;
+    };
+    let decoded = tokio::task::spawn_blocking(move || images::decode(&data, format)).await;
+    match decoded {
+        Ok(Ok(decoded)) => {
+            let storage = storage.clone();
+            generate_thumbnails(pool.clone(), storage, attachment.id, attachment.storage_key, decoded.image).await
+        }
+        Ok(Err(err)) => tracing::error!("Failed to decode attachment {} for thumbnails: {:?}", attachment.id, err),
+        Err(err) => tracing::error!("Image decoding task failed for attachment {}: {:?}", attachment.id, err),
+    }
+}
+
+/// 添付ファイルとそのサムネイルを保存先から削除します。
+/// 行の削除は済んでいるため、失敗してもログに残すだけにします（ファイルが残るだけで参照はされない）。
+pub async fn delete_files(storage: &dyn Storage, attachment: &Attachment) {
+    delete_key(storage, &attachment.storage_key).await;
+    // 生成中のサムネイルも残さないよう、記録の有無にかかわらずすべての候補を削除する
+    for max_size in THUMBNAIL_SIZES {
+        for format in ThumbnailFormat::ALL {
+            delete_key(storage, &thumbnail_key(&attachment.storage_key, max_size, format)).await;
+        }
+    }
+}
+
+async fn delete_key(storage: &dyn Storage, key: &str) {
+    if let Err(err) = storage.delete(key).await {
+        tracing::error!("Failed to delete attachment file {}: {:?}", key, err);
+    }
+}
+
+// 画像であれば読み込んで向きを補正し、必要に応じてメタデータを取り除いた内容を返します。
+// 画像の処理はCPUを使うため、ブロッキング用のスレッドで行います。
+async fn process_image(file: &UploadedFile, keep_metadata: bool) -> Result<Option<ProcessedImage>, AttachmentServiceError> {
+    let Some(format) = images::detect_format(&file.data) else {
+        return Ok(None);
+    };
+
+    let data = file.data.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let decoded = images::decode(&data, format)?;
+        let data = if keep_metadata {
+            data
+        } else {
+            Bytes::from(images::encode_without_metadata(&decoded)?)
+        };
+        Ok::<_, image::ImageError>(ProcessedImage {
+            data,
+            content_type: decoded.content_type(),
+            image: decoded.image,
+        })
+    })
+    .await;
+
+    match result {
+        Ok(Ok(processed)) => Ok(Some(processed)),
+        Ok(Err(err)) => {
+            tracing::warn!("Failed to process image {}: {:?}", file.filename, err);
+            Err(AttachmentServiceError::InvalidFile(format!("{} is not a valid image", file.filename)))
+        }
+        Err(err) => {
+            tracing::error!("Image processing task failed: {:?}", err);
+            Err(AttachmentServiceError::InvalidFile(format!("{} could not be processed", file.filename)))
+        }
+    }
+}
+
+// サムネイルを生成して保存先に置き、添付ファイルの情報に記録します（バックグラウンドで実行）。
+async fn generate_thumbnails(
+    pool: MySqlPool,
+    storage: Arc<dyn Storage>,
+    attachment_id: i32,
+    storage_key: String,
+    image: DynamicImage,
+) {
+    let longest_side = image.width().max(image.height());
+    let image = Arc::new(image);
+    let mut thumbnails = Vec::new();
+
+    for (index, max_size) in THUMBNAIL_SIZES.into_iter().enumerate() {
+        // 1つ小さいサイズで元の大きさのまま収まる場合は、同じ画像になるため生成しない
+        if index > 0 && longest_side <= THUMBNAIL_SIZES[index - 1] {
+            break;
+        }
+        for format in ThumbnailFormat::ALL {
+            let source = image.clone();
+            let generated = tokio::task::spawn_blocking(move || images::thumbnail(&source, max_size, format)).await;
+            let (data, width, height) = match generated {
+                Ok(Ok(generated)) => generated,
+                Ok(Err(err)) => {
+                    tracing::error!("Failed to generate thumbnail for attachment {}: {:?}", attachment_id, err);
+                    continue;
+                }
+                Err(err) => {
+                    tracing::error!("Thumbnail task failed for attachment {}: {:?}", attachment_id, err);
+                    continue;
+                }
+            };
+
+            let size = data.len() as i64;
+            let key = thumbnail_key(&storage_key, max_size, format);
+            if let Err(err) = storage.put(&key, Bytes::from(data), format.content_type()).await {
+                tracing::error!("Failed to store thumbnail {}: {:?}", key, err);
+                continue;
+            }
+            thumbnails.push(Thumbnail {
+                max_size,
+                format: format.extension().to_string(),
+                width,
+                height,
+                size,
+            });
+        }
+    }
+
+    match AttachmentRepository::new(&pool).update_thumbnails(attachment_id, &thumbnails).await {
+        Ok(true) => {}
+        Ok(false) => {
+            // 生成中に添付ファイルが削除された場合は、サムネイルも残さない
+            for thumbnail in &thumbnails {
+                if let Some(format) = ThumbnailFormat::from_extension(&thumbnail.format) {
+                    delete_key(storage.as_ref(), &thumbnail_key(&storage_key, thumbnail.max_size, format)).await;
+                }
+            }
+        }
+        Err(err) => tracing::error!("Failed to record thumbnails for attachment {}: {:?}", attachment_id, err),
+    }
+}
+
+// サムネイルの保存先のキー（元のキー.{長辺}.{拡張子}）
+fn thumbnail_key(storage_key: &str, max_size: u32, format: ThumbnailFormat) -> String {
+    format!("{}.{}.{}", storage_key, max_size, format.extension())
+}
+
+// 保存先のキー（entries/{エントリID}/{日時}-{ハッシュ}.{拡張子}）
+fn storage_key(entry_id: i32, filename: &str, data: &[u8], now: chrono::NaiveDateTime) -> String {
+    let digest = hex::encode(Sha256::digest(data));
+    let extension = filename
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .filter(|extension| !extension.is_empty() && extension.len() <= 10 && extension.chars().all(|c| c.is_ascii_alphanumeric()));
+    let name = format!("{}-{}", now.format("%Y%m%d%H%M%S%f"), &digest[..16]);
+    match extension {
+        Some(extension) => format!("entries/{}/{}.{}", entry_id, name, extension),
+        None => format!("entries/{}/{}", entry_id, name),
+    }
+}
+
+// ブラウザによってはパスごと送られるため、最後の要素だけを使う
+fn clean_filename(filename: &str) -> String {
+    let name = filename
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_FILENAME_CHARS)
+        .collect::<String>();
+    match name.trim() {
+        "" => "file".to_string(),
+        name => name.to_string(),
+    }
+}
@@ -0,0 +1,749 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::hash::Hash;
+
+use sqlx::{MySqlPool, Result, Error as SqlxError};
+
+use crate::models::backup::{Backup, RestoreReport, BACKUP_VERSION};
+use crate::models::{
+    attachment::Attachment,
+    capture::CaptureTokenRow,
+    comment::Comment,
+    entry::{EntryRow, EntryView},
+    prompt::{DailyPrompt, EntryPrompt, Prompt},
+    reaction::Reaction,
+    reminder::Reminder,
+    tag::{EntryTag, Tag},
+    webhook::Webhook,
+};
+use crate::repositories::backup_repository::BackupRepository;
+
+// エラー型を定義（サービス層固有のエラーを返す場合）
+#[derive(Debug)]
+pub enum BackupServiceError {
+    DatabaseError(()),
+    // このバージョンでは読み込めないバックアップ形式
+    UnsupportedVersion(u32),
+}
+
+// sqlx::ErrorからBackupServiceErrorへの変換
+impl From<SqlxError> for BackupServiceError {
+    fn from(err: SqlxError) -> Self {
+        tracing::error!("Database error occurred: {:?}", err);
+        BackupServiceError::DatabaseError(())
+    }
+}
+
+// リストアで追加する行
+#[derive(Default)]
+struct RestorePlan<'b> {
+    entries: Vec<&'b EntryRow>,
+    tags: Vec<&'b Tag>,
+    entry_tags: Vec<&'b EntryTag>,
+    comments: Vec<&'b Comment>,
+    reactions: Vec<&'b Reaction>,
+    attachments: Vec<&'b Attachment>,
+    prompts: Vec<&'b Prompt>,
+    daily_prompts: Vec<&'b DailyPrompt>,
+    entry_prompts: Vec<&'b EntryPrompt>,
+    entry_views: Vec<&'b EntryView>,
+    webhooks: Vec<&'b Webhook>,
+    reminders: Vec<&'b Reminder>,
+    capture_tokens: Vec<&'b CaptureTokenRow>,
+}
+
+pub struct BackupService<'a> {
+    pool: &'a MySqlPool,
+    backup_repo: BackupRepository<'a>,
+}
+
+impl<'a> BackupService<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self {
+            pool,
+            backup_repo: BackupRepository::new(pool),
+        }
+    }
+
+    /// データベースの内容をすべてバックアップ形式で取得します。
+    pub async fn export(&self) -> Result<Backup, BackupServiceError> {
+        Ok(Backup {
+            version: BACKUP_VERSION,
+            exported_at: chrono::Utc::now().naive_utc(),
+            entries: self.backup_repo.find_all_entries().await?,
+            tags: self.backup_repo.find_all_tags().await?,
+            entry_tags: self.backup_repo.find_all_entry_tags().await?,
+            comments: self.backup_repo.find_all_comments().await?,
+            reactions: self.backup_repo.find_all_reactions().await?,
+            attachments: self.backup_repo.find_all_attachments().await?,
+            prompts: self.backup_repo.find_all_prompts().await?,
+            daily_prompts: self.backup_repo.find_all_daily_prompts().await?,
+            entry_prompts: self.backup_repo.find_all_entry_prompts().await?,
+            entry_views: self.backup_repo.find_all_entry_views().await?,
+            webhooks: self.backup_repo.find_all_webhooks().await?,
+            reminders: self.backup_repo.find_all_reminders().await?,
+            capture_tokens: self.backup_repo.find_all_capture_tokens().await?,
+        })
+    }
+
+    /// バックアップをリストアします。古いバージョンのバックアップにないテーブルは空として扱います。
+    /// IDを保ったまま存在しない行だけを追加するため、同じバックアップを何度適用しても結果は変わりません。
+    /// 同じIDで内容の異なる行や、一意な列（タグ名・下書きのキーなど）の値が別のIDの行と重なる行がある場合は
+    /// 衝突として報告し、その行はスキップします。
+    /// `dry_run` が真の場合は書き込みを行わず、結果の予定だけを返します。
+    pub async fn restore(
+        &self,
+        backup: &Backup,
+        dry_run: bool,
+    ) -> Result<RestoreReport, BackupServiceError> {
+        if backup.version == 0 || backup.version > BACKUP_VERSION {
+            return Err(BackupServiceError::UnsupportedVersion(backup.version));
+        }
+
+        let current = self.export().await?;
+        let mut report = RestoreReport {
+            dry_run,
+            ..Default::default()
+        };
+        let plan = plan_restore(backup, &current, &mut report);
+
+        if dry_run {
+            return Ok(report);
+        }
+
+        // 外部キーの依存関係の順に書き込む
+        let mut tx = self.pool.begin().await?;
+        BackupRepository::insert_tags_tx(&mut tx, &plan.tags).await?;
+        BackupRepository::insert_entries_tx(&mut tx, &plan.entries).await?;
+        BackupRepository::insert_entry_tags_tx(&mut tx, &plan.entry_tags).await?;
+        BackupRepository::insert_comments_tx(&mut tx, &plan.comments).await?;
+        BackupRepository::insert_reactions_tx(&mut tx, &plan.reactions).await?;
+        BackupRepository::insert_attachments_tx(&mut tx, &plan.attachments).await?;
+        BackupRepository::insert_prompts_tx(&mut tx, &plan.prompts).await?;
+        BackupRepository::insert_daily_prompts_tx(&mut tx, &plan.daily_prompts).await?;
+        BackupRepository::insert_entry_prompts_tx(&mut tx, &plan.entry_prompts).await?;
+        BackupRepository::insert_entry_views_tx(&mut tx, &plan.entry_views).await?;
+        BackupRepository::insert_webhooks_tx(&mut tx, &plan.webhooks).await?;
+        BackupRepository::insert_reminders_tx(&mut tx, &plan.reminders).await?;
+        BackupRepository::insert_capture_tokens_tx(&mut tx, &plan.capture_tokens).await?;
+        tx.commit().await?;
+
+        Ok(report)
+    }
+}
+
+// バックアップと現在のデータを比較し、追加する行を決めます。
+fn plan_restore<'b>(backup: &'b Backup, current: &Backup, report: &mut RestoreReport) -> RestorePlan<'b> {
+    let mut plan = RestorePlan::default();
+
+    // タグ（名前の一意制約も確認する）
+    let (tags, mut invalid_tags) = diff_rows("tag", &backup.tags, &current.tags, |tag| tag.id, report);
+    let (tags, conflicted_names) =
+        keep_unique("tag", "name", tags, &current.tags, |tag| tag.id, |tag| Some(tag.name.as_str()), report);
+    invalid_tags.extend(conflicted_names);
+    report.table("tag").inserted += tags.len();
+    plan.tags = tags;
+    let valid_tags = valid_ids(&current.tags, &plan.tags, &invalid_tags, |tag| tag.id);
+
+    // エントリ（下書きのキーの一意制約も確認する）
+    // 通知の記録は日時に応じて変わるため、比較には含めない
+    let entry_id = |row: &EntryRow| row.entry.id;
+    let (entries, mut invalid_entries) = diff_rows_by(
+        "entry",
+        &backup.entries,
+        &current.entries,
+        entry_id,
+        |incoming, existing| incoming.entry == existing.entry,
+        report,
+    );
+    let (entries, conflicted_keys) = keep_unique(
+        "entry",
+        "draft_key",
+        entries,
+        &current.entries,
+        entry_id,
+        |row| row.entry.draft_key.as_deref(),
+        report,
+    );
+    invalid_entries.extend(conflicted_keys);
+    report.table("entry").inserted += entries.len();
+    plan.entries = entries;
+    let valid_entries = valid_ids(&current.entries, &plan.entries, &invalid_entries, entry_id);
+
+    // エントリとタグの関連
+    let mut existing_entry_tags: HashSet<(i32, i32)> = current
+        .entry_tags
+        .iter()
+        .map(|entry_tag| (entry_tag.entry_id, entry_tag.tag_id))
+        .collect();
+    for entry_tag in &backup.entry_tags {
+        let key = (entry_tag.entry_id, entry_tag.tag_id);
+        let label = format!("entry_id={},tag_id={}", entry_tag.entry_id, entry_tag.tag_id);
+        if existing_entry_tags.contains(&key) {
+            report.table("entry_tag").unchanged += 1;
+        } else if !valid_entries.contains(&entry_tag.entry_id) {
+            report.conflict("entry_tag", label, "entry is missing or conflicted");
+        } else if !valid_tags.contains(&entry_tag.tag_id) {
+            report.conflict("entry_tag", label, "tag is missing or conflicted");
+        } else {
+            existing_entry_tags.insert(key);
+            report.table("entry_tag").inserted += 1;
+            plan.entry_tags.push(entry_tag);
+        }
+    }
+
+    // コメント・リアクション・添付ファイル（エントリに依存する）
+    let (comments, _) = diff_rows("comment", &backup.comments, &current.comments, |comment| comment.id, report);
+    plan.comments = keep_with_entry("comment", comments, &valid_entries, |comment| (comment.id, comment.entry_id), report);
+
+    let (reactions, _) =
+        diff_rows("reaction", &backup.reactions, &current.reactions, |reaction| reaction.id, report);
+    plan.reactions =
+        keep_with_entry("reaction", reactions, &valid_entries, |reaction| (reaction.id, reaction.entry_id), report);
+
+    let (attachments, _) = diff_rows(
+        "attachment",
+        &backup.attachments,
+        &current.attachments,
+        |attachment| attachment.id,
+        report,
+    );
+    let (attachments, _) = keep_unique(
+        "attachment",
+        "storage_key",
+        attachments,
+        &current.attachments,
+        |attachment| attachment.id,
+        |attachment| Some(attachment.storage_key.as_str()),
+        report,
+    );
+    plan.attachments = keep_with_entry(
+        "attachment",
+        attachments,
+        &valid_entries,
+        |attachment| (attachment.id, attachment.entry_id),
+        report,
+    );
+
+    // お題（初期データのお題は登録した日時が環境ごとに異なるため、本文と言語が同じなら同じ行として扱う）
+    let (prompts, conflicted_prompts) = diff_rows_by(
+        "prompt",
+        &backup.prompts,
+        &current.prompts,
+        |prompt| prompt.id,
+        |incoming, existing| incoming.text == existing.text && incoming.language == existing.language,
+        report,
+    );
+    report.table("prompt").inserted += prompts.len();
+    plan.prompts = prompts;
+    let valid_prompts = valid_ids(&current.prompts, &plan.prompts, &conflicted_prompts, |prompt| prompt.id);
+
+    let daily_prompt_key = |daily_prompt: &DailyPrompt| format!("{}:{}", daily_prompt.date, daily_prompt.language);
+    let (daily_prompts, _) = diff_rows_by(
+        "daily_prompt",
+        &backup.daily_prompts,
+        &current.daily_prompts,
+        daily_prompt_key,
+        |incoming, existing| incoming == existing,
+        report,
+    );
+    plan.daily_prompts = keep_with_references("daily_prompt", daily_prompts, daily_prompt_key, report, |daily_prompt| {
+        (!valid_prompts.contains(&daily_prompt.prompt_id)).then_some("prompt is missing or conflicted")
+    });
+
+    let (entry_prompts, _) = diff_rows_by(
+        "entry_prompt",
+        &backup.entry_prompts,
+        &current.entry_prompts,
+        |entry_prompt| entry_prompt.entry_id,
+        |incoming, existing| incoming == existing,
+        report,
+    );
+    plan.entry_prompts = keep_with_references(
+        "entry_prompt",
+        entry_prompts,
+        |entry_prompt| entry_prompt.entry_id,
+        report,
+        |entry_prompt| {
+            if !valid_entries.contains(&entry_prompt.entry_id) {
+                Some("entry is missing or conflicted")
+            } else if !valid_prompts.contains(&entry_prompt.prompt_id) {
+                Some("prompt is missing or conflicted")
+            } else {
+                None
+            }
+        },
+    );
+
+    let (entry_views, _) = diff_rows_by(
+        "entry_view",
+        &backup.entry_views,
+        &current.entry_views,
+        |entry_view| entry_view.entry_id,
+        |incoming, existing| incoming == existing,
+        report,
+    );
+    plan.entry_views = keep_with_references("entry_view", entry_views, |entry_view| entry_view.entry_id, report, |entry_view| {
+        (!valid_entries.contains(&entry_view.entry_id)).then_some("entry is missing or conflicted")
+    });
+
+    // Webhookとリマインダー（Webhookに依存する）
+    let (webhooks, conflicted_webhooks) =
+        diff_rows("webhook", &backup.webhooks, &current.webhooks, |webhook| webhook.id, report);
+    report.table("webhook").inserted += webhooks.len();
+    plan.webhooks = webhooks;
+    let valid_webhooks = valid_ids(&current.webhooks, &plan.webhooks, &conflicted_webhooks, |webhook| webhook.id);
+
+    let (reminders, _) = diff_rows("reminder", &backup.reminders, &current.reminders, |reminder| reminder.id, report);
+    plan.reminders = keep_with_references("reminder", reminders, |reminder| reminder.id, report, |reminder| {
+        reminder
+            .webhook_id
+            .is_some_and(|webhook_id| !valid_webhooks.contains(&webhook_id))
+            .then_some("webhook is missing or conflicted")
+    });
+
+    // キャプチャのトークン（ハッシュの一意制約も確認する）
+    let (capture_tokens, _) = diff_rows(
+        "capture_token",
+        &backup.capture_tokens,
+        &current.capture_tokens,
+        |capture_token| capture_token.id,
+        report,
+    );
+    let (capture_tokens, _) = keep_unique(
+        "capture_token",
+        "token_hash",
+        capture_tokens,
+        &current.capture_tokens,
+        |capture_token| capture_token.id,
+        |capture_token| Some(capture_token.token_hash.as_str()),
+        report,
+    );
+    report.table("capture_token").inserted += capture_tokens.len();
+    plan.capture_tokens = capture_tokens;
+
+    plan
+}
+
+// IDで既存の行と比較し、追加すべき行と衝突したIDを返します。
+// 同じ内容の行がすでにある場合は unchanged として数えます。
+fn diff_rows<'b, T: PartialEq>(
+    table: &'static str,
+    incoming: &'b [T],
+    existing: &[T],
+    id: impl Fn(&T) -> i32,
+    report: &mut RestoreReport,
+) -> (Vec<&'b T>, HashSet<i32>) {
+    diff_rows_by(table, incoming, existing, id, |incoming, existing| incoming == existing, report)
+}
+
+// キー（IDや複合キー）で既存の行と比較し、追加すべき行と衝突したキーを返します。
+// `same` が真を返す行がすでにある場合は unchanged として数えます。
+fn diff_rows_by<'b, T, K: Clone + Eq + Hash + Display>(
+    table: &'static str,
+    incoming: &'b [T],
+    existing: &[T],
+    key: impl Fn(&T) -> K,
+    same: impl Fn(&T, &T) -> bool,
+    report: &mut RestoreReport,
+) -> (Vec<&'b T>, HashSet<K>) {
+    let existing: HashMap<K, &T> = existing.iter().map(|row| (key(row), row)).collect();
+    let mut seen = HashSet::new();
+    let mut rows = Vec::new();
+    let mut conflicted = HashSet::new();
+
+    for row in incoming {
+        let row_key = key(row);
+        if !seen.insert(row_key.clone()) {
+            report.conflict(table, row_key.to_string(), "id is duplicated in the backup");
+            conflicted.insert(row_key);
+            continue;
+        }
+        match existing.get(&row_key) {
+            Some(current) if same(row, current) => report.table(table).unchanged += 1,
+            Some(_) => {
+                report.conflict(table, row_key.to_string(), "a different row with the same id already exists");
+                conflicted.insert(row_key);
+            }
+            None => rows.push(row),
+        }
+    }
+
+    // バックアップ内で重複していたキーは追加しない
+    rows.retain(|row| !conflicted.contains(&key(row)));
+    (rows, conflicted)
+}
+
+// 一意な列の値（`unique` が None の行は対象外）が、既存の行や先に追加する行と重なる行を衝突として報告し、
+// 残りの行と衝突した行のIDを返します。
+fn keep_unique<'b, T>(
+    table: &'static str,
+    column: &str,
+    rows: Vec<&'b T>,
+    existing: &[T],
+    id: impl Fn(&T) -> i32,
+    unique: impl Fn(&T) -> Option<&str>,
+    report: &mut RestoreReport,
+) -> (Vec<&'b T>, HashSet<i32>) {
+    let mut used: HashMap<String, i32> =
+        existing.iter().filter_map(|row| Some((unique(row)?.to_string(), id(row)))).collect();
+    let mut kept = Vec::new();
+    let mut conflicted = HashSet::new();
+
+    for row in rows {
+        let Some(value) = unique(row) else {
+            kept.push(row);
+            continue;
+        };
+        match used.get(value) {
+            Some(&other_id) => {
+                let reason = format!("{} is already used by {} {}", column, table, other_id);
+                report.conflict(table, id(row).to_string(), reason);
+                conflicted.insert(id(row));
+            }
+            None => {
+                used.insert(value.to_string(), id(row));
+                kept.push(row);
+            }
+        }
+    }
+    (kept, conflicted)
+}
+
+// リストア後に参照できるID（既存の行と追加する行から、衝突したものを除く）
+fn valid_ids<T>(
+    existing: &[T],
+    inserted: &[&T],
+    invalid: &HashSet<i32>,
+    id: impl Fn(&T) -> i32,
+) -> HashSet<i32> {
+    existing
+        .iter()
+        .chain(inserted.iter().copied())
+        .map(id)
+        .filter(|row_id| !invalid.contains(row_id))
+        .collect()
+}
+
+// 参照先のエントリが存在する行だけを残します。
+fn keep_with_entry<'b, T>(
+    table: &'static str,
+    rows: Vec<&'b T>,
+    valid_entries: &HashSet<i32>,
+    ids: impl Fn(&T) -> (i32, i32),
+    report: &mut RestoreReport,
+) -> Vec<&'b T> {
+    let mut kept = Vec::new();
+    for row in rows {
+        let (row_id, entry_id) = ids(row);
+        if valid_entries.contains(&entry_id) {
+            report.table(table).inserted += 1;
+            kept.push(row);
+        } else {
+            report.conflict(table, row_id.to_string(), "entry is missing or conflicted");
+        }
+    }
+    kept
+}
+
+// 参照先が存在する行だけを残します。`missing` は参照先が足りない場合にその理由を返します。
+fn keep_with_references<'b, T, K: Display>(
+    table: &'static str,
+    rows: Vec<&'b T>,
+    key: impl Fn(&T) -> K,
+    report: &mut RestoreReport,
+    missing: impl Fn(&T) -> Option<&'static str>,
+) -> Vec<&'b T> {
+    let mut kept = Vec::new();
+    for row in rows {
+        match missing(row) {
+            Some(reason) => report.conflict(table, key(row).to_string(), reason),
+            None => {
+                report.table(table).inserted += 1;
+                kept.push(row);
+            }
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+    use serde::{de::DeserializeOwned, Serialize};
+    use sqlx::types::Json;
+
+    use super::*;
+    use crate::models::entry::{Entry, EntryStatus};
+    use crate::models::reminder::ReminderChannel;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap().and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn empty() -> Backup {
+        serde_json::from_str(r#"{"version": 2, "exported_at": "2024-01-01T00:00:00", "entries": [], "tags": [], "entry_tags": []}"#)
+            .unwrap()
+    }
+
+    // すべてのテーブルに行があるバックアップ
+    fn sample() -> Backup {
+        Backup {
+            version: BACKUP_VERSION,
+            exported_at: at(31, 0),
+            entries: vec![
+                EntryRow {
+                    entry: Entry {
+                        id: 1,
+                        content: "晴れ 🌞".to_string(),
+                        datetime: at(1, 9),
+                        unlock_at: Some(at(1, 9)),
+                        status: EntryStatus::Published,
+                        draft_key: None,
+                        draft_tags: None,
+                        publish_at: None,
+                    },
+                    unlock_notified: Some(true),
+                    publish_notified: Some(false),
+                },
+                EntryRow {
+                    entry: Entry {
+                        id: 2,
+                        content: "書きかけ".to_string(),
+                        datetime: at(2, 9),
+                        unlock_at: Some(at(30, 0)),
+                        status: EntryStatus::Draft,
+                        draft_key: Some("draft-1".to_string()),
+                        draft_tags: Some(Json(vec!["旅行".to_string(), "下書きだけのタグ".to_string()])),
+                        publish_at: None,
+                    },
+                    unlock_notified: Some(false),
+                    publish_notified: Some(false),
+                },
+            ],
+            tags: vec![Tag { id: 1, name: "旅行".to_string() }],
+            entry_tags: vec![EntryTag { entry_id: 1, tag_id: 1 }],
+            comments: vec![Comment {
+                id: 1,
+                entry_id: 1,
+                author: "友人".to_string(),
+                content: "いいね".to_string(),
+                datetime: at(1, 10),
+            }],
+            reactions: vec![Reaction { id: 1, entry_id: 1, emoji: "👍".to_string(), datetime: at(1, 11) }],
+            attachments: vec![Attachment {
+                id: 1,
+                entry_id: 1,
+                storage_key: "entries/1/photo.jpg".to_string(),
+                filename: "photo.jpg".to_string(),
+                content_type: "image/jpeg".to_string(),
+                size: 1234,
+                datetime: at(1, 12),
+                width: Some(640),
+                height: Some(480),
+                thumbnails: Json(Vec::new()),
+            }],
+            prompts: vec![Prompt {
+                id: 1,
+                text: "今日いちばん印象に残った出来事は？".to_string(),
+                language: "ja".to_string(),
+                enabled: true,
+                datetime: at(1, 0),
+            }],
+            daily_prompts: vec![DailyPrompt { date: at(1, 0).date(), language: String::new(), prompt_id: 1 }],
+            entry_prompts: vec![EntryPrompt { entry_id: 1, prompt_id: 1 }],
+            entry_views: vec![EntryView { entry_id: 1, viewed_at: at(3, 0) }],
+            webhooks: vec![Webhook {
+                id: 1,
+                url: "https://example.com/hook".to_string(),
+                secret: "secret".to_string(),
+                event_types: Json(vec!["entry.created".to_string()]),
+                enabled: true,
+                datetime: at(1, 0),
+            }],
+            reminders: vec![Reminder {
+                id: 1,
+                name: "わたし".to_string(),
+                remind_at: NaiveTime::from_hms_opt(21, 0, 0).unwrap(),
+                channel: ReminderChannel::Webhook,
+                webhook_id: Some(1),
+                email: None,
+                enabled: true,
+                last_checked_on: Some(at(2, 0).date()),
+                last_sent_at: Some(at(2, 12)),
+                last_error: None,
+                datetime: at(1, 0),
+            }],
+            capture_tokens: vec![CaptureTokenRow {
+                id: 1,
+                name: "phone".to_string(),
+                token_hash: "a".repeat(64),
+                datetime: at(1, 0),
+                last_used_at: None,
+            }],
+        }
+    }
+
+    fn copy<T: Serialize + DeserializeOwned>(row: &T) -> T {
+        serde_json::from_value(serde_json::to_value(row).unwrap()).unwrap()
+    }
+
+    fn copy_all<T: Serialize + DeserializeOwned>(database: &mut Vec<T>, rows: &[&T]) {
+        database.extend(rows.iter().map(|row| copy(*row)));
+    }
+
+    // リストアの書き込みをメモリ上のデータベース（エクスポートの結果と同じ形）に適用します
+    fn apply(plan: &RestorePlan<'_>, database: &mut Backup) {
+        copy_all(&mut database.entries, &plan.entries);
+        copy_all(&mut database.tags, &plan.tags);
+        copy_all(&mut database.entry_tags, &plan.entry_tags);
+        copy_all(&mut database.comments, &plan.comments);
+        copy_all(&mut database.reactions, &plan.reactions);
+        copy_all(&mut database.attachments, &plan.attachments);
+        copy_all(&mut database.prompts, &plan.prompts);
+        copy_all(&mut database.daily_prompts, &plan.daily_prompts);
+        copy_all(&mut database.entry_prompts, &plan.entry_prompts);
+        copy_all(&mut database.entry_views, &plan.entry_views);
+        copy_all(&mut database.webhooks, &plan.webhooks);
+        copy_all(&mut database.reminders, &plan.reminders);
+        copy_all(&mut database.capture_tokens, &plan.capture_tokens);
+    }
+
+    // JSONに書き出して読み込み直したバックアップをリストアし、リストア後のデータベースを返します
+    fn restore_into(backup: &Backup, database: &mut Backup) -> RestoreReport {
+        let json = serde_json::to_string(backup).unwrap();
+        let parsed: Backup = serde_json::from_str(&json).unwrap();
+        let mut report = RestoreReport::default();
+        let plan = plan_restore(&parsed, database, &mut report);
+        apply(&plan, database);
+        report
+    }
+
+    #[test]
+    fn export_restore_export_round_trips() {
+        let original = sample();
+        let mut database = empty();
+
+        let report = restore_into(&original, &mut database);
+        assert!(report.conflicts.is_empty(), "{:?}", report.conflicts);
+        for (table, counts) in &report.tables {
+            assert_eq!(counts.inserted, 1 + usize::from(*table == "entry"), "{}", table);
+        }
+
+        // 再びエクスポートした内容は元のバックアップと同じ
+        database.version = original.version;
+        database.exported_at = original.exported_at;
+        assert_eq!(database, original);
+
+        // 同じバックアップをもう一度リストアしても何も追加されない
+        let report = restore_into(&original, &mut database);
+        assert!(report.conflicts.is_empty());
+        assert!(report.tables.values().all(|counts| counts.inserted == 0 && counts.unchanged > 0));
+        assert_eq!(database, original);
+    }
+
+    #[test]
+    fn dry_run_reports_conflicts_and_skips_dependent_rows() {
+        let backup = sample();
+        let mut current = empty();
+        // 同じIDで内容の異なるエントリ、同じ名前で別IDのタグ、同じハッシュで別IDのトークン
+        current.entries.push(EntryRow {
+            entry: Entry { content: "別の日記".to_string(), ..copy(&backup.entries[0].entry) },
+            ..copy(&backup.entries[0])
+        });
+        current.tags.push(Tag { id: 5, name: "旅行".to_string() });
+        current.capture_tokens.push(CaptureTokenRow { id: 9, ..copy(&backup.capture_tokens[0]) });
+        // 初期データのお題は登録日時が違っても同じお題として扱う
+        current.prompts.push(Prompt { datetime: at(20, 0), ..copy(&backup.prompts[0]) });
+
+        let mut report = RestoreReport { dry_run: true, ..Default::default() };
+        let plan = plan_restore(&backup, &current, &mut report);
+
+        let conflicted: Vec<(&str, &str)> =
+            report.conflicts.iter().map(|conflict| (conflict.table, conflict.key.as_str())).collect();
+        assert_eq!(
+            conflicted,
+            vec![
+                ("tag", "1"),
+                ("entry", "1"),
+                ("entry_tag", "entry_id=1,tag_id=1"),
+                ("comment", "1"),
+                ("reaction", "1"),
+                ("attachment", "1"),
+                ("entry_prompt", "1"),
+                ("entry_view", "1"),
+                ("capture_token", "1"),
+            ]
+        );
+        assert_eq!(report.tables["entry"].inserted, 1);
+        assert_eq!(report.tables["prompt"].unchanged, 1);
+        assert_eq!(report.tables["daily_prompt"].inserted, 1);
+        assert_eq!(report.tables["reminder"].inserted, 1);
+        // 衝突した行は追加されない
+        assert_eq!(plan.entries.iter().map(|row| row.entry.id).collect::<Vec<_>>(), vec![2]);
+        assert!(plan.tags.is_empty() && plan.capture_tokens.is_empty() && plan.entry_prompts.is_empty());
+
+        // ドライランの結果はJSONとして返せる
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["dry_run"], true);
+        assert_eq!(json["conflicts"][1]["reason"], "a different row with the same id already exists");
+    }
+
+    #[test]
+    fn reports_unique_keys_used_by_other_ids_as_conflicts() {
+        let backup = sample();
+        let mut current = empty();
+        // 同じ下書きのキー・保存先のキーを別のIDの行が使っている
+        current.entries.push(EntryRow {
+            entry: Entry { id: 7, ..copy(&backup.entries[1].entry) },
+            ..copy(&backup.entries[1])
+        });
+        current.entries.push(EntryRow {
+            entry: Entry { id: 8, ..copy(&backup.entries[0].entry) },
+            ..copy(&backup.entries[0])
+        });
+        current.attachments.push(Attachment { id: 3, entry_id: 8, ..copy(&backup.attachments[0]) });
+
+        let mut report = RestoreReport::default();
+        let plan = plan_restore(&backup, &current, &mut report);
+
+        let conflicted: Vec<(&str, &str, &str)> = report
+            .conflicts
+            .iter()
+            .map(|conflict| (conflict.table, conflict.key.as_str(), conflict.reason.as_str()))
+            .collect();
+        assert_eq!(
+            conflicted,
+            vec![
+                ("entry", "2", "draft_key is already used by entry 7"),
+                ("attachment", "1", "storage_key is already used by attachment 3"),
+            ]
+        );
+        assert_eq!(plan.entries.iter().map(|row| row.entry.id).collect::<Vec<_>>(), vec![1]);
+        assert!(plan.attachments.is_empty());
+    }
+
+    #[test]
+    fn restores_version_1_backups() {
+        let v1 = r#"{
+            "version": 1,
+            "exported_at": "2024-01-31T00:00:00",
+            "entries": [{"id": 1, "content": "晴れ", "datetime": "2024-01-01T09:00:00"}],
+            "tags": [{"id": 1, "name": "天気"}],
+            "entry_tags": [{"entry_id": 1, "tag_id": 1}],
+            "comments": [],
+            "reactions": [],
+            "attachments": []
+        }"#;
+        let backup: Backup = serde_json::from_str(v1).unwrap();
+        assert!(backup.prompts.is_empty() && backup.webhooks.is_empty());
+        assert_eq!(backup.entries[0].entry.status, EntryStatus::Published);
+        assert_eq!(backup.entries[0].unlock_notified, None);
+
+        let mut database = empty();
+        let report = restore_into(&backup, &mut database);
+        assert!(report.conflicts.is_empty());
+        assert_eq!(database.entries.len(), 1);
+        assert_eq!(database.entry_tags.len(), 1);
+    }
+}
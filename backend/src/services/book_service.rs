@@ -0,0 +1,264 @@
+use askama::Template;
+use async_zip::tokio::write::ZipFileWriter;
+use async_zip::{error::ZipError, Compression, ZipEntryBuilder};
+use chrono::{Datelike, NaiveDate, Timelike};
+use sha2::{Digest, Sha256};
+use sqlx::{MySqlPool, Error as SqlxError};
+use tokio::io::AsyncWrite;
+
+use crate::config;
+use crate::models::book::{BookQuery, WritingMode};
+use crate::repositories::tag_repository::TagRepository;
+use crate::services::diary_service::{DiaryService, DiaryServiceError};
+use crate::services::feed_service::FEED_TITLE;
+
+// EPUBのコンテナ（OPFファイルの場所を示す）
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+const WEEKDAYS: [&str; 7] = ["月", "火", "水", "木", "金", "土", "日"];
+
+// エラー型を定義（サービス層固有のエラーを返す場合）
+#[derive(Debug)]
+pub enum BookServiceError {
+    DatabaseError(()),
+    // 書き込みに失敗した（クライアントの切断を含む）
+    WriteError(()),
+    // 指定されたタグが存在しない
+    NotFound,
+    // 期間の指定が正しくない
+    InvalidRange,
+}
+
+// sqlx::ErrorからBookServiceErrorへの変換
+impl From<SqlxError> for BookServiceError {
+    fn from(err: SqlxError) -> Self {
+        tracing::error!("Database error occurred: {:?}", err);
+        BookServiceError::DatabaseError(())
+    }
+}
+
+// DiaryServiceErrorからBookServiceErrorへの変換（ログはDiaryService側で出力済み）
+impl From<DiaryServiceError> for BookServiceError {
+    fn from(err: DiaryServiceError) -> Self {
+        match err {
+            DiaryServiceError::DatabaseError(()) => BookServiceError::DatabaseError(()),
+        }
+    }
+}
+
+// async_zip::error::ZipErrorからBookServiceErrorへの変換
+impl From<ZipError> for BookServiceError {
+    fn from(err: ZipError) -> Self {
+        tracing::error!("Failed to write epub: {:?}", err);
+        BookServiceError::WriteError(())
+    }
+}
+
+// askama::ErrorからBookServiceErrorへの変換
+impl From<askama::Error> for BookServiceError {
+    fn from(err: askama::Error) -> Self {
+        tracing::error!("Failed to render template: {:?}", err);
+        BookServiceError::WriteError(())
+    }
+}
+
+/// 書籍にまとめるエントリ（月ごとの章に分けたもの）
+pub struct Book {
+    title: String,
+    period: String,
+    tag: Option<String>,
+    identifier: String,
+    vertical: bool,
+    entry_count: usize,
+    chapters: Vec<Chapter>,
+}
+
+// 1か月分のエントリ
+struct Chapter {
+    id: String,
+    label: String,
+    year: i32,
+    month: u32,
+    entries: Vec<BookEntry>,
+}
+
+// 日付は縦書きで数字を縦中横にできるよう、要素ごとに持つ
+struct BookEntry {
+    day: u32,
+    weekday: &'static str,
+    hour: u32,
+    minute: String,
+    lines: Vec<String>,
+    tags: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(path = "book/content.opf", escape = "html")]
+struct PackageDocument<'a> {
+    book: &'a Book,
+    modified: String,
+}
+
+#[derive(Template)]
+#[template(path = "book/nav.xhtml", escape = "html")]
+struct NavDocument<'a> {
+    book: &'a Book,
+}
+
+#[derive(Template)]
+#[template(path = "book/cover.xhtml", escape = "html")]
+struct CoverDocument<'a> {
+    book: &'a Book,
+}
+
+#[derive(Template)]
+#[template(path = "book/chapter.xhtml", escape = "html")]
+struct ChapterDocument<'a> {
+    book: &'a Book,
+    chapter: &'a Chapter,
+}
+
+#[derive(Template)]
+#[template(path = "book/style.css", escape = "none")]
+struct StyleSheet<'a> {
+    book: &'a Book,
+}
+
+pub struct BookService<'a> {
+    pool: &'a MySqlPool,
+    tag_repo: TagRepository<'a>,
+    diary_service: DiaryService<'a>,
+}
+
+impl<'a> BookService<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self {
+            pool,
+            tag_repo: TagRepository::new(),
+            diary_service: DiaryService::new(pool),
+        }
+    }
+
+    /// 条件に合うエントリを読み込み、月ごとの章に分けた書籍を組み立てます。
+    /// 書き出しの前に呼ぶことで、条件の誤りをレスポンスのステータスコードで返せます。
+    pub async fn load_book(&self, query: &BookQuery) -> Result<Book, BookServiceError> {
+        if query.from > query.to {
+            return Err(BookServiceError::InvalidRange);
+        }
+        let tag = match query.tag {
+            Some(tag_id) => Some(
+                self.tag_repo
+                    .find_tag(self.pool, tag_id)
+                    .await?
+                    .ok_or(BookServiceError::NotFound)?,
+            ),
+            None => None,
+        };
+
+        let timezone = config::diary_timezone();
+        let from = config::start_of_day_utc(query.from, timezone);
+        let to = config::start_of_day_utc(query.to.succ_opt().ok_or(BookServiceError::InvalidRange)?, timezone);
+        let entries = self
+            .diary_service
+            .get_entries_between_with_details(from, to, query.tag)
+            .await?;
+
+        // エントリは日時順のため、月が変わるたびに章を追加する
+        let mut chapters: Vec<Chapter> = Vec::new();
+        for entry in &entries {
+            let local = entry.datetime.and_utc().with_timezone(&timezone);
+            let (year, month) = (local.year(), local.month());
+            if !chapters.last().is_some_and(|chapter| chapter.year == year && chapter.month == month) {
+                chapters.push(Chapter {
+                    id: format!("month-{:04}-{:02}", year, month),
+                    label: format!("{}年{}月", year, month),
+                    year,
+                    month,
+                    entries: Vec::new(),
+                });
+            }
+            if let Some(chapter) = chapters.last_mut() {
+                chapter.entries.push(BookEntry {
+                    day: local.day(),
+                    weekday: WEEKDAYS[local.weekday().num_days_from_monday() as usize],
+                    hour: local.hour(),
+                    minute: format!("{:02}", local.minute()),
+                    lines: entry.content.trim_end().lines().map(clean_text).collect(),
+                    tags: entry.tags.iter().map(|tag| clean_text(&tag.name)).collect(),
+                });
+            }
+        }
+
+        let title = match &query.title {
+            Some(title) if !title.trim().is_empty() => clean_text(title.trim()),
+            _ if query.from.year() == query.to.year() => format!("{} {}", FEED_TITLE, query.from.year()),
+            _ => format!("{} {}–{}", FEED_TITLE, query.from.year(), query.to.year()),
+        };
+        let tag = tag.map(|tag| clean_text(&tag.name));
+        // 同じ条件で書き出した書籍は、リーダー上で同じ本として扱われるようにする
+        let digest = Sha256::digest(format!("{}\n{}\n{}\n{:?}", title, query.from, query.to, tag));
+
+        Ok(Book {
+            period: format!("{} 〜 {}", format_date(query.from), format_date(query.to)),
+            identifier: format!("urn:hobohi-diary:{}", &hex::encode(digest)[..32]),
+            vertical: query.writing_mode == WritingMode::Vertical,
+            entry_count: entries.len(),
+            title,
+            tag,
+            chapters,
+        })
+    }
+}
+
+impl Book {
+    /// EPUB 3 として書き出します。
+    pub async fn write_epub<W>(&self, writer: W) -> Result<(), BookServiceError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut zip = ZipFileWriter::with_tokio(writer);
+
+        // mimetype は先頭に無圧縮で置く必要がある
+        zip.write_entry_whole(ZipEntryBuilder::new("mimetype".into(), Compression::Stored), b"application/epub+zip")
+            .await?;
+        write_file(&mut zip, "META-INF/container.xml", CONTAINER_XML).await?;
+
+        let modified = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let package = PackageDocument { book: self, modified }.render()?;
+        write_file(&mut zip, "OEBPS/content.opf", &package).await?;
+        write_file(&mut zip, "OEBPS/style.css", &StyleSheet { book: self }.render()?).await?;
+        write_file(&mut zip, "OEBPS/cover.xhtml", &CoverDocument { book: self }.render()?).await?;
+        write_file(&mut zip, "OEBPS/nav.xhtml", &NavDocument { book: self }.render()?).await?;
+        for chapter in &self.chapters {
+            let document = ChapterDocument { book: self, chapter }.render()?;
+            write_file(&mut zip, &format!("OEBPS/{}.xhtml", chapter.id), &document).await?;
+        }
+
+        zip.close().await?;
+        Ok(())
+    }
+}
+
+async fn write_file<W>(zip: &mut ZipFileWriter<W>, path: &str, content: &str) -> Result<(), BookServiceError>
+where
+    W: AsyncWrite + Unpin,
+{
+    let builder = ZipEntryBuilder::new(path.to_string().into(), Compression::Deflate);
+    zip.write_entry_whole(builder, content.as_bytes()).await?;
+    Ok(())
+}
+
+fn format_date(date: NaiveDate) -> String {
+    format!("{}年{}月{}日", date.year(), date.month(), date.day())
+}
+
+// XML 1.0 で許可されない制御文字は取り除く
+fn clean_text(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control() || *c == '\t').collect()
+}
@@ -0,0 +1,161 @@
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::{MySqlPool, Result, Error as SqlxError};
+
+use crate::models::capture::{CaptureToken, CreatedCaptureToken};
+use crate::models::tag::EntryWithTags;
+use crate::repositories::capture_token_repository::CaptureTokenRepository;
+use crate::services::diary_service::{DiaryService, DiaryServiceError};
+
+// トークンのバイト数（16進表記ではこの2倍の長さになる）
+const TOKEN_BYTES: usize = 24;
+
+// エラー型を定義（サービス層固有のエラーを返す場合）
+#[derive(Debug)]
+pub enum CaptureServiceError {
+    DatabaseError(()),
+    // 指定されたトークンが存在しない（削除時）
+    NotFound,
+    // トークンが正しくない（投稿時）
+    Unauthorized,
+    // 名前や本文が空
+    EmptyContent,
+}
+
+// sqlx::ErrorからCaptureServiceErrorへの変換
+impl From<SqlxError> for CaptureServiceError {
+    fn from(err: SqlxError) -> Self {
+        tracing::error!("Database error occurred: {:?}", err);
+        CaptureServiceError::DatabaseError(())
+    }
+}
+
+// DiaryServiceErrorからCaptureServiceErrorへの変換（ログはDiaryService側で出力済み）
+impl From<DiaryServiceError> for CaptureServiceError {
+    fn from(err: DiaryServiceError) -> Self {
+        match err {
+            DiaryServiceError::DatabaseError(()) => CaptureServiceError::DatabaseError(()),
+        }
+    }
+}
+
+pub struct CaptureService<'a> {
+    token_repo: CaptureTokenRepository<'a>,
+    diary_service: DiaryService<'a>,
+}
+
+impl<'a> CaptureService<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self {
+            token_repo: CaptureTokenRepository::new(pool),
+            diary_service: DiaryService::new(pool),
+        }
+    }
+
+    /// 発行済みのトークンの一覧を取得します。
+    pub async fn get_tokens(&self) -> Result<Vec<CaptureToken>, CaptureServiceError> {
+        Ok(self.token_repo.find_tokens().await?)
+    }
+
+    /// 新しいトークンを発行します。トークン本体を返すのはこのときだけです。
+    pub async fn create_token(&self, name: &str) -> Result<CreatedCaptureToken, CaptureServiceError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(CaptureServiceError::EmptyContent);
+        }
+
+        let mut bytes = [0u8; TOKEN_BYTES];
+        rand::rng().fill_bytes(&mut bytes);
+        let token = hex::encode(bytes);
+        let now = chrono::Utc::now().naive_utc();
+        let id = self.token_repo.create_token(name, &hash_token(&token), now).await?;
+
+        Ok(CreatedCaptureToken {
+            id,
+            name: name.to_string(),
+            token,
+            datetime: now,
+        })
+    }
+
+    /// トークンを削除（失効）します。
+    pub async fn delete_token(&self, id: i32) -> Result<(), CaptureServiceError> {
+        if !self.token_repo.delete_token(id).await? {
+            return Err(CaptureServiceError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// トークンが有効かどうかを確認します（最終利用日時は更新しません）。
+    #[cfg(feature = "smtp")]
+    pub async fn verify_token(&self, token: &str) -> Result<bool, CaptureServiceError> {
+        Ok(self.token_repo.token_exists(&hash_token(token)).await?)
+    }
+
+    /// トークンを確認し、送られた本文からエントリを作成します。
+    /// 本文の先頭行が `#タグ` だけで構成されている場合は、その行をタグとして扱います。
+    /// 戻り値は作成されたエントリのIDです。
+    pub async fn capture(&self, token: &str, text: &str, tags: Vec<String>) -> Result<i32, CaptureServiceError> {
+        let now = chrono::Utc::now().naive_utc();
+        if self.token_repo.use_token(&hash_token(token), now).await?.is_none() {
+            return Err(CaptureServiceError::Unauthorized);
+        }
+
+        let mut entry = parse_capture(text);
+        for tag in tags {
+            let tag = tag.trim().trim_start_matches(['#', '＃']).to_string();
+            if !tag.is_empty() && !entry.tags.contains(&tag) {
+                entry.tags.push(tag);
+            }
+        }
+        if entry.content.trim().is_empty() {
+            return Err(CaptureServiceError::EmptyContent);
+        }
+
+        Ok(self.diary_service.create_entry_with_tags(&entry).await?)
+    }
+}
+
+/// 送られた本文を、先頭行のタグ（`#日記 #買い物` など）と本文に分けます。
+/// 先頭行に `#` で始まらない語が含まれる場合は、すべて本文として扱います。
+pub fn parse_capture(text: &str) -> EntryWithTags {
+    // 改行コードを揃え、前後の空行を除く
+    let text = text.replace("\r\n", "\n");
+    let text = text.trim_matches('\n');
+
+    let (first_line, rest) = text.split_once('\n').unwrap_or((text, ""));
+    let words: Vec<&str> = first_line.split_whitespace().collect();
+    let is_tag_line = !words.is_empty()
+        && words
+            .iter()
+            .all(|word| word.starts_with(['#', '＃']) && word.chars().count() > 1);
+    if !is_tag_line {
+        return EntryWithTags {
+            content: text.trim_end().to_string(),
+            tags: Vec::new(),
+            prompt_id: None,
+            unlock_at: None,
+            publish_at: None,
+        };
+    }
+
+    let mut tags: Vec<String> = Vec::new();
+    for word in words {
+        let tag = word.trim_start_matches(['#', '＃']).to_string();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    EntryWithTags {
+        content: rest.trim_matches('\n').trim_end().to_string(),
+        tags,
+        prompt_id: None,
+        unlock_at: None,
+        publish_at: None,
+    }
+}
+
+// トークンはハッシュにしてから照合・保存する
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.trim().as_bytes()))
+}
@@ -0,0 +1,87 @@
+use sqlx::{MySqlPool, Result, Error as SqlxError};
+use crate::models::comment::{Comment, CreateCommentRequest, UpdateCommentRequest};
+use crate::repositories::{
+    comment_repository::CommentRepository,
+    diary_repository::DiaryRepository,
+};
+
+// エラー型を定義（サービス層固有のエラーを返す場合）
+#[derive(Debug)]
+pub enum CommentServiceError {
+    DatabaseError(()),
+    // 対象のエントリまたはコメントが存在しない
+    NotFound,
+}
+
+// sqlx::ErrorからCommentServiceErrorへの変換
+impl From<SqlxError> for CommentServiceError {
+    fn from(err: SqlxError) -> Self {
+        tracing::error!("Database error occurred: {:?}", err);
+        CommentServiceError::DatabaseError(())
+    }
+}
+
+pub struct CommentService<'a> {
+    diary_repo: DiaryRepository<'a>,
+    comment_repo: CommentRepository<'a>,
+}
+
+impl<'a> CommentService<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self {
+            diary_repo: DiaryRepository::new(pool),
+            comment_repo: CommentRepository::new(pool),
+        }
+    }
+
+    /// エントリに付いたコメント一覧を取得します。
+    pub async fn get_comments(&self, entry_id: i32) -> Result<Vec<Comment>, CommentServiceError> {
+        if !self.diary_repo.entry_exists(entry_id).await? {
+            return Err(CommentServiceError::NotFound);
+        }
+        let comments = self.comment_repo.find_comments_for_entry(entry_id).await?;
+        Ok(comments)
+    }
+
+    /// エントリにコメントを追加し、作成されたコメントを返します。
+    pub async fn create_comment(
+        &self,
+        entry_id: i32,
+        request: &CreateCommentRequest,
+    ) -> Result<Comment, CommentServiceError> {
+        if !self.diary_repo.entry_exists(entry_id).await? {
+            return Err(CommentServiceError::NotFound);
+        }
+        let id = self
+            .comment_repo
+            .create_comment(entry_id, request.author.trim(), &request.content)
+            .await?;
+        self.comment_repo
+            .find_comment(id)
+            .await?
+            .ok_or(CommentServiceError::NotFound)
+    }
+
+    /// コメントの本文を更新し、更新後のコメントを返します。
+    pub async fn update_comment(
+        &self,
+        id: i32,
+        request: &UpdateCommentRequest,
+    ) -> Result<Comment, CommentServiceError> {
+        if !self.comment_repo.update_comment(id, &request.content).await? {
+            return Err(CommentServiceError::NotFound);
+        }
+        self.comment_repo
+            .find_comment(id)
+            .await?
+            .ok_or(CommentServiceError::NotFound)
+    }
+
+    /// コメントを削除します。
+    pub async fn delete_comment(&self, id: i32) -> Result<(), CommentServiceError> {
+        if !self.comment_repo.delete_comment(id).await? {
+            return Err(CommentServiceError::NotFound);
+        }
+        Ok(())
+    }
+}
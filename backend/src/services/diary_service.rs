@@ -1,13 +1,42 @@
-use sqlx::{MySqlPool, Result, Error as SqlxError};
+use std::collections::HashMap;
+
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use sqlx::{MySql, MySqlConnection, MySqlPool, Result, Error as SqlxError, Transaction};
 use crate::models::{
-    entry::{Entry, CreateEntryRequest}, // CreateEntryRequest は simple_create で使用
-    tag::EntryWithTags, // EntryWithTags は create_entry_with_tags で使用
+    entry::{Draft, Entry, EntryStatus, CreateEntryRequest, RandomEntryQuery, RandomMode, SaveDraftRequest}, // CreateEntryRequest は simple_create で使用
+    tag::{EntryWithTags, Tag}, // EntryWithTags は create_entry_with_tags で使用
 };
 use crate::repositories::{
-    diary_repository::DiaryRepository,
+    attachment_repository::AttachmentRepository,
+    comment_repository::CommentRepository,
+    diary_repository::{DiaryRepository, RandomEntryFilter},
+    prompt_repository::PromptRepository,
+    reaction_repository::ReactionRepository,
     tag_repository::TagRepository,
 };
-use crate::handlers::diary::{EntriesWithTagsResponse, EntryWithTagsResponse}; // ハンドラーのレスポンス型を再利用
+use crate::config;
+use crate::events::{self, DiaryEvent, RecordedEvent};
+use crate::importers::{self, ParsedImport};
+use crate::markdown;
+use crate::models::attachment::{Attachment, AttachmentResponse};
+use crate::models::reaction::ReactionCount;
+use crate::services::attachment_service;
+use crate::storage::Storage;
+use crate::models::import::{ImportIssue, ImportReport};
+use crate::handlers::diary::{EntriesWithTagsResponse, EntryWithTagsResponse, OnThisDayYear}; // ハンドラーのレスポンス型を再利用
+
+// 「再発見」で避ける、最近ランダム表示したエントリの期間（日数）
+const REDISCOVER_WINDOW_DAYS: u64 = 90;
+// 開封日時を過ぎたタイムカプセルを確認する間隔
+const UNLOCK_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+// 1回に確認するタイムカプセルの数
+const UNLOCK_BATCH_SIZE: u32 = 100;
+// 公開日時を過ぎた予約投稿を確認する間隔
+const PUBLISH_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+// 1回に確認する予約投稿の数
+const PUBLISH_BATCH_SIZE: u32 = 100;
 
 // エラー型を定義（サービス層固有のエラーを返す場合）
 #[derive(Debug)]
@@ -31,6 +60,10 @@ pub struct DiaryService<'a> {
     pool: &'a MySqlPool,
     diary_repo: DiaryRepository<'a>,
     tag_repo: TagRepository<'a>,
+    comment_repo: CommentRepository<'a>,
+    reaction_repo: ReactionRepository<'a>,
+    attachment_repo: AttachmentRepository<'a>,
+    prompt_repo: PromptRepository<'a>,
 }
 
 impl<'a> DiaryService<'a> {
@@ -39,6 +72,10 @@ impl<'a> DiaryService<'a> {
             pool,
             diary_repo: DiaryRepository::new(pool),
             tag_repo: TagRepository::new(), // TagRepositoryは現状poolを直接使わない
+            comment_repo: CommentRepository::new(pool),
+            reaction_repo: ReactionRepository::new(pool),
+            attachment_repo: AttachmentRepository::new(pool),
+            prompt_repo: PromptRepository::new(pool),
         }
     }
 
@@ -57,18 +94,7 @@ impl<'a> DiaryService<'a> {
 
         let total_pages = (count as f64 / limit as f64).ceil() as u32;
 
-        // エントリごとにタグを取得
-        let mut entries_with_tags_response = Vec::new();
-        for entry in entries {
-            // TagRepositoryを使ってタグを取得
-            let tags = self.tag_repo.find_tags_for_entry(self.pool, entry.id).await?;
-            entries_with_tags_response.push(EntryWithTagsResponse {
-                id: entry.id,
-                content: entry.content.clone(), // 必要に応じてclone
-                datetime: entry.datetime,
-                tags,
-            });
-        }
+        let entries_with_tags_response = self.build_entry_responses(entries).await?;
 
         Ok(EntriesWithTagsResponse {
             entries: entries_with_tags_response,
@@ -77,32 +103,293 @@ impl<'a> DiaryService<'a> {
         })
     }
 
+    /// エントリにタグ・コメント数・リアクション数を付与したレスポンスを構築します。
+    /// 関連データはエントリごとではなく、まとめて取得します。
+    /// 開封前のタイムカプセルは、本文を開封日時を知らせる文に置き換え、添付ファイルを含めません。
+    async fn build_entry_responses(
+        &self,
+        entries: Vec<Entry>,
+    ) -> Result<Vec<EntryWithTagsResponse>, DiaryServiceError> {
+        let mut conn = self.pool.acquire().await?;
+        self.build_entry_responses_with(&mut conn, entries).await
+    }
+
+    // `build_entry_responses` と同じ内容を、指定した接続（トランザクションなど）で読み込みます
+    async fn build_entry_responses_with(
+        &self,
+        conn: &mut MySqlConnection,
+        entries: Vec<Entry>,
+    ) -> Result<Vec<EntryWithTagsResponse>, DiaryServiceError> {
+        let entry_ids: Vec<i32> = entries.iter().map(|entry| entry.id).collect();
+
+        let details = EntryDetails {
+            tags: self.tag_repo.find_tags_for_entries(&mut *conn, &entry_ids).await?,
+            comment_counts: self.comment_repo.count_comments_for_entries(&mut *conn, &entry_ids).await?,
+            reaction_counts: self.reaction_repo.count_reactions_for_entries(&mut *conn, &entry_ids).await?,
+            attachments: self.attachment_repo.find_attachments_for_entries(&mut *conn, &entry_ids).await?,
+            prompt_ids: self.prompt_repo.find_prompt_ids_for_entries(&mut *conn, &entry_ids).await?,
+        };
+        Ok(details.into_responses(entries, chrono::Utc::now().naive_utc()))
+    }
+
+    /// 公開日時前の予約投稿（タグ付き）を公開日時の順に取得します。
+    pub async fn get_scheduled_entries(&self) -> Result<Vec<EntryWithTagsResponse>, DiaryServiceError> {
+        let entries = self.diary_repo.find_scheduled_entries().await?;
+        self.build_entry_responses(entries).await
+    }
+
+    /// IDでエントリ（タグ付き）を取得します。
+    pub async fn get_entry_with_details(&self, id: i32) -> Result<Option<EntryWithTagsResponse>, DiaryServiceError> {
+        let Some(entry) = self.diary_repo.find_entry(id).await? else {
+            return Ok(None);
+        };
+        Ok(self.build_entry_responses(vec![entry]).await?.pop())
+    }
+
     /// 新しいエントリとタグを作成します。
+    /// 戻り値は作成されたエントリのIDです。
     pub async fn create_entry_with_tags(
         &self,
         entry_data: &EntryWithTags, // ハンドラーから渡されるデータ構造
-    ) -> Result<(), DiaryServiceError> {
+    ) -> Result<i32, DiaryServiceError> {
         // トランザクション開始
         let mut tx = self.pool.begin().await?;
 
         // 1. エントリを作成 (リポジトリを使用)
-        let entry_id = DiaryRepository::create_entry_with_tags_tx(&mut tx, &entry_data.content).await?;
+        // 予約投稿の日時は公開日時にする
+        let datetime = entry_data.publish_at.unwrap_or_else(|| chrono::Utc::now().naive_utc());
+        let entry_id = DiaryRepository::create_entry_with_tags_tx(
+            &mut tx,
+            &entry_data.content,
+            datetime,
+            entry_data.unlock_at,
+            entry_data.publish_at,
+        )
+        .await?;
 
         // 2. タグを処理 (リポジトリを使用)
-        for tag_name in &entry_data.tags {
+        let created_tags = Self::attach_tags_tx(&mut tx, entry_id, &entry_data.tags).await?;
+
+        // 3. 答えたお題を記録
+        if let Some(prompt_id) = entry_data.prompt_id {
+            PromptRepository::associate_entry_with_prompt_tx(&mut tx, entry_id, prompt_id).await?;
+        }
+
+        // 4. イベントを記録（予約投稿の作成は公開日時に spawn_publish_worker が記録する）
+        let mut recorded = Self::record_tags_created_tx(&mut tx, &created_tags).await?;
+        if entry_data.publish_at.is_none() {
+            recorded.extend(self.record_entry_tx(&mut tx, entry_id, |entry| DiaryEvent::EntryCreated(entry)).await?);
+        }
+
+        // トランザクションのコミット
+        tx.commit().await?;
+
+        events::publish(recorded);
+        Ok(entry_id)
+    }
+
+    /// エントリの本文とタグを更新します。タグは指定されたものに置き換えます。
+    /// 戻り値は更新対象のエントリが存在したかどうかです。
+    pub async fn update_entry_with_tags(&self, id: i32, entry_data: &EntryWithTags) -> Result<bool, DiaryServiceError> {
+        let mut tx = self.pool.begin().await?;
+        if !DiaryRepository::update_entry_content_tx(&mut tx, id, &entry_data.content).await? {
+            return Ok(false);
+        }
+        TagRepository::remove_entry_tags_tx(&mut tx, id).await?;
+        let created_tags = Self::attach_tags_tx(&mut tx, id, &entry_data.tags).await?;
+        let mut recorded = Self::record_tags_created_tx(&mut tx, &created_tags).await?;
+        recorded.extend(self.record_entry_tx(&mut tx, id, |entry| DiaryEvent::EntryUpdated(entry)).await?);
+        tx.commit().await?;
+
+        events::publish(recorded);
+        Ok(true)
+    }
+
+    /// エントリを削除します。タグとの関連・コメント・リアクション・添付ファイルも一緒に削除されます。
+    /// 戻り値は削除対象のエントリが存在したかどうかです。
+    pub async fn delete_entry(&self, id: i32, storage: &dyn Storage) -> Result<bool, DiaryServiceError> {
+        let attachments = self.attachment_repo.find_attachments_for_entry(id).await?;
+        // 添付ファイルなどの行は外部キーの ON DELETE CASCADE で削除される
+        let mut tx = self.pool.begin().await?;
+        if !DiaryRepository::delete_entry_tx(&mut tx, id).await? {
+            return Ok(false);
+        }
+        let recorded = events::record(&mut tx, DiaryEvent::EntryDeleted { id }).await?;
+        tx.commit().await?;
+
+        events::publish([recorded]);
+        for attachment in &attachments {
+            attachment_service::delete_files(storage, attachment).await;
+        }
+        Ok(true)
+    }
+
+    // 変更後のエントリを同じトランザクションで読み込み、イベントとして記録します（SSEとWebhook）。
+    // エントリが見つからない場合（公開日時前の予約投稿など）は記録しません。
+    async fn record_entry_tx(
+        &self,
+        tx: &mut Transaction<'_, MySql>,
+        id: i32,
+        event: fn(&EntryWithTagsResponse) -> DiaryEvent<'_>,
+    ) -> Result<Option<RecordedEvent>, DiaryServiceError> {
+        let Some(entry) = DiaryRepository::find_entry_tx(tx, id).await? else {
+            return Ok(None);
+        };
+        let Some(entry) = self.build_entry_responses_with(tx, vec![entry]).await?.pop() else {
+            return Ok(None);
+        };
+        Ok(Some(events::record(tx, event(&entry)).await?))
+    }
+
+    async fn record_tags_created_tx(
+        tx: &mut Transaction<'_, MySql>,
+        tags: &[Tag],
+    ) -> Result<Vec<RecordedEvent>, DiaryServiceError> {
+        let mut recorded = Vec::with_capacity(tags.len());
+        for tag in tags {
+            recorded.push(events::record(tx, DiaryEvent::TagCreated(tag)).await?);
+        }
+        Ok(recorded)
+    }
+
+    /// 下書きの一覧を、最後に保存した日時の新しい順に取得します。
+    pub async fn get_drafts(&self) -> Result<Vec<Draft>, DiaryServiceError> {
+        let entries = self.diary_repo.find_drafts().await?;
+        let entry_ids: Vec<i32> = entries.iter().map(|entry| entry.id).collect();
+        let mut tags = self.tag_repo.find_tags_for_entries(self.pool, &entry_ids).await?;
+
+        Ok(entries
+            .into_iter()
+            .map(|entry| {
+                let tags = tags.remove(&entry.id).unwrap_or_default();
+                draft_from_entry(entry, tags)
+            })
+            .collect())
+    }
+
+    /// キーで下書きを取得します。
+    pub async fn get_draft(&self, key: &str) -> Result<Option<Draft>, DiaryServiceError> {
+        let Some(entry) = self.diary_repo.find_draft(key).await? else {
+            return Ok(None);
+        };
+        let tags = self.tag_repo.find_tags_for_entries(self.pool, &[entry.id]).await?.remove(&entry.id);
+        Ok(Some(draft_from_entry(entry, tags.unwrap_or_default())))
+    }
+
+    /// キーの下書きを保存します（自動保存）。下書きがなければ作成し、あれば本文とタグを置き換えます。
+    /// タグは名前だけを保存し、公開するまで作成しません（`tag.created` も公開時に配信します）。
+    /// 戻り値は保存後の下書きです。キーのエントリがすでに公開済みの場合は何も変更せず `None` を返します。
+    pub async fn save_draft(&self, key: &str, request: &SaveDraftRequest) -> Result<Option<Draft>, DiaryServiceError> {
+        let tags = importers::normalize_tags(&request.tags);
+        let mut tx = self.pool.begin().await?;
+        let now = chrono::Utc::now().naive_utc();
+        let (id, status) = DiaryRepository::upsert_draft_tx(&mut tx, key, &request.content, &tags, now).await?;
+        if status != EntryStatus::Draft {
+            return Ok(None);
+        }
+        // 以前の保存で関連付けたタグがあれば外す（タグは名前で保存するため）
+        TagRepository::remove_entry_tags_tx(&mut tx, id).await?;
+        tx.commit().await?;
+
+        Ok(Some(Draft {
+            key: key.to_string(),
+            content: request.content.clone(),
+            tags,
+            updated_at: now,
+        }))
+    }
+
+    /// キーの下書きを公開します。日時は公開した日時になり、下書きに付けていたタグをここで作成して関連付けます。
+    /// 戻り値は公開したエントリです。下書きがない場合は `None` を返します。
+    pub async fn publish_draft(&self, key: &str) -> Result<Option<EntryWithTagsResponse>, DiaryServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let now = chrono::Utc::now().naive_utc();
+        let Some((id, tags)) = DiaryRepository::publish_draft_tx(&mut tx, key, now).await? else {
+            return Ok(None);
+        };
+        let created_tags = Self::attach_tags_tx(&mut tx, id, &tags).await?;
+        let mut recorded = Self::record_tags_created_tx(&mut tx, &created_tags).await?;
+        recorded.extend(self.record_entry_tx(&mut tx, id, |entry| DiaryEvent::EntryCreated(entry)).await?);
+        tx.commit().await?;
+
+        events::publish(recorded);
+        self.get_entry_with_details(id).await
+    }
+
+    /// キーの下書きを削除します。
+    /// 戻り値は削除対象の下書きが存在したかどうかです。
+    pub async fn delete_draft(&self, key: &str) -> Result<bool, DiaryServiceError> {
+        Ok(self.diary_repo.delete_draft(key).await?)
+    }
+
+    /// 外部形式から読み込んだエントリを元の日時のまま取り込みます。
+    /// 本文が同じで、日記のタイムゾーンで同じ日の公開済みのエントリがすでにある記事は重複として報告し、スキップします。
+    /// 書き出し元によってタイムゾーンの扱いが違い、同じ記事でも時刻がずれることがあるため、時刻は比べません。
+    /// 一括の取り込みのため、`entry.created` や `tag.created` のイベントは記録しません（SSEにもWebhookにも配信しない）。
+    pub async fn import_entries(&self, parsed: ParsedImport) -> Result<ImportReport, DiaryServiceError> {
+        let mut report = ImportReport {
+            skipped: parsed.skipped,
+            ..Default::default()
+        };
+
+        // すべての記事を1つのトランザクションで取り込む
+        let timezone = config::diary_timezone();
+        let mut tx = self.pool.begin().await?;
+        for entry in parsed.entries {
+            // 同じトランザクション内で追加したエントリも対象になるため、インポート元の中での重複も検出できる
+            let hash = hex::encode(Sha256::digest(entry.content.as_bytes()));
+            let date = entry.datetime.and_utc().with_timezone(&timezone).date_naive();
+            let from = config::start_of_day_utc(date, timezone);
+            let to = from + Days::new(1);
+            if let Some(existing_id) = DiaryRepository::find_duplicate_entry_tx(&mut tx, &hash, from, to).await? {
+                report.duplicates.push(ImportIssue {
+                    title: entry.title,
+                    datetime: Some(entry.datetime),
+                    reason: format!("same content on the same day as entry {}", existing_id),
+                });
+                continue;
+            }
+
+            let entry_id =
+                DiaryRepository::create_entry_with_tags_tx(&mut tx, &entry.content, entry.datetime, None, None).await?;
+            Self::attach_tags_tx(&mut tx, entry_id, &entry.tags).await?;
+            report.imported += 1;
+        }
+        tx.commit().await?;
+
+        Ok(report)
+    }
+
+    // タグを検索または作成してエントリに関連付けます（トランザクション内）。
+    // 戻り値は新しく作成したタグです。
+    async fn attach_tags_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        entry_id: i32,
+        tag_names: &[String],
+    ) -> Result<Vec<Tag>, DiaryServiceError> {
+        let mut attached = Vec::new();
+        let mut created = Vec::new();
+        for tag_name in tag_names {
             if tag_name.trim().is_empty() {
                 continue; // 空のタグはスキップ
             }
             // タグを検索または作成
-            let tag_id = TagRepository::find_or_create_tag_tx(&mut tx, tag_name).await?;
+            let (tag_id, is_new) = TagRepository::find_or_create_tag_tx(tx, tag_name).await?;
+            if is_new {
+                created.push(Tag {
+                    id: tag_id,
+                    name: tag_name.clone(),
+                });
+            }
+            // 同じタグが複数回指定されても関連付けは1回だけにする
+            if attached.contains(&tag_id) {
+                continue;
+            }
             // エントリとタグを関連付け
-            TagRepository::associate_entry_with_tag_tx(&mut tx, entry_id, tag_id).await?;
+            TagRepository::associate_entry_with_tag_tx(tx, entry_id, tag_id).await?;
+            attached.push(tag_id);
         }
-
-        // トランザクションのコミット
-        tx.commit().await?;
-
-        Ok(())
+        Ok(created)
     }
 
     /// シンプルなエントリを作成します（タグなし）。
@@ -110,7 +397,12 @@ impl<'a> DiaryService<'a> {
         &self,
         request: &CreateEntryRequest,
     ) -> Result<(), DiaryServiceError> {
-        self.diary_repo.create_simple_entry(&request.content).await?;
+        let mut tx = self.pool.begin().await?;
+        let entry_id = DiaryRepository::create_simple_entry_tx(&mut tx, &request.content).await?;
+        let recorded = self.record_entry_tx(&mut tx, entry_id as i32, |entry| DiaryEvent::EntryCreated(entry)).await?;
+        tx.commit().await?;
+
+        events::publish(recorded);
         Ok(())
     }
 
@@ -120,13 +412,14 @@ impl<'a> DiaryService<'a> {
         Ok(count)
     }
 
-    /// タグIDでフィルタリングされたエントリ一覧とページネーション情報を取得します。
-    pub async fn get_entries_by_tag(
+    // タグIDでフィルタリングされたエントリ一覧と総ページ数を取得します。
+    // レスポンスには、タイムカプセルを封じる `get_entries_by_tag_with_details` を使います。
+    async fn get_entries_by_tag(
         &self,
         tag_id: i32,
         page: u32,
         limit: u32,
-    ) -> Result<(Vec<Entry>, u32), DiaryServiceError> { // 戻り値をタプルに変更 (EntriesResponseはハンドラー層で構築)
+    ) -> Result<(Vec<Entry>, u32), DiaryServiceError> {
         let offset = (page - 1) * limit;
 
         // エントリ取得
@@ -138,4 +431,334 @@ impl<'a> DiaryService<'a> {
 
         Ok((entries, total_pages))
     }
+
+    /// タグIDでフィルタリングされたエントリ一覧（タグ付き）とページネーション情報を取得します。
+    pub async fn get_entries_by_tag_with_details(
+        &self,
+        tag_id: i32,
+        page: u32,
+        limit: u32,
+    ) -> Result<EntriesWithTagsResponse, DiaryServiceError> {
+        let (entries, total_pages) = self.get_entries_by_tag(tag_id, page, limit).await?;
+        let entries = self.build_entry_responses(entries).await?;
+
+        Ok(EntriesWithTagsResponse {
+            entries,
+            total_pages,
+            current_page: page,
+        })
+    }
+
+    /// 指定された期間（`from` 以上 `to` 未満、UTC）のエントリ（タグ付き）を古い順に取得します。
+    /// `tag_id` を指定した場合は、そのタグが付いたエントリだけを取得します。
+    pub async fn get_entries_between_with_details(
+        &self,
+        from: chrono::NaiveDateTime,
+        to: chrono::NaiveDateTime,
+        tag_id: Option<i32>,
+    ) -> Result<Vec<EntryWithTagsResponse>, DiaryServiceError> {
+        let entries = self.diary_repo.find_entries_between(from, to, tag_id).await?;
+        self.build_entry_responses(entries).await
+    }
+
+    /// 過去の各年の同じ月日（`same_week` の場合は前後3日を含む1週間）に書いたエントリ（タグ付き）を、
+    /// 新しい年から順に年ごとにまとめて取得します。日付は日記のタイムゾーンで判定し、今年は含みません。
+    pub async fn get_entries_on_this_day(
+        &self,
+        month: u32,
+        day: u32,
+        same_week: bool,
+    ) -> Result<Vec<OnThisDayYear>, DiaryServiceError> {
+        let timezone = config::diary_timezone();
+        let this_year = chrono::Utc::now().with_timezone(&timezone).year();
+        let Some(first_datetime) = self.diary_repo.find_first_entry_datetime().await? else {
+            return Ok(Vec::new());
+        };
+        // 1週間の範囲は年をまたぐことがあるため、最初のエントリの前の年の分から調べる
+        let first_year = first_datetime.and_utc().with_timezone(&timezone).year();
+        let oldest_year = if same_week { first_year - 1 } else { first_year };
+
+        let mut years = Vec::new();
+        let mut ranges = Vec::new();
+        for year in (oldest_year..this_year).rev() {
+            let Some((from, to)) = day_range(year, month, day, same_week) else {
+                continue;
+            };
+            years.push(year);
+            ranges.push((config::start_of_day_utc(from, timezone), config::start_of_day_utc(to, timezone)));
+        }
+
+        let entries = self.diary_repo.find_entries_in_ranges(&ranges).await?;
+        let mut groups: Vec<OnThisDayYear> = years
+            .iter()
+            .map(|&year| OnThisDayYear {
+                year,
+                years_ago: this_year - year,
+                entries: Vec::new(),
+            })
+            .collect();
+        for entry in self.build_entry_responses(entries).await? {
+            if let Some(index) = ranges
+                .iter()
+                .position(|(from, to)| *from <= entry.datetime && entry.datetime < *to)
+            {
+                groups[index].entries.push(entry);
+            }
+        }
+        groups.retain(|group| !group.entries.is_empty());
+        Ok(groups)
+    }
+
+    /// 条件に合うエントリ（タグ付き）をランダムに1件取得します。
+    /// 「再発見」モードでは、最近ランダム表示していないエントリから選びます（すべて表示済みの場合は条件に合うすべてから選ぶ）。
+    /// 選んだエントリは表示の記録に残します。
+    pub async fn get_random_entry(
+        &self,
+        query: &RandomEntryQuery,
+    ) -> Result<Option<EntryWithTagsResponse>, DiaryServiceError> {
+        let timezone = config::diary_timezone();
+        let now = chrono::Utc::now().naive_utc();
+        let cutoff = now.checked_sub_days(Days::new(REDISCOVER_WINDOW_DAYS)).unwrap_or(now);
+        let mut filter = RandomEntryFilter {
+            tag_id: query.tag,
+            from: query.from.map(|from| config::start_of_day_utc(from, timezone)),
+            to: query
+                .to
+                .and_then(|to| to.succ_opt())
+                .map(|to| config::start_of_day_utc(to, timezone)),
+            not_viewed_since: (query.mode == RandomMode::Rediscover).then_some(cutoff),
+            unlocked_at: Some(now),
+        };
+
+        let mut range = self.diary_repo.find_random_candidate_id_range(&filter).await?;
+        if range.is_none() && filter.not_viewed_since.is_some() {
+            filter.not_viewed_since = None;
+            range = self.diary_repo.find_random_candidate_id_range(&filter).await?;
+        }
+        let Some((min_id, max_id)) = range else {
+            return Ok(None);
+        };
+
+        // IDの範囲から乱数で選び、その位置以降で最初のエントリを使う
+        // （IDの欠番の直後のエントリは選ばれやすくなるが、件数を数えて読み飛ばすより速い）
+        let from_id = rand::rng().random_range(min_id..=max_id);
+        let mut entry = self.diary_repo.find_random_candidate(&filter, from_id).await?;
+        if entry.is_none() {
+            // 範囲を取得した後に末尾のエントリが削除された場合は、先頭に戻って探す
+            entry = self.diary_repo.find_random_candidate(&filter, min_id).await?;
+        }
+        let Some(entry) = entry else {
+            return Ok(None);
+        };
+
+        self.diary_repo.record_entry_view(entry.id, now).await?;
+        // 表示の記録は「再発見」の期間より古いものを使わないため、都度削除して小さく保つ
+        self.diary_repo.delete_entry_views_before(cutoff).await?;
+
+        Ok(self.build_entry_responses(vec![entry]).await?.pop())
+    }
+}
+
+// エントリのレスポンスに付ける、まとめて取得した関連データ（キーはエントリID）
+struct EntryDetails {
+    tags: HashMap<i32, Vec<Tag>>,
+    comment_counts: HashMap<i32, i64>,
+    reaction_counts: HashMap<i32, Vec<ReactionCount>>,
+    attachments: HashMap<i32, Vec<Attachment>>,
+    prompt_ids: HashMap<i32, i32>,
+}
+
+impl EntryDetails {
+    // `now` の時点で開封前のタイムカプセルは、本文を開封日時を知らせる文に置き換え、添付ファイルを含めません
+    fn into_responses(mut self, entries: Vec<Entry>, now: NaiveDateTime) -> Vec<EntryWithTagsResponse> {
+        entries
+            .into_iter()
+            .map(|mut entry| {
+                let locked = entry.seal(now);
+                let entry_attachments = self.attachments.remove(&entry.id).unwrap_or_default();
+                EntryWithTagsResponse {
+                    id: entry.id,
+                    tags: self.tags.remove(&entry.id).unwrap_or_default(),
+                    comment_count: self.comment_counts.get(&entry.id).copied().unwrap_or(0),
+                    reaction_counts: self.reaction_counts.remove(&entry.id).unwrap_or_default(),
+                    attachments: if locked {
+                        Vec::new()
+                    } else {
+                        entry_attachments.into_iter().map(AttachmentResponse::from).collect()
+                    },
+                    prompt_id: self.prompt_ids.get(&entry.id).copied(),
+                    unlock_at: entry.unlock_at,
+                    locked,
+                    publish_at: entry.publish_at,
+                    content_html: markdown::render_entry(entry.id, &entry.content),
+                    content: entry.content,
+                    datetime: entry.datetime,
+                }
+            })
+            .collect()
+    }
+}
+
+// 下書きのエントリをレスポンスにします。
+// タグの名前を保存する前の下書きは、関連付けてあるタグ（`tags`）を使います。
+fn draft_from_entry(entry: Entry, tags: Vec<Tag>) -> Draft {
+    Draft {
+        key: entry.draft_key.unwrap_or_default(),
+        content: entry.content,
+        tags: match entry.draft_tags {
+            Some(draft_tags) => draft_tags.0,
+            None => tags.into_iter().map(|tag| tag.name).collect(),
+        },
+        updated_at: entry.datetime,
+    }
+}
+
+// `year` 年の `month` 月 `day` 日を含む期間（`from` 以上 `to` 未満の日付）を返します。
+// 2月29日がない年は、1日だけの場合は対象外、1週間の場合は2月28日を中心にします。
+fn day_range(year: i32, month: u32, day: u32, same_week: bool) -> Option<(NaiveDate, NaiveDate)> {
+    if !same_week {
+        let date = NaiveDate::from_ymd_opt(year, month, day)?;
+        return Some((date, date.succ_opt()?));
+    }
+    let center = NaiveDate::from_ymd_opt(year, month, day).or_else(|| NaiveDate::from_ymd_opt(year, month, day - 1))?;
+    Some((center.checked_sub_days(Days::new(3))?, center.checked_add_days(Days::new(4))?))
+}
+
+/// 開封日時を過ぎたタイムカプセルを確認し、`entry.unlocked` イベントを配信するタスクを起動します。
+/// 通知済みかどうかはDBに記録するため、停止中に開封日時を過ぎたタイムカプセルも起動後に1回だけ通知します。
+pub fn spawn_unlock_worker(pool: MySqlPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(UNLOCK_CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            // エラーのログはFromで出力済み
+            let _ = publish_opened_capsules(&pool).await;
+        }
+    });
+}
+
+// 開封日時を過ぎたタイムカプセルの開封を配信します
+async fn publish_opened_capsules(pool: &MySqlPool) -> Result<(), DiaryServiceError> {
+    let diary_service = DiaryService::new(pool);
+    loop {
+        let now = chrono::Utc::now().naive_utc();
+        let ids = diary_service.diary_repo.find_opened_capsule_ids(now, UNLOCK_BATCH_SIZE).await?;
+        for &id in &ids {
+            // 他のプロセスが先に通知した場合は配信しない
+            let mut tx = pool.begin().await?;
+            if DiaryRepository::mark_unlock_notified_tx(&mut tx, id).await? {
+                let recorded = diary_service
+                    .record_entry_tx(&mut tx, id, |entry| DiaryEvent::EntryUnlocked(entry))
+                    .await?;
+                tx.commit().await?;
+                events::publish(recorded);
+            }
+        }
+        if ids.len() < UNLOCK_BATCH_SIZE as usize {
+            return Ok(());
+        }
+    }
+}
+
+/// 公開日時を過ぎた予約投稿を確認するタスクを起動します。
+/// 予約投稿は公開日時から一覧などに含まれ、このタスクが `entry.created` イベントを配信します。
+/// 通知したかどうかはDBに記録するため、停止中に公開日時を過ぎた予約投稿も起動後に1回だけ配信します。
+pub fn spawn_publish_worker(pool: MySqlPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PUBLISH_CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            // エラーのログはFromで出力済み
+            let _ = publish_scheduled_entries(&pool).await;
+        }
+    });
+}
+
+// 公開日時を過ぎた予約投稿の作成を配信します
+async fn publish_scheduled_entries(pool: &MySqlPool) -> Result<(), DiaryServiceError> {
+    let diary_service = DiaryService::new(pool);
+    loop {
+        let ids = diary_service.diary_repo.find_due_scheduled_ids(PUBLISH_BATCH_SIZE).await?;
+        for &id in &ids {
+            // 他のプロセスが先に通知した場合は配信しない
+            let mut tx = pool.begin().await?;
+            if DiaryRepository::mark_publish_notified_tx(&mut tx, id).await? {
+                let recorded = diary_service
+                    .record_entry_tx(&mut tx, id, |entry| DiaryEvent::EntryCreated(entry))
+                    .await?;
+                tx.commit().await?;
+                events::publish(recorded);
+            }
+        }
+        if ids.len() < PUBLISH_BATCH_SIZE as usize {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use sqlx::types::Json;
+
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 5, day).unwrap().and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn entry(id: i32, content: &str, unlock_at: Option<NaiveDateTime>) -> Entry {
+        Entry {
+            id,
+            content: content.to_string(),
+            datetime: at(1, 9),
+            unlock_at,
+            status: EntryStatus::Published,
+            draft_key: None,
+            draft_tags: None,
+            publish_at: None,
+        }
+    }
+
+    fn attachment(id: i32, entry_id: i32) -> Attachment {
+        Attachment {
+            id,
+            entry_id,
+            storage_key: format!("key-{id}"),
+            filename: "photo.jpg".to_string(),
+            content_type: "image/jpeg".to_string(),
+            size: 10,
+            datetime: at(1, 9),
+            width: None,
+            height: None,
+            thumbnails: Json(Vec::new()),
+        }
+    }
+
+    #[test]
+    fn seals_locked_capsules_in_listings() {
+        let details = EntryDetails {
+            tags: HashMap::from([(2, vec![Tag { id: 1, name: "未来".to_string() }])]),
+            comment_counts: HashMap::new(),
+            reaction_counts: HashMap::new(),
+            attachments: HashMap::from([(1, vec![attachment(1, 1)]), (2, vec![attachment(2, 2)])]),
+            prompt_ids: HashMap::new(),
+        };
+        let entries = vec![entry(1, "開封済み", Some(at(2, 0))), entry(2, "未来への手紙", Some(at(30, 0)))];
+
+        let responses = details.into_responses(entries, at(10, 0));
+
+        assert!(!responses[0].locked);
+        assert_eq!(responses[0].content, "開封済み");
+        assert_eq!(responses[0].attachments.len(), 1);
+
+        let locked = &responses[1];
+        assert!(locked.locked);
+        assert!(!locked.content.contains("未来への手紙"));
+        assert!(!locked.content_html.contains("未来への手紙"));
+        assert!(locked.attachments.is_empty());
+        assert_eq!(locked.tags.len(), 1);
+    }
 }
@@ -0,0 +1,185 @@
+use async_zip::tokio::write::ZipFileWriter;
+use chrono::FixedOffset;
+use async_zip::{error::ZipError, Compression, ZipDateTime, ZipEntryBuilder};
+use futures::TryStreamExt;
+use sqlx::{MySqlPool, Result, Error as SqlxError};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::config;
+use crate::importers::movable_type;
+
+use crate::models::{entry::Entry, tag::Tag};
+use crate::repositories::{
+    diary_repository::DiaryRepository,
+    tag_repository::TagRepository,
+};
+
+// タグをまとめて取得する単位
+const EXPORT_CHUNK_SIZE: usize = 100;
+
+// エラー型を定義（サービス層固有のエラーを返す場合）
+#[derive(Debug)]
+pub enum ExportServiceError {
+    DatabaseError(()),
+    // 書き込みに失敗した（クライアントの切断を含む）
+    WriteError(()),
+}
+
+// sqlx::ErrorからExportServiceErrorへの変換
+impl From<SqlxError> for ExportServiceError {
+    fn from(err: SqlxError) -> Self {
+        tracing::error!("Database error occurred: {:?}", err);
+        ExportServiceError::DatabaseError(())
+    }
+}
+
+// async_zip::error::ZipErrorからExportServiceErrorへの変換
+impl From<ZipError> for ExportServiceError {
+    fn from(err: ZipError) -> Self {
+        tracing::error!("Failed to write zip archive: {:?}", err);
+        ExportServiceError::WriteError(())
+    }
+}
+
+// std::io::ErrorからExportServiceErrorへの変換
+impl From<std::io::Error> for ExportServiceError {
+    fn from(err: std::io::Error) -> Self {
+        tracing::error!("Failed to write export: {:?}", err);
+        ExportServiceError::WriteError(())
+    }
+}
+
+pub struct ExportService<'a> {
+    pool: &'a MySqlPool,
+    diary_repo: DiaryRepository<'a>,
+    tag_repo: TagRepository<'a>,
+}
+
+impl<'a> ExportService<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self {
+            pool,
+            diary_repo: DiaryRepository::new(pool),
+            tag_repo: TagRepository::new(),
+        }
+    }
+
+    /// すべてのエントリをMarkdownファイルにしたZIPを書き出します（開封前のタイムカプセルは本文を隠します）。
+    /// エントリはストリームで読み出し、一定件数ごとにタグを取得しながら逐次書き込みます。
+    pub async fn write_markdown_zip<W>(&self, writer: W) -> Result<(), ExportServiceError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut zip = ZipFileWriter::with_tokio(writer);
+        let now = chrono::Utc::now().naive_utc();
+        let timezone = config::diary_timezone();
+        let mut chunks = self
+            .diary_repo
+            .stream_all_entries()
+            .try_chunks(EXPORT_CHUNK_SIZE);
+
+        while let Some(entries) = chunks.try_next().await.map_err(|err| err.1)? {
+            let entry_ids: Vec<i32> = entries.iter().map(|entry| entry.id).collect();
+            let mut tags = self.tag_repo.find_tags_for_entries(self.pool, &entry_ids).await?;
+
+            for mut entry in entries {
+                // 開封前のタイムカプセルの本文は書き出さない
+                entry.seal(now);
+                let entry_tags = tags.remove(&entry.id).unwrap_or_default();
+                let path = markdown_path(&entry, timezone);
+                let builder = ZipEntryBuilder::new(path.into(), Compression::Deflate)
+                    .last_modification_date(ZipDateTime::from_chrono(&entry.datetime.and_utc()));
+                let document = render_markdown_document(&entry, &entry_tags);
+                zip.write_entry_whole(builder, document.as_bytes()).await?;
+            }
+        }
+
+        zip.close().await?;
+        Ok(())
+    }
+
+    /// すべてのエントリを Movable Type 形式で書き出します。
+    /// ZIPと同様に、エントリをストリームで読み出しながら逐次書き込みます。
+    pub async fn write_movable_type<W>(&self, mut writer: W) -> Result<(), ExportServiceError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let timezone = config::diary_timezone();
+        let now = chrono::Utc::now().naive_utc();
+        let mut chunks = self
+            .diary_repo
+            .stream_all_entries()
+            .try_chunks(EXPORT_CHUNK_SIZE);
+
+        while let Some(entries) = chunks.try_next().await.map_err(|err| err.1)? {
+            let entry_ids: Vec<i32> = entries.iter().map(|entry| entry.id).collect();
+            let mut tags = self.tag_repo.find_tags_for_entries(self.pool, &entry_ids).await?;
+
+            for mut entry in entries {
+                // 開封前のタイムカプセルの本文は書き出さない
+                entry.seal(now);
+                let entry_tags = tags.remove(&entry.id).unwrap_or_default();
+                let record = movable_type::render_entry(&entry, &entry_tags, timezone);
+                writer.write_all(record.as_bytes()).await?;
+            }
+        }
+
+        writer.shutdown().await?;
+        Ok(())
+    }
+}
+
+// ZIP内のパス（日記のタイムゾーンでの YYYY/MM/DD-id.md）
+fn markdown_path(entry: &Entry, timezone: FixedOffset) -> String {
+    let local = entry.datetime.and_utc().with_timezone(&timezone);
+    format!("{}-{}.md", local.format("%Y/%m/%d"), entry.id)
+}
+
+/// 日時とタグをYAMLのフロントマターに持つMarkdown文書を生成します。
+pub fn render_markdown_document(entry: &Entry, tags: &[Tag]) -> String {
+    let mut document = String::from("---\n");
+    document.push_str(&format!("id: {}\n", entry.id));
+    document.push_str(&format!("datetime: {}\n", entry.datetime.and_utc().to_rfc3339()));
+    if tags.is_empty() {
+        document.push_str("tags: []\n");
+    } else {
+        document.push_str("tags:\n");
+        for tag in tags {
+            // JSONの文字列はYAMLとしても有効なので、エスケープに利用する
+            let quoted = serde_json::to_string(&tag.name).unwrap_or_default();
+            document.push_str(&format!("  - {}\n", quoted));
+        }
+    }
+    document.push_str("---\n\n");
+    document.push_str(&entry.content);
+    if !entry.content.ends_with('\n') {
+        document.push('\n');
+    }
+    document
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::models::entry::EntryStatus;
+
+    #[test]
+    fn markdown_path_uses_diary_timezone_date() {
+        let entry = Entry {
+            id: 7,
+            content: "夜更かし".to_string(),
+            // 日本時間では 2024-05-02 01:30
+            datetime: NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_hms_opt(16, 30, 0).unwrap(),
+            unlock_at: None,
+            status: EntryStatus::Published,
+            draft_key: None,
+            draft_tags: None,
+            publish_at: None,
+        };
+
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        assert_eq!(markdown_path(&entry, tokyo), "2024/05/02-7.md");
+    }
+}
@@ -0,0 +1,263 @@
+use chrono::{NaiveDateTime, SecondsFormat};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::{MySqlPool, Result, Error as SqlxError};
+
+use crate::handlers::diary::EntryWithTagsResponse;
+use crate::repositories::tag_repository::TagRepository;
+use crate::services::diary_service::{DiaryService, DiaryServiceError};
+
+// フィードに含めるエントリ数
+pub const FEED_ENTRY_LIMIT: u32 = 20;
+pub const FEED_TITLE: &str = "ほぼ日だいあり";
+
+// エラー型を定義（サービス層固有のエラーを返す場合）
+#[derive(Debug)]
+pub enum FeedServiceError {
+    DatabaseError(()),
+    // 対象のタグが存在しない
+    NotFound,
+}
+
+// sqlx::ErrorからFeedServiceErrorへの変換
+impl From<SqlxError> for FeedServiceError {
+    fn from(err: SqlxError) -> Self {
+        tracing::error!("Database error occurred: {:?}", err);
+        FeedServiceError::DatabaseError(())
+    }
+}
+
+// DiaryServiceErrorからFeedServiceErrorへの変換（ログはDiaryService側で出力済み）
+impl From<DiaryServiceError> for FeedServiceError {
+    fn from(err: DiaryServiceError) -> Self {
+        match err {
+            DiaryServiceError::DatabaseError(()) => FeedServiceError::DatabaseError(()),
+        }
+    }
+}
+
+/// 生成済みのフィードと、条件付きリクエストの判定に使う情報
+pub struct Feed {
+    pub body: String,
+    pub content_type: &'static str,
+    pub etag: String,
+}
+
+impl Feed {
+    fn new(body: String, content_type: &'static str) -> Self {
+        let digest = Sha256::digest(body.as_bytes());
+        Self {
+            etag: format!("\"{}\"", &hex::encode(digest)[..32]),
+            body,
+            content_type,
+        }
+    }
+}
+
+// JSON Feed 1.1 の構造体
+#[derive(Serialize)]
+struct JsonFeed<'a> {
+    version: &'static str,
+    title: &'a str,
+    home_page_url: String,
+    feed_url: String,
+    items: Vec<JsonFeedItem<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedItem<'a> {
+    id: String,
+    url: String,
+    title: String,
+    content_text: &'a str,
+    content_html: &'a str,
+    date_published: String,
+    tags: Vec<&'a str>,
+}
+
+pub struct FeedService<'a> {
+    pool: &'a MySqlPool,
+    diary_service: DiaryService<'a>,
+    tag_repo: TagRepository<'a>,
+}
+
+impl<'a> FeedService<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self {
+            pool,
+            diary_service: DiaryService::new(pool),
+            tag_repo: TagRepository::new(),
+        }
+    }
+
+    /// 最新エントリのAtomフィードを生成します。
+    /// 公開範囲の概念はまだないため、すべてのエントリが対象です。
+    pub async fn atom_feed(&self) -> Result<Feed, FeedServiceError> {
+        let entries = self
+            .diary_service
+            .get_entries_with_details(1, FEED_ENTRY_LIMIT)
+            .await?
+            .entries;
+        let self_url = format!("{}/feed.atom", backend_url());
+        let body = render_atom(FEED_TITLE, &self_url, &format!("{}/", site_url()), &entries, entry_url);
+        Ok(Feed::new(body, "application/atom+xml; charset=utf-8"))
+    }
+
+    /// 最新エントリのJSON Feed（1.1）を生成します。
+    pub async fn json_feed(&self) -> Result<Feed, FeedServiceError> {
+        let entries = self
+            .diary_service
+            .get_entries_with_details(1, FEED_ENTRY_LIMIT)
+            .await?
+            .entries;
+
+        let feed = JsonFeed {
+            version: "https://jsonfeed.org/version/1.1",
+            title: FEED_TITLE,
+            home_page_url: format!("{}/", site_url()),
+            feed_url: format!("{}/feed.json", backend_url()),
+            items: entries
+                .iter()
+                .map(|entry| JsonFeedItem {
+                    id: entry_url(entry.id),
+                    url: entry_url(entry.id),
+                    title: entry_title(entry),
+                    content_text: &entry.content,
+                    content_html: &entry.content_html,
+                    date_published: format_rfc3339(entry.datetime),
+                    tags: entry.tags.iter().map(|tag| tag.name.as_str()).collect(),
+                })
+                .collect(),
+        };
+        // 構造体のシリアライズは失敗しない
+        let body = serde_json::to_string(&feed).unwrap_or_default();
+        Ok(Feed::new(body, "application/feed+json; charset=utf-8"))
+    }
+
+    /// 指定されたタグが付いたエントリのAtomフィードを生成します。
+    pub async fn tag_atom_feed(&self, tag_id: i32) -> Result<Feed, FeedServiceError> {
+        let tag = self
+            .tag_repo
+            .find_tag(self.pool, tag_id)
+            .await?
+            .ok_or(FeedServiceError::NotFound)?;
+        let entries = self
+            .diary_service
+            .get_entries_by_tag_with_details(tag_id, 1, FEED_ENTRY_LIMIT)
+            .await?
+            .entries;
+
+        let title = format!("{} - #{}", FEED_TITLE, tag.name);
+        let self_url = format!("{}/api/tags/{}/feed.atom", backend_url(), tag_id);
+        let body = render_atom(&title, &self_url, &format!("{}/", site_url()), &entries, entry_url);
+        Ok(Feed::new(body, "application/atom+xml; charset=utf-8"))
+    }
+}
+
+/// エントリ一覧をAtom（RFC 4287）形式のXMLに変換します。
+/// エントリのリンク先は `entry_url` で決めます（APIのフィードと静的サイトで異なるため）。
+pub fn render_atom(
+    title: &str,
+    self_url: &str,
+    home_url: &str,
+    entries: &[EntryWithTagsResponse],
+    entry_url: impl Fn(i32) -> String,
+) -> String {
+    // エントリがない場合も本文が変わらないよう、更新日時は固定値にする
+    let updated = entries
+        .iter()
+        .map(|entry| entry.datetime)
+        .max()
+        .map(format_rfc3339)
+        .unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string());
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str(&format!("  <title>{}</title>\n", escape_xml(title)));
+    xml.push_str(&format!("  <id>{}</id>\n", escape_xml(self_url)));
+    xml.push_str(&format!("  <link rel=\"alternate\" href=\"{}\"/>\n", escape_xml(home_url)));
+    xml.push_str(&format!("  <link rel=\"self\" href=\"{}\"/>\n", escape_xml(self_url)));
+    xml.push_str(&format!("  <updated>{}</updated>\n", updated));
+    xml.push_str(&format!("  <author><name>{}</name></author>\n", escape_xml(FEED_TITLE)));
+
+    for entry in entries {
+        let url = entry_url(entry.id);
+        let datetime = format_rfc3339(entry.datetime);
+        xml.push_str("  <entry>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", escape_xml(&entry_title(entry))));
+        xml.push_str(&format!("    <id>{}</id>\n", escape_xml(&url)));
+        xml.push_str(&format!("    <link rel=\"alternate\" href=\"{}\"/>\n", escape_xml(&url)));
+        xml.push_str(&format!("    <published>{}</published>\n", datetime));
+        xml.push_str(&format!("    <updated>{}</updated>\n", datetime));
+        for tag in &entry.tags {
+            xml.push_str(&format!("    <category term=\"{}\"/>\n", escape_xml(&tag.name)));
+        }
+        xml.push_str(&format!("    <content type=\"html\">{}</content>\n", escape_xml(&entry.content_html)));
+        xml.push_str("  </entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
+
+/// フロントエンドのURL（エントリへのリンクに使います）
+pub fn site_url() -> String {
+    std::env::var("SITE_URL")
+        .unwrap_or_else(|_| "http://localhost:3000".to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+// バックエンドのURL（フィード自身のURLに使う）
+fn backend_url() -> String {
+    std::env::var("BACKEND_URL")
+        .unwrap_or_else(|_| "http://localhost:9001".to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+fn entry_url(entry_id: i32) -> String {
+    format!("{}/#entry-{}", site_url(), entry_id)
+}
+
+// 日記にはタイトルがないため、本文の1行目をタイトルとして使う
+pub fn entry_title(entry: &EntryWithTagsResponse) -> String {
+    const MAX_TITLE_CHARS: usize = 40;
+
+    // Markdownの見出し記号は取り除く
+    let first_line = entry
+        .content
+        .lines()
+        .map(|line| line.trim().trim_start_matches('#').trim())
+        .find(|line| !line.is_empty());
+    match first_line {
+        Some(line) if line.chars().count() > MAX_TITLE_CHARS => {
+            format!("{}…", line.chars().take(MAX_TITLE_CHARS).collect::<String>())
+        }
+        Some(line) => line.to_string(),
+        None => entry.datetime.format("%Y-%m-%d %H:%M").to_string(),
+    }
+}
+
+// DBの日時はUTCで保存されている
+fn format_rfc3339(datetime: NaiveDateTime) -> String {
+    datetime.and_utc().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // XML 1.0 で許可されない制御文字は取り除く
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -1,2 +1,15 @@
+pub mod attachment_service;
+pub mod backup_service;
+pub mod book_service;
+pub mod capture_service;
+pub mod comment_service;
 pub mod diary_service;
+pub mod export_service;
+pub mod feed_service;
+pub mod prompt_service;
+pub mod reaction_service;
+pub mod reminder_service;
+pub mod site_service;
+pub mod stats_service;
 pub mod tag_service;
+pub mod webhook_service;
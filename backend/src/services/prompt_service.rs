@@ -0,0 +1,203 @@
+use chrono::{Days, NaiveDate};
+use sha2::{Digest, Sha256};
+use sqlx::{MySqlPool, Result, Error as SqlxError};
+
+use crate::config;
+use crate::models::prompt::{CreatePromptRequest, Prompt, TodayPromptResponse, UpdatePromptRequest};
+use crate::repositories::prompt_repository::PromptRepository;
+
+/// お題の言語として指定できる値
+pub const PROMPT_LANGUAGES: [&str; 2] = ["ja", "en"];
+
+// 今日のお題を選ぶときに避ける、最近選んだお題の期間（日数）
+const RECENT_PROMPT_DAYS: u64 = 30;
+// お題の長さの上限（文字数）
+const MAX_PROMPT_LENGTH: usize = 500;
+
+// 初期のお題（テキスト、言語）。お題が1件もない場合に起動時に登録します
+const DEFAULT_PROMPTS: &[(&str, &str)] = &[
+    ("今日いちばん印象に残った出来事は何ですか？", "ja"),
+    ("最近、ありがたいと感じたことを3つ書いてみましょう。", "ja"),
+    ("今日食べたもので、いちばんおいしかったものは？", "ja"),
+    ("最近よく考えていることは何ですか？", "ja"),
+    ("1年前の自分に伝えたいことは？", "ja"),
+    ("今週できるようになったこと、少し進んだことは？", "ja"),
+    ("今日話した人との会話で覚えていることは？", "ja"),
+    ("いま気になっている本・映画・音楽について書いてみましょう。", "ja"),
+    ("今日の天気と、そのときの気分は？", "ja"),
+    ("明日やってみたいことを1つ挙げるとしたら？", "ja"),
+    ("最近うまくいかなかったことと、そこから学んだことは？", "ja"),
+    ("子どもの頃に好きだった場所を思い出して書いてみましょう。", "ja"),
+    ("What was the best part of your day?", "en"),
+    ("Write down three things you are grateful for right now.", "en"),
+    ("What is something you learned this week?", "en"),
+    ("Describe a conversation you had today that stuck with you.", "en"),
+    ("What is on your mind more than usual lately?", "en"),
+    ("What would you tell yourself from one year ago?", "en"),
+    ("What made you laugh recently?", "en"),
+    ("Describe a place where you feel at ease.", "en"),
+    ("What is one small thing you want to do tomorrow?", "en"),
+    ("What challenged you today, and how did you handle it?", "en"),
+    ("Write about a meal you enjoyed recently.", "en"),
+    ("What are you looking forward to this month?", "en"),
+];
+
+// エラー型を定義（サービス層固有のエラーを返す場合）
+#[derive(Debug)]
+pub enum PromptServiceError {
+    DatabaseError(()),
+    // 指定されたお題が存在しない（今日のお題では、選べるお題がない）
+    NotFound,
+    // 本文や言語の指定が正しくない
+    InvalidRequest(String),
+}
+
+// sqlx::ErrorからPromptServiceErrorへの変換
+impl From<SqlxError> for PromptServiceError {
+    fn from(err: SqlxError) -> Self {
+        tracing::error!("Database error occurred: {:?}", err);
+        PromptServiceError::DatabaseError(())
+    }
+}
+
+pub struct PromptService<'a> {
+    prompt_repo: PromptRepository<'a>,
+}
+
+impl<'a> PromptService<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self {
+            prompt_repo: PromptRepository::new(pool),
+        }
+    }
+
+    /// お題の一覧を取得します。
+    pub async fn get_prompts(&self, language: Option<&str>) -> Result<Vec<Prompt>, PromptServiceError> {
+        if let Some(language) = language {
+            validate_language(language)?;
+        }
+        Ok(self.prompt_repo.find_prompts(language).await?)
+    }
+
+    /// IDでお題を取得します。
+    pub async fn get_prompt(&self, id: i32) -> Result<Prompt, PromptServiceError> {
+        self.prompt_repo.find_prompt(id).await?.ok_or(PromptServiceError::NotFound)
+    }
+
+    /// お題を登録し、登録されたお題を返します。
+    pub async fn create_prompt(&self, request: &CreatePromptRequest) -> Result<Prompt, PromptServiceError> {
+        validate_text(&request.text)?;
+        validate_language(&request.language)?;
+
+        let now = chrono::Utc::now().naive_utc();
+        let id = self
+            .prompt_repo
+            .create_prompt(request.text.trim(), &request.language, now)
+            .await?;
+        self.get_prompt(id).await
+    }
+
+    /// お題を更新し、更新後のお題を返します。
+    pub async fn update_prompt(&self, id: i32, request: &UpdatePromptRequest) -> Result<Prompt, PromptServiceError> {
+        let mut prompt = self.get_prompt(id).await?;
+
+        if let Some(text) = &request.text {
+            validate_text(text)?;
+            prompt.text = text.trim().to_string();
+        }
+        if let Some(language) = &request.language {
+            validate_language(language)?;
+            prompt.language = language.clone();
+        }
+        if let Some(enabled) = request.enabled {
+            prompt.enabled = enabled;
+        }
+
+        if !self.prompt_repo.update_prompt(&prompt).await? {
+            return Err(PromptServiceError::NotFound);
+        }
+        Ok(prompt)
+    }
+
+    /// お題を削除します。このお題に答えたエントリは残り、お題との関連だけが削除されます。
+    pub async fn delete_prompt(&self, id: i32) -> Result<(), PromptServiceError> {
+        if !self.prompt_repo.delete_prompt(id).await? {
+            return Err(PromptServiceError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// お題が1件も登録されていない場合に、初期のお題を登録します。
+    /// 戻り値は登録したお題の数です。
+    pub async fn seed_default_prompts(&self) -> Result<u64, PromptServiceError> {
+        let now = chrono::Utc::now().naive_utc();
+        Ok(self.prompt_repo.insert_prompts_if_empty(DEFAULT_PROMPTS, now).await?)
+    }
+
+    /// 今日のお題を取得します。
+    /// 同じ日（と言語）には同じお題を返します。初めて選ぶときは、最近選んだお題を避けて日付から決まるお題を選び、記録します。
+    pub async fn get_today_prompt(&self, language: Option<&str>) -> Result<TodayPromptResponse, PromptServiceError> {
+        if let Some(language) = language {
+            validate_language(language)?;
+        }
+        let key = language.unwrap_or("");
+        let today = chrono::Utc::now().with_timezone(&config::diary_timezone()).date_naive();
+
+        if let Some(prompt) = self.prompt_repo.find_daily_prompt(today, key).await? {
+            return Ok(TodayPromptResponse { date: today, prompt });
+        }
+
+        let since = today.checked_sub_days(Days::new(RECENT_PROMPT_DAYS));
+        let mut candidates = self.prompt_repo.find_candidate_ids(language, since).await?;
+        if candidates.is_empty() {
+            // すべて最近選んだ場合は、有効なお題すべてから選ぶ
+            candidates = self.prompt_repo.find_candidate_ids(language, None).await?;
+        }
+        if candidates.is_empty() {
+            return Err(PromptServiceError::NotFound);
+        }
+
+        let prompt_id = candidates[daily_index(today, key, candidates.len())];
+        self.prompt_repo.insert_daily_prompt(today, key, prompt_id).await?;
+
+        // 同時に選ばれた場合は先に記録されたお題を返す
+        let prompt = self
+            .prompt_repo
+            .find_daily_prompt(today, key)
+            .await?
+            .ok_or(PromptServiceError::NotFound)?;
+        Ok(TodayPromptResponse { date: today, prompt })
+    }
+}
+
+// 日付と言語から決まる、候補の中の位置
+fn daily_index(date: NaiveDate, language: &str, len: usize) -> usize {
+    let digest = Sha256::digest(format!("{}:{}", date, language).as_bytes());
+    let seed = u64::from_be_bytes(digest[..8].try_into().unwrap());
+    (seed % len as u64) as usize
+}
+
+fn validate_text(text: &str) -> Result<(), PromptServiceError> {
+    let length = text.trim().chars().count();
+    if length == 0 {
+        return Err(PromptServiceError::InvalidRequest("text cannot be empty".to_string()));
+    }
+    if length > MAX_PROMPT_LENGTH {
+        return Err(PromptServiceError::InvalidRequest(format!(
+            "text must be at most {} characters",
+            MAX_PROMPT_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+fn validate_language(language: &str) -> Result<(), PromptServiceError> {
+    if !PROMPT_LANGUAGES.contains(&language) {
+        return Err(PromptServiceError::InvalidRequest(format!(
+            "unknown language: {} (expected one of {})",
+            language,
+            PROMPT_LANGUAGES.join(", ")
+        )));
+    }
+    Ok(())
+}
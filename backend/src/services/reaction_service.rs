@@ -0,0 +1,72 @@
+use sqlx::{MySqlPool, Result, Error as SqlxError};
+use crate::models::reaction::{CreateReactionRequest, Reaction};
+use crate::repositories::{
+    diary_repository::DiaryRepository,
+    reaction_repository::ReactionRepository,
+};
+
+// エラー型を定義（サービス層固有のエラーを返す場合）
+#[derive(Debug)]
+pub enum ReactionServiceError {
+    DatabaseError(()),
+    // 対象のエントリまたはリアクションが存在しない
+    NotFound,
+}
+
+// sqlx::ErrorからReactionServiceErrorへの変換
+impl From<SqlxError> for ReactionServiceError {
+    fn from(err: SqlxError) -> Self {
+        tracing::error!("Database error occurred: {:?}", err);
+        ReactionServiceError::DatabaseError(())
+    }
+}
+
+pub struct ReactionService<'a> {
+    diary_repo: DiaryRepository<'a>,
+    reaction_repo: ReactionRepository<'a>,
+}
+
+impl<'a> ReactionService<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self {
+            diary_repo: DiaryRepository::new(pool),
+            reaction_repo: ReactionRepository::new(pool),
+        }
+    }
+
+    /// エントリに付いたリアクション一覧を取得します。
+    pub async fn get_reactions(&self, entry_id: i32) -> Result<Vec<Reaction>, ReactionServiceError> {
+        if !self.diary_repo.entry_exists(entry_id).await? {
+            return Err(ReactionServiceError::NotFound);
+        }
+        let reactions = self.reaction_repo.find_reactions_for_entry(entry_id).await?;
+        Ok(reactions)
+    }
+
+    /// エントリにリアクションを追加し、作成されたリアクションを返します。
+    pub async fn create_reaction(
+        &self,
+        entry_id: i32,
+        request: &CreateReactionRequest,
+    ) -> Result<Reaction, ReactionServiceError> {
+        if !self.diary_repo.entry_exists(entry_id).await? {
+            return Err(ReactionServiceError::NotFound);
+        }
+        let id = self
+            .reaction_repo
+            .create_reaction(entry_id, request.emoji.trim())
+            .await?;
+        self.reaction_repo
+            .find_reaction(id)
+            .await?
+            .ok_or(ReactionServiceError::NotFound)
+    }
+
+    /// リアクションを削除します。
+    pub async fn delete_reaction(&self, id: i32) -> Result<(), ReactionServiceError> {
+        if !self.reaction_repo.delete_reaction(id).await? {
+            return Err(ReactionServiceError::NotFound);
+        }
+        Ok(())
+    }
+}
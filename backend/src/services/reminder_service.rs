@@ -0,0 +1,236 @@
+use std::time::Duration;
+
+use sqlx::{MySqlPool, Result, Error as SqlxError};
+
+use crate::config;
+use crate::models::reminder::{CreateReminderRequest, Reminder, ReminderChannel, UpdateReminderRequest};
+use crate::notifiers::{Notifiers, ReminderNotice};
+use crate::repositories::{
+    diary_repository::DiaryRepository, reminder_repository::ReminderRepository, webhook_repository::WebhookRepository,
+};
+
+// 通知する時刻を過ぎたリマインダーを確認する間隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+// 記録するエラーの長さの上限
+const MAX_ERROR_LENGTH: usize = 1000;
+
+// エラー型を定義（サービス層固有のエラーを返す場合）
+#[derive(Debug)]
+pub enum ReminderServiceError {
+    DatabaseError(()),
+    // 指定されたリマインダーが存在しない
+    NotFound,
+    // 名前や送信先の指定が正しくない
+    InvalidRequest(String),
+}
+
+// sqlx::ErrorからReminderServiceErrorへの変換
+impl From<SqlxError> for ReminderServiceError {
+    fn from(err: SqlxError) -> Self {
+        tracing::error!("Database error occurred: {:?}", err);
+        ReminderServiceError::DatabaseError(())
+    }
+}
+
+pub struct ReminderService<'a> {
+    reminder_repo: ReminderRepository<'a>,
+    webhook_repo: WebhookRepository<'a>,
+}
+
+impl<'a> ReminderService<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self {
+            reminder_repo: ReminderRepository::new(pool),
+            webhook_repo: WebhookRepository::new(pool),
+        }
+    }
+
+    /// 登録されているリマインダーの一覧を取得します。
+    pub async fn get_reminders(&self) -> Result<Vec<Reminder>, ReminderServiceError> {
+        Ok(self.reminder_repo.find_reminders().await?)
+    }
+
+    /// リマインダーを登録し、登録されたリマインダーを返します。
+    /// 今日の通知する時刻をすでに過ぎている場合は、翌日から通知します。
+    pub async fn create_reminder(&self, request: &CreateReminderRequest) -> Result<Reminder, ReminderServiceError> {
+        let mut reminder = Reminder {
+            id: 0,
+            name: request.name.trim().to_string(),
+            remind_at: request.remind_at,
+            channel: request.channel,
+            webhook_id: request.webhook_id,
+            email: request.email.as_deref().map(|email| email.trim().to_string()),
+            enabled: true,
+            last_checked_on: None,
+            last_sent_at: None,
+            last_error: None,
+            datetime: chrono::Utc::now().naive_utc(),
+        };
+        self.validate(&reminder).await?;
+        skip_if_passed_today(&mut reminder);
+
+        let id = self.reminder_repo.create_reminder(&reminder).await?;
+        self.reminder_repo
+            .find_reminder(id)
+            .await?
+            .ok_or(ReminderServiceError::NotFound)
+    }
+
+    /// リマインダーの設定を更新し、更新後のリマインダーを返します。
+    /// 時刻の変更や有効化で今日の通知する時刻をすでに過ぎた場合は、翌日から通知します。
+    pub async fn update_reminder(
+        &self,
+        id: i32,
+        request: &UpdateReminderRequest,
+    ) -> Result<Reminder, ReminderServiceError> {
+        let mut reminder = self
+            .reminder_repo
+            .find_reminder(id)
+            .await?
+            .ok_or(ReminderServiceError::NotFound)?;
+
+        if let Some(name) = &request.name {
+            reminder.name = name.trim().to_string();
+        }
+        if let Some(remind_at) = request.remind_at {
+            reminder.remind_at = remind_at;
+        }
+        if let Some(channel) = request.channel {
+            reminder.channel = channel;
+        }
+        if let Some(webhook_id) = request.webhook_id {
+            reminder.webhook_id = Some(webhook_id);
+        }
+        if let Some(email) = &request.email {
+            reminder.email = Some(email.trim().to_string());
+        }
+        if let Some(enabled) = request.enabled {
+            reminder.enabled = enabled;
+        }
+        self.validate(&reminder).await?;
+        if request.remind_at.is_some() || request.enabled == Some(true) {
+            skip_if_passed_today(&mut reminder);
+        }
+
+        if !self.reminder_repo.update_reminder(&reminder).await? {
+            return Err(ReminderServiceError::NotFound);
+        }
+        Ok(reminder)
+    }
+
+    /// リマインダーを削除します。
+    pub async fn delete_reminder(&self, id: i32) -> Result<(), ReminderServiceError> {
+        if !self.reminder_repo.delete_reminder(id).await? {
+            return Err(ReminderServiceError::NotFound);
+        }
+        Ok(())
+    }
+
+    // 名前と、通知方法に応じた送信先を確認します
+    async fn validate(&self, reminder: &Reminder) -> Result<(), ReminderServiceError> {
+        if reminder.name.is_empty() || reminder.name.contains(['\r', '\n']) {
+            return Err(ReminderServiceError::InvalidRequest("name must be a single non-empty line".to_string()));
+        }
+        match reminder.channel {
+            ReminderChannel::Webhook => {
+                let Some(webhook_id) = reminder.webhook_id else {
+                    return Err(ReminderServiceError::InvalidRequest(
+                        "webhook_id is required for the webhook channel".to_string(),
+                    ));
+                };
+                if self.webhook_repo.find_webhook(webhook_id).await?.is_none() {
+                    return Err(ReminderServiceError::InvalidRequest(format!("webhook {} not found", webhook_id)));
+                }
+            }
+            ReminderChannel::Email => {
+                if !reminder.email.as_deref().is_some_and(is_valid_email) {
+                    return Err(ReminderServiceError::InvalidRequest(
+                        "a valid email is required for the email channel".to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// リマインダーを確認するタスクを起動します。
+/// 確認した日はDBに記録するため、再起動しても同じ日に2回通知することはなく、
+/// 停止中に通知する時刻を過ぎたリマインダーは起動後に確認します（その日のうちに限る）。
+pub fn spawn_scheduler(pool: MySqlPool) {
+    tokio::spawn(async move {
+        let notifiers = Notifiers::from_env(pool.clone());
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            // エラーのログはFromで出力済み
+            let _ = process_due_reminders(&pool, &notifiers).await;
+        }
+    });
+}
+
+// 通知する時刻を過ぎたリマインダーを確認し、今日のエントリがなければ通知します。
+// エントリには書いた人の情報がないため、誰かが今日書いていればすべてのリマインダーを通知しません
+async fn process_due_reminders(pool: &MySqlPool, notifiers: &Notifiers) -> Result<(), ReminderServiceError> {
+    let reminder_repo = ReminderRepository::new(pool);
+    let timezone = config::diary_timezone();
+    let now = chrono::Utc::now().with_timezone(&timezone);
+    let today = now.date_naive();
+
+    let reminders = reminder_repo.find_due_reminders(today, now.time()).await?;
+    if reminders.is_empty() {
+        return Ok(());
+    }
+
+    let from = config::start_of_day_utc(today, timezone);
+    let to = today
+        .succ_opt()
+        .map(|tomorrow| config::start_of_day_utc(tomorrow, timezone))
+        .unwrap_or(chrono::NaiveDateTime::MAX);
+    let written = DiaryRepository::new(pool).entry_exists_between(from, to).await?;
+
+    for reminder in &reminders {
+        // 他のプロセスが先に確認した場合は通知しない
+        if !reminder_repo.claim_reminder(reminder.id, today).await? {
+            continue;
+        }
+        if written {
+            continue;
+        }
+
+        let notice = ReminderNotice { reminder, date: today };
+        let result = match notifiers.get(reminder.channel) {
+            Ok(notifier) => notifier.notify(&notice).await,
+            Err(err) => Err(err),
+        };
+        let error = result.err().map(|err| {
+            tracing::warn!("Failed to send reminder {}: {}", reminder.id, err);
+            err.to_string().chars().take(MAX_ERROR_LENGTH).collect::<String>()
+        });
+        reminder_repo
+            .record_result(reminder.id, chrono::Utc::now().naive_utc(), error.as_deref())
+            .await?;
+    }
+    Ok(())
+}
+
+// 今日の通知する時刻を過ぎている場合は、今日は確認済みにします（登録や変更の直後に通知しないため）
+fn skip_if_passed_today(reminder: &mut Reminder) {
+    let now = chrono::Utc::now().with_timezone(&config::diary_timezone());
+    let today = now.date_naive();
+    if reminder.remind_at <= now.time() && reminder.last_checked_on.is_none_or(|checked_on| checked_on < today) {
+        reminder.last_checked_on = Some(today);
+    }
+}
+
+// メールアドレスとして送信できる形か（ヘッダーやコマンドに影響する文字を含まないか）を確認します
+fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.is_empty()
+        && email.len() <= 255
+        && !email.chars().any(|c| c.is_whitespace() || c.is_control() || matches!(c, '<' | '>' | ','))
+}
@@ -0,0 +1,489 @@
+use std::path::{Path, PathBuf};
+
+use askama::Template;
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime};
+use sqlx::{MySqlPool, Error as SqlxError};
+
+use crate::config;
+use crate::handlers::diary::{EntriesWithTagsResponse, EntryWithTagsResponse};
+use crate::models::tag::Tag;
+use crate::repositories::{
+    diary_repository::DiaryRepository,
+    tag_repository::TagRepository,
+};
+use crate::services::diary_service::{DiaryService, DiaryServiceError};
+use crate::services::feed_service::{self, FEED_ENTRY_LIMIT, FEED_TITLE};
+
+// 1ページあたりの件数（APIのエントリ一覧と揃える）
+const PAGE_SIZE: u32 = 10;
+// 生成したディレクトリに置く目印のファイル（目印のない空でないディレクトリは置き換えない）
+const MARKER_FILE: &str = ".diary-site";
+
+// エラー型を定義（サービス層固有のエラーを返す場合）
+#[derive(Debug)]
+pub enum SiteServiceError {
+    DatabaseError(()),
+    // テンプレートの描画、またはファイルの書き込みに失敗した
+    WriteError(()),
+    // 出力先が、以前に生成したものではない空でないディレクトリ
+    OutDirNotGenerated(()),
+}
+
+// sqlx::ErrorからSiteServiceErrorへの変換
+impl From<SqlxError> for SiteServiceError {
+    fn from(err: SqlxError) -> Self {
+        tracing::error!("Database error occurred: {:?}", err);
+        SiteServiceError::DatabaseError(())
+    }
+}
+
+// DiaryServiceErrorからSiteServiceErrorへの変換（ログはDiaryService側で出力済み）
+impl From<DiaryServiceError> for SiteServiceError {
+    fn from(err: DiaryServiceError) -> Self {
+        match err {
+            DiaryServiceError::DatabaseError(()) => SiteServiceError::DatabaseError(()),
+        }
+    }
+}
+
+// std::io::ErrorからSiteServiceErrorへの変換
+impl From<std::io::Error> for SiteServiceError {
+    fn from(err: std::io::Error) -> Self {
+        tracing::error!("Failed to write site: {:?}", err);
+        SiteServiceError::WriteError(())
+    }
+}
+
+// askama::ErrorからSiteServiceErrorへの変換
+impl From<askama::Error> for SiteServiceError {
+    fn from(err: askama::Error) -> Self {
+        tracing::error!("Failed to render template: {:?}", err);
+        SiteServiceError::WriteError(())
+    }
+}
+
+// テンプレートに渡すエントリ
+struct EntryView {
+    url: String,
+    title: String,
+    date: String,
+    content_html: String,
+    tags: Vec<TagView>,
+}
+
+struct TagView {
+    url: String,
+    name: String,
+}
+
+struct MonthView {
+    url: String,
+    label: String,
+}
+
+// 前後のページへのリンク
+struct Pagination {
+    current: u32,
+    total: u32,
+    prev: Option<String>,
+    next: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "site/entries.html")]
+struct EntriesPage<'a> {
+    base_url: &'a str,
+    site_title: &'a str,
+    heading: String,
+    entries: Vec<EntryView>,
+    pagination: Option<Pagination>,
+}
+
+#[derive(Template)]
+#[template(path = "site/entry.html")]
+struct EntryPage<'a> {
+    base_url: &'a str,
+    site_title: &'a str,
+    entry: EntryView,
+}
+
+#[derive(Template)]
+#[template(path = "site/tags.html")]
+struct TagsPage<'a> {
+    base_url: &'a str,
+    site_title: &'a str,
+    tags: Vec<TagView>,
+}
+
+#[derive(Template)]
+#[template(path = "site/archive.html")]
+struct ArchivePage<'a> {
+    base_url: &'a str,
+    site_title: &'a str,
+    months: Vec<MonthView>,
+}
+
+pub struct SiteService<'a> {
+    pool: &'a MySqlPool,
+    diary_repo: DiaryRepository<'a>,
+    tag_repo: TagRepository<'a>,
+    diary_service: DiaryService<'a>,
+}
+
+impl<'a> SiteService<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self {
+            pool,
+            diary_repo: DiaryRepository::new(pool),
+            tag_repo: TagRepository::new(),
+            diary_service: DiaryService::new(pool),
+        }
+    }
+
+    /// 公開エントリから静的サイトを生成し、`out_dir` に書き出します。
+    /// `base_url` はサイトを配置するURL（末尾は `/`）で、ページ間のリンクとフィードに使います。
+    /// 隣の一時ディレクトリに書き出してから `out_dir` と入れ替えるため、前回の生成で書き出したファイルのうち
+    /// 削除されたエントリやタグのページは残りません（`out_dir` に置いた他のファイルも消えます）。
+    /// `out_dir` が空でなく、以前の生成で置いた目印のファイルもない場合は、何も書き出さずにエラーを返します。
+    /// 書き出したファイル数を返します。
+    pub async fn generate(&self, out_dir: &Path, base_url: &str) -> Result<usize, SiteServiceError> {
+        let base_url = format!("{}/", base_url.trim_end_matches('/'));
+        let out_dir = std::path::absolute(out_dir)?;
+        if !is_replaceable(&out_dir).await? {
+            tracing::error!("{} is not empty and was not generated by the site command", out_dir.display());
+            return Err(SiteServiceError::OutDirNotGenerated(()));
+        }
+        let staging_dir = sibling_dir(&out_dir, "tmp")?;
+        // 前回の生成が途中で失敗した場合の残り
+        remove_dir_if_exists(&staging_dir).await?;
+
+        let mut writer = SiteWriter {
+            out_dir: staging_dir.clone(),
+            base_url: &base_url,
+            timezone: config::diary_timezone(),
+            written: 0,
+        };
+        let result = match self.write_site(&mut writer).await {
+            Ok(()) => tokio::fs::write(staging_dir.join(MARKER_FILE), "").await.map_err(SiteServiceError::from),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            if let Err(err) = remove_dir_if_exists(&staging_dir).await {
+                tracing::error!("Failed to remove {}: {:?}", staging_dir.display(), err);
+            }
+            return Err(err);
+        }
+
+        replace_dir(&staging_dir, &out_dir).await?;
+        Ok(writer.written)
+    }
+
+    // すべてのページとフィードを書き出します
+    async fn write_site(&self, writer: &mut SiteWriter<'_>) -> Result<(), SiteServiceError> {
+        self.write_index_pages(writer).await?;
+        self.write_tag_pages(writer).await?;
+        self.write_archive_pages(writer).await?;
+
+        let base_url = writer.base_url;
+        let feed_entries = self
+            .diary_service
+            .get_entries_with_details(1, FEED_ENTRY_LIMIT)
+            .await?
+            .entries;
+        let feed = feed_service::render_atom(
+            FEED_TITLE,
+            &format!("{}feed.atom", base_url),
+            base_url,
+            &feed_entries,
+            |id| format!("{}entries/{}/", base_url, id),
+        );
+        writer.write_file("feed.atom", &feed).await
+    }
+
+    // トップページ（ページ送りあり）と、各エントリのページを書き出します
+    async fn write_index_pages(&self, writer: &mut SiteWriter<'_>) -> Result<(), SiteServiceError> {
+        let mut page = 1;
+        loop {
+            let response = self.diary_service.get_entries_with_details(page, PAGE_SIZE).await?;
+            for entry in &response.entries {
+                let html = EntryPage {
+                    base_url: writer.base_url,
+                    site_title: FEED_TITLE,
+                    entry: writer.entry_view(entry),
+                }
+                .render()?;
+                writer.write_file(&format!("entries/{}/index.html", entry.id), &html).await?;
+            }
+
+            writer.write_listing("", FEED_TITLE.to_string(), &response).await?;
+            if page >= response.total_pages {
+                return Ok(());
+            }
+            page += 1;
+        }
+    }
+
+    // タグ一覧と、タグごとのエントリ一覧（ページ送りあり）を書き出します
+    async fn write_tag_pages(&self, writer: &mut SiteWriter<'_>) -> Result<(), SiteServiceError> {
+        let tags = self.tag_repo.find_all_tags(self.pool).await?;
+
+        for tag in &tags {
+            let mut page = 1;
+            loop {
+                let response = self
+                    .diary_service
+                    .get_entries_by_tag_with_details(tag.id, page, PAGE_SIZE)
+                    .await?;
+                writer
+                    .write_listing(&format!("tags/{}/", tag.id), format!("#{}", tag.name), &response)
+                    .await?;
+                if page >= response.total_pages {
+                    break;
+                }
+                page += 1;
+            }
+        }
+
+        let html = TagsPage {
+            base_url: writer.base_url,
+            site_title: FEED_TITLE,
+            tags: tags.iter().map(|tag| writer.tag_view(tag)).collect(),
+        }
+        .render()?;
+        writer.write_file("tags/index.html", &html).await
+    }
+
+    // 月別アーカイブの一覧と、月ごとのエントリ一覧を書き出します
+    async fn write_archive_pages(&self, writer: &mut SiteWriter<'_>) -> Result<(), SiteServiceError> {
+        let timezone = writer.timezone;
+        let months = self.diary_repo.find_entry_months(&timezone.to_string()).await?;
+
+        let mut month_views = Vec::with_capacity(months.len());
+        for (year, month) in months {
+            let (Some(from), Some(to)) = (
+                month_start_utc(year, month, timezone),
+                month_start_utc(year + month / 12, month % 12 + 1, timezone),
+            ) else {
+                continue;
+            };
+
+            let entries = self.diary_service.get_entries_between_with_details(from, to, None).await?;
+            let path = format!("archive/{:04}/{:02}/", year, month);
+            let label = format!("{}年{}月", year, month);
+            let html = EntriesPage {
+                base_url: writer.base_url,
+                site_title: FEED_TITLE,
+                heading: label.clone(),
+                entries: entries.iter().map(|entry| writer.entry_view(entry)).collect(),
+                pagination: None,
+            }
+            .render()?;
+            writer.write_file(&format!("{}index.html", path), &html).await?;
+
+            month_views.push(MonthView {
+                url: format!("{}{}", writer.base_url, path),
+                label,
+            });
+        }
+
+        let html = ArchivePage {
+            base_url: writer.base_url,
+            site_title: FEED_TITLE,
+            months: month_views,
+        }
+        .render()?;
+        writer.write_file("archive/index.html", &html).await
+    }
+}
+
+// 出力先と書き出したファイル数を管理します
+struct SiteWriter<'a> {
+    out_dir: PathBuf,
+    base_url: &'a str,
+    timezone: FixedOffset,
+    written: usize,
+}
+
+impl SiteWriter<'_> {
+    async fn write_file(&mut self, path: &str, content: &str) -> Result<(), SiteServiceError> {
+        let path = self.out_dir.join(path);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, content).await?;
+        self.written += 1;
+        Ok(())
+    }
+
+    // ページ送りのあるエントリ一覧を書き出します。
+    // 1ページ目は `{dir}index.html`、2ページ目以降は `{dir}page/{n}/index.html` です。
+    async fn write_listing(
+        &mut self,
+        dir: &str,
+        heading: String,
+        response: &EntriesWithTagsResponse,
+    ) -> Result<(), SiteServiceError> {
+        let page_url = |page: u32| match page {
+            1 => format!("{}{}", self.base_url, dir),
+            page => format!("{}{}page/{}/", self.base_url, dir, page),
+        };
+        let current = response.current_page;
+        let total = response.total_pages.max(1);
+        let pagination = Pagination {
+            current,
+            total,
+            prev: (current > 1).then(|| page_url(current - 1)),
+            next: (current < total).then(|| page_url(current + 1)),
+        };
+
+        let html = EntriesPage {
+            base_url: self.base_url,
+            site_title: FEED_TITLE,
+            heading,
+            entries: response.entries.iter().map(|entry| self.entry_view(entry)).collect(),
+            pagination: Some(pagination),
+        }
+        .render()?;
+
+        let path = match current {
+            1 => format!("{}index.html", dir),
+            page => format!("{}page/{}/index.html", dir, page),
+        };
+        self.write_file(&path, &html).await
+    }
+
+    fn entry_view(&self, entry: &EntryWithTagsResponse) -> EntryView {
+        EntryView {
+            url: format!("{}entries/{}/", self.base_url, entry.id),
+            title: feed_service::entry_title(entry),
+            date: entry
+                .datetime
+                .and_utc()
+                .with_timezone(&self.timezone)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+            content_html: entry.content_html.clone(),
+            tags: entry.tags.iter().map(|tag| self.tag_view(tag)).collect(),
+        }
+    }
+
+    fn tag_view(&self, tag: &Tag) -> TagView {
+        TagView {
+            url: format!("{}tags/{}/", self.base_url, tag.id),
+            name: tag.name.clone(),
+        }
+    }
+}
+
+// 出力先と同じ親ディレクトリに置く作業用のディレクトリ（`.{名前}.{suffix}`）
+// 同じファイルシステム上に置くことで、入れ替えをリネームだけで行えます
+fn sibling_dir(dir: &Path, suffix: &str) -> std::io::Result<PathBuf> {
+    let name = dir.file_name().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{} has no directory name", dir.display()))
+    })?;
+    Ok(dir.with_file_name(format!(".{}.{}", name.to_string_lossy(), suffix)))
+}
+
+async fn remove_dir_if_exists(dir: &Path) -> std::io::Result<()> {
+    match tokio::fs::remove_dir_all(dir).await {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+// 出力先が存在しない・空である・以前の生成で書き出したもの（目印のファイルがある）のいずれかかを返します
+async fn is_replaceable(out_dir: &Path) -> std::io::Result<bool> {
+    let mut entries = match tokio::fs::read_dir(out_dir).await {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(true),
+        Err(err) => return Err(err),
+    };
+    if entries.next_entry().await?.is_none() {
+        return Ok(true);
+    }
+    tokio::fs::try_exists(out_dir.join(MARKER_FILE)).await
+}
+
+// 書き出したディレクトリを出力先と入れ替え、以前の内容を削除します
+async fn replace_dir(staging_dir: &Path, out_dir: &Path) -> std::io::Result<()> {
+    let old_dir = sibling_dir(out_dir, "old")?;
+    remove_dir_if_exists(&old_dir).await?;
+
+    let had_previous = match tokio::fs::rename(out_dir, &old_dir).await {
+        Ok(()) => true,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => false,
+        Err(err) => return Err(err),
+    };
+    if let Err(err) = tokio::fs::rename(staging_dir, out_dir).await {
+        // 以前の内容を元に戻す
+        if had_previous {
+            tokio::fs::rename(&old_dir, out_dir).await?;
+        }
+        return Err(err);
+    }
+    if had_previous {
+        remove_dir_if_exists(&old_dir).await?;
+    }
+    Ok(())
+}
+
+// 日記のタイムゾーンでの月初を、DBと同じUTCの日時で返します
+fn month_start_utc(year: i64, month: i64, timezone: FixedOffset) -> Option<NaiveDateTime> {
+    let date = NaiveDate::from_ymd_opt(i32::try_from(year).ok()?, u32::try_from(month).ok()?, 1)?;
+    Some(config::start_of_day_utc(date, timezone))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn replaces_previous_output_without_stale_files() {
+        let root = tempfile::tempdir().unwrap();
+        let out_dir = root.path().join("public");
+        tokio::fs::create_dir_all(out_dir.join("entries/1")).await.unwrap();
+        tokio::fs::write(out_dir.join("entries/1/index.html"), "deleted entry").await.unwrap();
+        tokio::fs::write(out_dir.join(MARKER_FILE), "").await.unwrap();
+        assert!(is_replaceable(&out_dir).await.unwrap());
+
+        let staging_dir = sibling_dir(&out_dir, "tmp").unwrap();
+        tokio::fs::create_dir_all(staging_dir.join("entries/2")).await.unwrap();
+        tokio::fs::write(staging_dir.join("entries/2/index.html"), "entry").await.unwrap();
+
+        replace_dir(&staging_dir, &out_dir).await.unwrap();
+
+        assert!(!out_dir.join("entries/1").exists());
+        assert_eq!(tokio::fs::read_to_string(out_dir.join("entries/2/index.html")).await.unwrap(), "entry");
+        // 作業用のディレクトリは残らない
+        let mut names = Vec::new();
+        let mut dir = tokio::fs::read_dir(root.path()).await.unwrap();
+        while let Some(entry) = dir.next_entry().await.unwrap() {
+            names.push(entry.file_name());
+        }
+        assert_eq!(names, ["public"]);
+    }
+
+    #[tokio::test]
+    async fn creates_output_directory_on_first_run() {
+        let root = tempfile::tempdir().unwrap();
+        let out_dir = root.path().join("public");
+        let staging_dir = sibling_dir(&out_dir, "tmp").unwrap();
+        tokio::fs::create_dir_all(&staging_dir).await.unwrap();
+        tokio::fs::write(staging_dir.join("feed.atom"), "feed").await.unwrap();
+
+        replace_dir(&staging_dir, &out_dir).await.unwrap();
+
+        assert!(out_dir.join("feed.atom").exists());
+        assert!(!staging_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn refuses_to_replace_directories_it_did_not_generate() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(is_replaceable(&root.path().join("public")).await.unwrap());
+        assert!(is_replaceable(root.path()).await.unwrap());
+
+        tokio::fs::write(root.path().join("notes.txt"), "大事なメモ").await.unwrap();
+        assert!(!is_replaceable(root.path()).await.unwrap());
+    }
+}
@@ -0,0 +1,218 @@
+use chrono::{Days, NaiveDate};
+use sqlx::{MySqlPool, Result, Error as SqlxError};
+
+use crate::config;
+use crate::models::stats::{
+    ActivityQuery, ActivityResponse, HourCount, PeriodCount, StatsResponse, Streak, TagUsage, WeekdayCount,
+};
+use crate::repositories::{diary_repository::DiaryRepository, tag_repository::TagRepository};
+
+// よく使われているタグとして返す数
+const TOP_TAG_LIMIT: u32 = 10;
+
+// アクティビティで一度に取得できる日数（うるう年の1年間）
+const MAX_ACTIVITY_DAYS: u64 = 366;
+
+// 日・ISO週・月ごとに数えるときの期間の名前（`DATE_FORMAT` の書式）
+const DAY_FORMAT: &str = "%Y-%m-%d";
+const ISO_WEEK_FORMAT: &str = "%x-W%v";
+const MONTH_FORMAT: &str = "%Y-%m";
+
+const WEEKDAY_NAMES: [&str; 7] = ["月", "火", "水", "木", "金", "土", "日"];
+
+// エラー型を定義（サービス層固有のエラーを返す場合）
+#[derive(Debug)]
+pub enum StatsServiceError {
+    DatabaseError(()),
+    // 指定されたタグが存在しない
+    NotFound,
+    // 期間の指定が正しくない
+    InvalidRange,
+}
+
+// sqlx::ErrorからStatsServiceErrorへの変換
+impl From<SqlxError> for StatsServiceError {
+    fn from(err: SqlxError) -> Self {
+        tracing::error!("Database error occurred: {:?}", err);
+        StatsServiceError::DatabaseError(())
+    }
+}
+
+pub struct StatsService<'a> {
+    pool: &'a MySqlPool,
+    diary_repo: DiaryRepository<'a>,
+    tag_repo: TagRepository<'a>,
+}
+
+impl<'a> StatsService<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self {
+            pool,
+            diary_repo: DiaryRepository::new(pool),
+            tag_repo: TagRepository::new(),
+        }
+    }
+
+    /// 書いた記録の統計を取得します。日付・曜日・時刻は日記のタイムゾーンで数えます。
+    pub async fn get_stats(&self) -> Result<StatsResponse, StatsServiceError> {
+        let timezone = config::diary_timezone();
+        let offset = timezone.to_string();
+        let today = chrono::Utc::now().with_timezone(&timezone).date_naive();
+
+        let (total_entries, total_characters) = self.diary_repo.sum_entry_lengths().await?;
+        let dates = self.diary_repo.find_entry_dates(&offset).await?;
+        let by_weekday = self.diary_repo.count_entries_by_weekday(&offset).await?;
+        let by_hour = self.diary_repo.count_entries_by_hour(&offset).await?;
+        let top_tags = self.diary_repo.count_top_tags(TOP_TAG_LIMIT).await?;
+        let entries_per_day = self.count_by_period(&offset, DAY_FORMAT).await?;
+        let entries_per_week = self.count_by_period(&offset, ISO_WEEK_FORMAT).await?;
+        let entries_per_month = self.count_by_period(&offset, MONTH_FORMAT).await?;
+
+        let mut entries_by_weekday: Vec<WeekdayCount> = (0..7)
+            .map(|weekday| WeekdayCount {
+                weekday,
+                name: WEEKDAY_NAMES[weekday as usize],
+                count: 0,
+            })
+            .collect();
+        for (weekday, count) in by_weekday {
+            if let Some(bucket) = usize::try_from(weekday).ok().and_then(|i| entries_by_weekday.get_mut(i)) {
+                bucket.count = count;
+            }
+        }
+        let mut entries_by_hour: Vec<HourCount> = (0..24).map(|hour| HourCount { hour, count: 0 }).collect();
+        for (hour, count) in by_hour {
+            if let Some(bucket) = usize::try_from(hour).ok().and_then(|i| entries_by_hour.get_mut(i)) {
+                bucket.count = count;
+            }
+        }
+
+        Ok(StatsResponse {
+            total_entries,
+            total_characters,
+            average_length: if total_entries > 0 {
+                total_characters as f64 / total_entries as f64
+            } else {
+                0.0
+            },
+            days_written: dates.len() as i64,
+            first_entry_date: dates.first().copied(),
+            current_streak: current_streak(&dates, today),
+            longest_streak: longest_streak(&dates),
+            entries_per_day,
+            entries_per_week,
+            entries_per_month,
+            most_used_tags: top_tags
+                .into_iter()
+                .map(|(id, name, count)| TagUsage { id, name, count })
+                .collect(),
+            busiest_weekday: busiest(&entries_by_weekday, |bucket| bucket.count).copied(),
+            busiest_hour: busiest(&entries_by_hour, |bucket| bucket.count).copied(),
+            entries_by_weekday,
+            entries_by_hour,
+        })
+    }
+
+    // 期間ごとのエントリ数（`format` は期間の名前の書式）
+    async fn count_by_period(&self, timezone: &str, format: &str) -> Result<Vec<PeriodCount>, StatsServiceError> {
+        let counts = self.diary_repo.count_entries_by_period(timezone, format).await?;
+        Ok(counts.into_iter().map(|(period, count)| PeriodCount { period, count }).collect())
+    }
+
+    /// 日ごとのエントリ数と文字数を取得します（ヒートマップ用）。
+    /// 期間は日記のタイムゾーンの日付で、両端を含めて最大366日です。
+    pub async fn get_activity(&self, query: &ActivityQuery) -> Result<ActivityResponse, StatsServiceError> {
+        let timezone = config::diary_timezone();
+        let to = query
+            .to
+            .unwrap_or_else(|| chrono::Utc::now().with_timezone(&timezone).date_naive());
+        let from = match query.from {
+            Some(from) => from,
+            None => to
+                .checked_sub_days(Days::new(MAX_ACTIVITY_DAYS - 1))
+                .ok_or(StatsServiceError::InvalidRange)?,
+        };
+        if from > to || (to - from).num_days() >= MAX_ACTIVITY_DAYS as i64 {
+            return Err(StatsServiceError::InvalidRange);
+        }
+        if let Some(tag_id) = query.tag
+            && self.tag_repo.find_tag(self.pool, tag_id).await?.is_none()
+        {
+            return Err(StatsServiceError::NotFound);
+        }
+
+        let end = to.succ_opt().ok_or(StatsServiceError::InvalidRange)?;
+        let rows = self
+            .diary_repo
+            .count_entries_by_date(
+                config::start_of_day_utc(from, timezone),
+                config::start_of_day_utc(end, timezone),
+                &timezone.to_string(),
+                query.tag,
+            )
+            .await?;
+
+        let days = (to - from).num_days() as usize + 1;
+        let mut counts = vec![0; days];
+        let mut characters = vec![0; days];
+        for (date, count, length) in rows {
+            if let Ok(index) = usize::try_from((date - from).num_days())
+                && index < days
+            {
+                counts[index] = count;
+                characters[index] = length;
+            }
+        }
+
+        Ok(ActivityResponse {
+            from,
+            to,
+            counts,
+            characters,
+        })
+    }
+}
+
+// 連続して書いた期間を古い順に返します（`dates` は重複のない昇順）
+fn streaks(dates: &[NaiveDate]) -> Vec<Streak> {
+    let mut streaks: Vec<Streak> = Vec::new();
+    for &date in dates {
+        match streaks.last_mut() {
+            Some(streak) if streak.end.and_then(|end| end.succ_opt()) == Some(date) => {
+                streak.days += 1;
+                streak.end = Some(date);
+            }
+            _ => streaks.push(Streak {
+                days: 1,
+                start: Some(date),
+                end: Some(date),
+            }),
+        }
+    }
+    streaks
+}
+
+// 今日まで続いている連続記録。今日はまだ書いていなくても、昨日まで続いていれば途切れていないものとする
+fn current_streak(dates: &[NaiveDate], today: NaiveDate) -> Streak {
+    let yesterday = today.pred_opt();
+    streaks(dates)
+        .pop()
+        .filter(|streak| streak.end == Some(today) || streak.end == yesterday)
+        .unwrap_or_default()
+}
+
+// 最長の連続記録（同じ長さの場合は新しい方）
+fn longest_streak(dates: &[NaiveDate]) -> Streak {
+    streaks(dates)
+        .into_iter()
+        .max_by_key(|streak| streak.days)
+        .unwrap_or_default()
+}
+
+// 件数が最も多い区分（すべて 0 の場合は None）
+fn busiest<T>(buckets: &[T], count: impl Fn(&T) -> i64) -> Option<&T> {
+    buckets
+        .iter()
+        .filter(|bucket| count(bucket) > 0)
+        .reduce(|best, bucket| if count(bucket) > count(best) { bucket } else { best })
+}
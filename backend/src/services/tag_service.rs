@@ -1,4 +1,5 @@
 use sqlx::{MySqlPool, Result, Error as SqlxError};
+use crate::events::{self, DiaryEvent};
 use crate::models::tag::Tag;
 use crate::repositories::tag_repository::TagRepository;
 
@@ -53,8 +54,7 @@ impl<'a> TagService<'a> {
 
         // トランザクションを開始してタグの検索または作成を行う
         let mut tx = self.pool.begin().await?;
-        let tag_id = TagRepository::find_or_create_tag_tx(&mut tx, tag_name).await?;
-        tx.commit().await?; // トランザクションをコミット
+        let (tag_id, is_new) = TagRepository::find_or_create_tag_tx(&mut tx, tag_name).await?;
 
         // IDが分かったので、完全なTagオブジェクトを取得して返す
         // (find_or_create_tag_txはIDしか返さないため)
@@ -62,9 +62,18 @@ impl<'a> TagService<'a> {
         // 今回はシンプルに再度取得する。
         let tag = sqlx::query_as::<_, Tag>("SELECT id, name FROM tag WHERE id = ?")
             .bind(tag_id)
-            .fetch_one(self.pool)
+            .fetch_one(&mut *tx)
             .await?; // エラーはFromトレイトによりTagServiceErrorに変換される
 
+        // 作成の配信はタグと同じトランザクションで記録する
+        let recorded = if is_new {
+            Some(events::record(&mut tx, DiaryEvent::TagCreated(&tag)).await?)
+        } else {
+            None
+        };
+        tx.commit().await?; // トランザクションをコミット
+
+        events::publish(recorded);
         Ok(tag)
     }
 
@@ -0,0 +1,640 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use hmac::{Hmac, KeyInit, Mac};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use sha2::Sha256;
+use sqlx::{MySql, MySqlPool, Result, Error as SqlxError, Transaction};
+use tokio::sync::Notify;
+
+use crate::events::EVENT_TYPES;
+use crate::models::webhook::{
+    CreateWebhookRequest, DeliveryStatus, PendingDelivery, UpdateWebhookRequest, WebhookDeliveriesResponse,
+    WebhookResponse,
+};
+use crate::repositories::webhook_repository::{DeliveryAttempt, WebhookRepository};
+
+// 送信を試みる回数の上限（超えたら失敗として記録する）
+const MAX_ATTEMPTS: i32 = 10;
+// 1回目の再送までの待ち時間（以降は倍にしていく）
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(30);
+// 再送までの待ち時間の上限
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(6 * 60 * 60);
+// 送信のタイムアウト
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+// 取り出した配信を送信中として扱う時間（この間に結果を記録できなければ再送する）
+const LEASE_DURATION: Duration = Duration::from_secs(5 * 60);
+// 新しい配信の通知がなくても、送信日時を過ぎた配信を確認する間隔
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+// 1回に取り出す配信の数
+const BATCH_SIZE: u32 = 20;
+// 記録するレスポンス・エラーの長さの上限
+const MAX_ERROR_LENGTH: usize = 1000;
+
+// 配信を追加したことを送信タスクに知らせる
+static WAKE: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+// エラー型を定義（サービス層固有のエラーを返す場合）
+#[derive(Debug)]
+pub enum WebhookServiceError {
+    DatabaseError(()),
+    // 指定されたWebhookが存在しない
+    NotFound,
+    // URLやイベントの種類の指定が正しくない
+    InvalidRequest(String),
+}
+
+// sqlx::ErrorからWebhookServiceErrorへの変換
+impl From<SqlxError> for WebhookServiceError {
+    fn from(err: SqlxError) -> Self {
+        tracing::error!("Database error occurred: {:?}", err);
+        WebhookServiceError::DatabaseError(())
+    }
+}
+
+pub struct WebhookService<'a> {
+    webhook_repo: WebhookRepository<'a>,
+}
+
+impl<'a> WebhookService<'a> {
+    pub fn new(pool: &'a MySqlPool) -> Self {
+        Self {
+            webhook_repo: WebhookRepository::new(pool),
+        }
+    }
+
+    /// 登録されているWebhookの一覧を取得します。
+    pub async fn get_webhooks(&self) -> Result<Vec<WebhookResponse>, WebhookServiceError> {
+        let webhooks = self.webhook_repo.find_webhooks().await?;
+        Ok(webhooks.into_iter().map(WebhookResponse::from).collect())
+    }
+
+    /// Webhookを登録し、登録されたWebhookを返します。
+    pub async fn create_webhook(&self, request: &CreateWebhookRequest) -> Result<WebhookResponse, WebhookServiceError> {
+        validate_url(&request.url)?;
+        validate_secret(&request.secret)?;
+        validate_event_types(&request.event_types)?;
+
+        let id = self
+            .webhook_repo
+            .create_webhook(request.url.trim(), &request.secret, &request.event_types)
+            .await?;
+        self.webhook_repo
+            .find_webhook(id)
+            .await?
+            .map(WebhookResponse::from)
+            .ok_or(WebhookServiceError::NotFound)
+    }
+
+    /// Webhookの設定を更新し、更新後のWebhookを返します。
+    pub async fn update_webhook(
+        &self,
+        id: i32,
+        request: &UpdateWebhookRequest,
+    ) -> Result<WebhookResponse, WebhookServiceError> {
+        let mut webhook = self
+            .webhook_repo
+            .find_webhook(id)
+            .await?
+            .ok_or(WebhookServiceError::NotFound)?;
+
+        if let Some(url) = &request.url {
+            validate_url(url)?;
+            webhook.url = url.trim().to_string();
+        }
+        if let Some(secret) = &request.secret {
+            validate_secret(secret)?;
+            webhook.secret = secret.clone();
+        }
+        if let Some(event_types) = &request.event_types {
+            validate_event_types(event_types)?;
+            webhook.event_types.0 = event_types.clone();
+        }
+        if let Some(enabled) = request.enabled {
+            webhook.enabled = enabled;
+        }
+
+        if !self.webhook_repo.update_webhook(&webhook).await? {
+            return Err(WebhookServiceError::NotFound);
+        }
+        Ok(WebhookResponse::from(webhook))
+    }
+
+    /// Webhookを削除します。未送信の配信と配信ログも削除されます。
+    pub async fn delete_webhook(&self, id: i32) -> Result<(), WebhookServiceError> {
+        if !self.webhook_repo.delete_webhook(id).await? {
+            return Err(WebhookServiceError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Webhookの配信ログ（新しい順）とページネーション情報を取得します。
+    pub async fn get_deliveries(
+        &self,
+        webhook_id: i32,
+        page: u32,
+        limit: u32,
+    ) -> Result<WebhookDeliveriesResponse, WebhookServiceError> {
+        if self.webhook_repo.find_webhook(webhook_id).await?.is_none() {
+            return Err(WebhookServiceError::NotFound);
+        }
+        let offset = (page.max(1) - 1) * limit;
+        let deliveries = self.webhook_repo.find_deliveries(webhook_id, limit, offset).await?;
+        let count = self.webhook_repo.count_deliveries(webhook_id).await?;
+
+        Ok(WebhookDeliveriesResponse {
+            deliveries,
+            total_pages: (count as f64 / limit as f64).ceil() as u32,
+            current_page: page,
+        })
+    }
+}
+
+/// イベントを購読しているWebhookへの配信を、イベントの元になった変更と同じトランザクションでキューに追加します。
+/// `data` はイベントの内容（JSON）で、送信するボディの `data` になります。
+/// コミットした後に `wake_worker` で送信タスクに知らせます。
+pub async fn enqueue_deliveries_tx(
+    tx: &mut Transaction<'_, MySql>,
+    event_type: &str,
+    data: &str,
+) -> Result<(), SqlxError> {
+    let now = chrono::Utc::now();
+    let payload = envelope(event_type, data, now)
+        .map_err(|_| SqlxError::Protocol(format!("invalid {} event data", event_type)))?;
+    WebhookRepository::enqueue_deliveries_tx(tx, event_type, &payload, now.naive_utc()).await?;
+    Ok(())
+}
+
+/// キューに追加した配信があることを送信タスクに知らせます。
+pub fn wake_worker() {
+    WAKE.notify_one();
+}
+
+/// 購読しているイベントの種類にかかわらず、指定したWebhookへの配信をキューに追加します。
+/// 送信・署名・再送は購読による配信と同じです。Webhookが存在しないか無効の場合は `NotFound` を返します。
+pub async fn enqueue_delivery(
+    pool: &MySqlPool,
+    webhook_id: i32,
+    event_type: &str,
+    data: &str,
+) -> Result<(), WebhookServiceError> {
+    let now = chrono::Utc::now();
+    let payload = envelope(event_type, data, now)?;
+
+    if !WebhookRepository::new(pool)
+        .enqueue_delivery(webhook_id, event_type, &payload, now.naive_utc())
+        .await?
+    {
+        return Err(WebhookServiceError::NotFound);
+    }
+    WAKE.notify_one();
+    Ok(())
+}
+
+// 送信するボディ（イベントの種類・発生日時・内容）を組み立てます
+fn envelope(event_type: &str, data: &str, now: chrono::DateTime<chrono::Utc>) -> Result<String, WebhookServiceError> {
+    let data: serde_json::Value = serde_json::from_str(data).map_err(|e| {
+        tracing::error!("Invalid {} event data: {:?}", event_type, e);
+        WebhookServiceError::InvalidRequest(e.to_string())
+    })?;
+    Ok(serde_json::json!({
+        "type": event_type,
+        "created_at": now.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        "data": data,
+    })
+    .to_string())
+}
+
+/// キューの配信を送信するタスクを起動します。
+/// 未送信の配信はDBに残るため、再起動後も続きから送信します。
+pub fn spawn_worker(pool: MySqlPool) {
+    tokio::spawn(async move {
+        let client = match WebhookClient::new(allow_private_networks()) {
+            Ok(client) => client,
+            Err(err) => {
+                tracing::error!("Failed to build webhook client: {:?}", err);
+                return;
+            }
+        };
+
+        loop {
+            // 取り出せる配信がなくなるまで送信する
+            while let Ok(count) = process_due_deliveries(&pool, &client).await {
+                if count < BATCH_SIZE as usize {
+                    break;
+                }
+            }
+            tokio::select! {
+                _ = WAKE.notified() => {}
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            }
+        }
+    });
+}
+
+// 送信日時を過ぎた配信を取り出して送信し、取り出した数を返します
+async fn process_due_deliveries(pool: &MySqlPool, client: &WebhookClient) -> Result<usize, WebhookServiceError> {
+    let repo = WebhookRepository::new(pool);
+    let now = chrono::Utc::now().naive_utc();
+    let lease_until = now + LEASE_DURATION;
+    let deliveries = repo.claim_due_deliveries(now, lease_until, BATCH_SIZE).await?;
+
+    let results = futures::future::join_all(deliveries.iter().map(|delivery| send(client, delivery))).await;
+    for (delivery, result) in deliveries.iter().zip(results) {
+        let attempt = attempt_result(delivery, &result, chrono::Utc::now().naive_utc());
+        if attempt.status == DeliveryStatus::Failed {
+            tracing::warn!("Giving up webhook delivery {} after {} attempts", delivery.id, attempt.attempts);
+        }
+        repo.record_attempt(delivery.id, &attempt).await?;
+    }
+    Ok(deliveries.len())
+}
+
+// 送信の結果から、記録する配信の状態と次に送信する日時を決めます
+fn attempt_result<'a>(
+    delivery: &PendingDelivery,
+    result: &'a Result<u16, SendError>,
+    attempted_at: chrono::NaiveDateTime,
+) -> DeliveryAttempt<'a> {
+    let attempts = delivery.attempts + 1;
+    let (response_status, error) = match result {
+        Ok(status) => (Some(i32::from(*status)), None),
+        Err(SendError { status, message }) => (status.map(i32::from), Some(message.as_str())),
+    };
+    let (status, next_attempt_at) = match result {
+        Ok(_) => (DeliveryStatus::Succeeded, attempted_at),
+        Err(_) if attempts >= MAX_ATTEMPTS => (DeliveryStatus::Failed, attempted_at),
+        Err(_) => (DeliveryStatus::Pending, attempted_at + retry_delay(attempts)),
+    };
+    DeliveryAttempt {
+        status,
+        attempts,
+        attempted_at,
+        next_attempt_at,
+        response_status,
+        error,
+    }
+}
+
+// 配信の送信に使うHTTPクライアント
+struct WebhookClient {
+    http: reqwest::Client,
+    // ループバック・プライベート・リンクローカルのアドレスへの送信を許可するか
+    allow_private_networks: bool,
+}
+
+impl WebhookClient {
+    fn new(allow_private_networks: bool) -> reqwest::Result<Self> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("hobohi-diary-webhook/", env!("CARGO_PKG_VERSION")))
+            .redirect(reqwest::redirect::Policy::none());
+        // 登録後に名前解決の結果が変わっても、内部のアドレスには送信しない
+        if !allow_private_networks {
+            builder = builder.dns_resolver(Arc::new(PublicResolver));
+        }
+        Ok(Self {
+            http: builder.build()?,
+            allow_private_networks,
+        })
+    }
+}
+
+// 名前解決の結果から、公開されていないアドレスを除くリゾルバー
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} does not resolve to a public address", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+struct SendError {
+    status: Option<u16>,
+    message: String,
+}
+
+// 配信を1回送信します。2xx が返れば成功です
+async fn send(client: &WebhookClient, delivery: &PendingDelivery) -> Result<u16, SendError> {
+    // IPアドレスのURLは名前解決を経ないため、ここで確認する（制限を追加する前に登録されたWebhookなど）
+    if !client.allow_private_networks
+        && !reqwest::Url::parse(&delivery.url).is_ok_and(|url| is_public_host(&url))
+    {
+        return Err(SendError {
+            status: None,
+            message: "url points to a non-public address".to_string(),
+        });
+    }
+
+    let response = client
+        .http
+        .post(&delivery.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-Diary-Event", &delivery.event_type)
+        .header("X-Diary-Delivery", delivery.id.to_string())
+        .header("X-Diary-Signature-256", signature(&delivery.secret, delivery.payload.as_bytes()))
+        .body(delivery.payload.clone())
+        .send()
+        .await
+        .map_err(|err| SendError {
+            status: None,
+            message: truncate(&err.to_string()),
+        })?;
+
+    // レスポンスのボディは配信ログに残さない（送信先の内部の情報を含むことがあるため）
+    let status = response.status();
+    if status.is_success() {
+        return Ok(status.as_u16());
+    }
+    Err(SendError {
+        status: Some(status.as_u16()),
+        message: status.to_string(),
+    })
+}
+
+/// ボディの署名（`sha256=` に続けてHMAC-SHA256の16進表記）を返します。
+/// 受信側は同じ鍵で計算した値と比べることで、送信元とボディの改ざんの有無を確認できます。
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac = <Hmac<Sha256>>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+// 失敗した回数に応じた再送までの待ち時間（30秒、1分、2分…、上限6時間）
+fn retry_delay(attempts: i32) -> Duration {
+    let exponent = u32::try_from(attempts - 1).unwrap_or(0).min(20);
+    (INITIAL_RETRY_DELAY * 2u32.pow(exponent)).min(MAX_RETRY_DELAY)
+}
+
+fn truncate(message: &str) -> String {
+    message.chars().take(MAX_ERROR_LENGTH).collect()
+}
+
+fn validate_url(url: &str) -> Result<(), WebhookServiceError> {
+    let url = match reqwest::Url::parse(url.trim()) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => url,
+        _ => return Err(WebhookServiceError::InvalidRequest("url must be an http or https URL".to_string())),
+    };
+    if !allow_private_networks() && !is_public_host(&url) {
+        return Err(WebhookServiceError::InvalidRequest(
+            "url must not point to a loopback, private or link-local address".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// ループバック・プライベート・リンクローカルのアドレスへの送信を許可するか（`WEBHOOK_ALLOW_PRIVATE_NETWORKS=true`）
+fn allow_private_networks() -> bool {
+    std::env::var("WEBHOOK_ALLOW_PRIVATE_NETWORKS").is_ok_and(|value| value.trim().eq_ignore_ascii_case("true"))
+}
+
+// URLのホストが公開されたアドレスか確認します。ドメイン名の解決結果は送信時に `PublicResolver` が確認します
+fn is_public_host(url: &reqwest::Url) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return is_public_ip(ip);
+    }
+    let domain = host.trim_end_matches('.').to_ascii_lowercase();
+    domain != "localhost" && !domain.ends_with(".localhost")
+}
+
+// ループバック・プライベート・リンクローカル・共有（CGNAT）・マルチキャストなど、
+// インターネット上の送信先ではないアドレスを除きます
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || first == 0
+                || (first == 100 && (64..128).contains(&second)))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(ip));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+fn validate_secret(secret: &str) -> Result<(), WebhookServiceError> {
+    if secret.is_empty() {
+        return Err(WebhookServiceError::InvalidRequest("secret cannot be empty".to_string()));
+    }
+    Ok(())
+}
+
+fn validate_event_types(event_types: &[String]) -> Result<(), WebhookServiceError> {
+    if event_types.is_empty() {
+        return Err(WebhookServiceError::InvalidRequest("event_types cannot be empty".to_string()));
+    }
+    if let Some(unknown) = event_types.iter().find(|event_type| !EVENT_TYPES.contains(&event_type.as_str())) {
+        return Err(WebhookServiceError::InvalidRequest(format!(
+            "unknown event type: {} (expected one of {})",
+            unknown,
+            EVENT_TYPES.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    use axum::body::Bytes;
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    // 受け取ったリクエスト（ヘッダーとボディ）と、これから返すステータスコード（空なら200）
+    #[derive(Default)]
+    struct Receiver {
+        requests: Mutex<Vec<(HeaderMap, Bytes)>>,
+        statuses: Mutex<VecDeque<StatusCode>>,
+    }
+
+    // Webhookの受信先をローカルで起動し、受信先のURLを返します
+    async fn spawn_receiver(statuses: &[StatusCode]) -> (String, Arc<Receiver>) {
+        async fn receive(
+            State(receiver): State<Arc<Receiver>>,
+            headers: HeaderMap,
+            body: Bytes,
+        ) -> (StatusCode, &'static str) {
+            receiver.requests.lock().unwrap().push((headers, body));
+            let status = receiver.statuses.lock().unwrap().pop_front().unwrap_or(StatusCode::OK);
+            (status, "internal details")
+        }
+
+        let receiver = Arc::new(Receiver {
+            statuses: Mutex::new(statuses.iter().copied().collect()),
+            ..Default::default()
+        });
+        let app = Router::new().route("/hook", post(receive)).with_state(receiver.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/hook", addr), receiver)
+    }
+
+    fn delivery(url: &str) -> PendingDelivery {
+        PendingDelivery {
+            id: 7,
+            event_type: "entry.created".to_string(),
+            payload: r#"{"type":"entry.created","created_at":"2024-01-01T00:00:00Z","data":{"id":1}}"#.to_string(),
+            attempts: 0,
+            url: url.to_string(),
+            secret: "secret".to_string(),
+        }
+    }
+
+    fn header<'a>(headers: &'a HeaderMap, name: &str) -> &'a str {
+        headers.get(name).and_then(|value| value.to_str().ok()).unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn signs_deliveries() {
+        let (url, receiver) = spawn_receiver(&[]).await;
+        let client = WebhookClient::new(true).unwrap();
+        let delivery = delivery(&url);
+
+        assert!(matches!(send(&client, &delivery).await, Ok(200)));
+
+        let requests = receiver.requests.lock().unwrap();
+        let (headers, body) = &requests[0];
+        assert_eq!(body.as_ref(), delivery.payload.as_bytes());
+        assert_eq!(header(headers, "content-type"), "application/json");
+        assert_eq!(header(headers, "x-diary-event"), "entry.created");
+        assert_eq!(header(headers, "x-diary-delivery"), "7");
+        // 受信側と同じ手順で署名を確認する
+        let signature = header(headers, "x-diary-signature-256").strip_prefix("sha256=").unwrap();
+        let mut mac = <Hmac<Sha256>>::new_from_slice(b"secret").unwrap();
+        mac.update(body);
+        assert!(mac.verify_slice(&hex::decode(signature).unwrap()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn retries_with_backoff_and_fails_after_max_attempts() {
+        let (url, receiver) = spawn_receiver(&[StatusCode::INTERNAL_SERVER_ERROR; MAX_ATTEMPTS as usize]).await;
+        let client = WebhookClient::new(true).unwrap();
+        let mut delivery = delivery(&url);
+        let attempted_at = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+
+        let mut delays = Vec::new();
+        loop {
+            let result = send(&client, &delivery).await;
+            let attempt = attempt_result(&delivery, &result, attempted_at);
+            assert_eq!(attempt.response_status, Some(500));
+            // レスポンスのボディは記録しない
+            assert_eq!(attempt.error, Some("500 Internal Server Error"));
+            delivery.attempts = attempt.attempts;
+            if attempt.status == DeliveryStatus::Failed {
+                assert_eq!(attempt.next_attempt_at, attempted_at);
+                break;
+            }
+            assert_eq!(attempt.status, DeliveryStatus::Pending);
+            delays.push((attempt.next_attempt_at - attempted_at).num_seconds());
+        }
+
+        assert_eq!(delivery.attempts, MAX_ATTEMPTS);
+        assert_eq!(delays, vec![30, 60, 120, 240, 480, 960, 1920, 3840, 7680]);
+        assert_eq!(retry_delay(20), MAX_RETRY_DELAY);
+        // 再送でも同じ配信IDと署名を送る
+        let requests = receiver.requests.lock().unwrap();
+        assert_eq!(requests.len(), MAX_ATTEMPTS as usize);
+        assert!(requests.iter().all(|(headers, _)| header(headers, "x-diary-delivery") == "7"));
+        assert!(requests.windows(2).all(|pair| {
+            header(&pair[0].0, "x-diary-signature-256") == header(&pair[1].0, "x-diary-signature-256")
+        }));
+    }
+
+    #[tokio::test]
+    async fn succeeds_on_retry() {
+        let (url, _receiver) = spawn_receiver(&[StatusCode::SERVICE_UNAVAILABLE]).await;
+        let client = WebhookClient::new(true).unwrap();
+        let mut delivery = delivery(&url);
+        let attempted_at = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+
+        let result = send(&client, &delivery).await;
+        let attempt = attempt_result(&delivery, &result, attempted_at);
+        assert_eq!((attempt.status, attempt.response_status), (DeliveryStatus::Pending, Some(503)));
+        delivery.attempts = attempt.attempts;
+
+        let result = send(&client, &delivery).await;
+        let attempt = attempt_result(&delivery, &result, attempted_at);
+        assert_eq!(attempt.status, DeliveryStatus::Succeeded);
+        assert_eq!((attempt.attempts, attempt.response_status, attempt.error), (2, Some(200), None));
+    }
+
+    #[tokio::test]
+    async fn does_not_send_to_non_public_addresses() {
+        let (url, receiver) = spawn_receiver(&[]).await;
+        let client = WebhookClient::new(false).unwrap();
+
+        // IPアドレスのURLと、名前解決でループバックのアドレスになるURL
+        let port = url.rsplit(':').next().unwrap();
+        for url in [url.clone(), format!("http://localhost:{}", port)] {
+            match send(&client, &delivery(&url)).await {
+                Err(SendError { status: None, .. }) => {}
+                other => panic!("{} was sent: {:?}", url, other.map_err(|err| err.message)),
+            }
+        }
+        assert!(receiver.requests.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_urls_for_non_public_addresses() {
+        for url in [
+            "http://127.0.0.1/hook",
+            "http://localhost:8080/hook",
+            "http://api.localhost/hook",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://10.0.0.1/hook",
+            "http://172.16.0.1/hook",
+            "http://192.168.1.1/hook",
+            "http://100.64.0.1/hook",
+            "http://0.0.0.0/hook",
+            "http://[::1]/hook",
+            "http://[fe80::1]/hook",
+            "http://[fd00::1]/hook",
+            "http://[::ffff:127.0.0.1]/hook",
+        ] {
+            assert!(!is_public_host(&reqwest::Url::parse(url).unwrap()), "{}", url);
+            assert!(matches!(validate_url(url), Err(WebhookServiceError::InvalidRequest(_))), "{}", url);
+        }
+        for url in ["https://example.com/hook", "http://93.184.216.34/hook", "http://[2606:4700::1111]/hook"] {
+            assert!(validate_url(url).is_ok(), "{}", url);
+        }
+        assert!(validate_url("ftp://example.com/hook").is_err());
+    }
+}
@@ -0,0 +1,471 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use mail_parser::{MessageParser, MimeHeaders};
+use sqlx::MySqlPool;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+use crate::services::attachment_service::{AttachmentService, UploadedFile};
+use crate::services::capture_service::{CaptureService, CaptureServiceError};
+use crate::storage::Storage;
+
+// 受け付けるメールの大きさの上限（添付ファイルのアップロードと揃える）
+const MAX_MESSAGE_SIZE: usize = 50 * 1024 * 1024;
+// コマンド行の長さの上限
+const MAX_COMMAND_LENGTH: usize = 4096;
+// 1通のメールで受け付ける宛先の数の上限
+const MAX_RECIPIENTS: usize = 10;
+// 宛先のローカル部の接頭辞（`diary+<トークン>`）
+const RECIPIENT_PREFIX: &str = "diary+";
+
+/// 受け取ったメールから作るエントリ
+#[derive(Debug)]
+pub struct MailEntry {
+    pub content: String,
+    pub tags: Vec<String>,
+    pub images: Vec<UploadedFile>,
+}
+
+// 宛先の確認と受け取ったメールの配送を行う先
+#[async_trait]
+trait Mailbox: Send + Sync {
+    // トークンが有効か確認します
+    async fn verify_token(&self, token: &str) -> Result<bool, CaptureServiceError>;
+
+    // 受け取ったメールを宛先ごとに配送し、クライアントへの応答を返します
+    async fn deliver(&self, tokens: &[String], message: &[u8]) -> String;
+}
+
+// 日記のデータベースと添付ファイルの保存先に配送する `Mailbox`
+struct DiaryMailbox {
+    pool: MySqlPool,
+    storage: Arc<dyn Storage>,
+}
+
+#[async_trait]
+impl Mailbox for DiaryMailbox {
+    async fn verify_token(&self, token: &str) -> Result<bool, CaptureServiceError> {
+        CaptureService::new(&self.pool).verify_token(token).await
+    }
+
+    async fn deliver(&self, tokens: &[String], message: &[u8]) -> String {
+        deliver(&self.pool, &self.storage, tokens, message).await
+    }
+}
+
+/// メールで日記を投稿するためのSMTPサーバーを起動します。
+/// `diary+<トークン>@...` 宛てのメールを受け取り、本文からエントリを作成します。
+/// トークンはクイック投稿（`POST /api/capture/:token`）と同じものを使います。
+pub async fn serve(listener: TcpListener, pool: MySqlPool, storage: Arc<dyn Storage>, hostname: String) {
+    let hostname: Arc<str> = hostname.into();
+    let mailbox = Arc::new(DiaryMailbox { pool, storage });
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(err) => {
+                tracing::warn!("Failed to accept SMTP connection: {:?}", err);
+                continue;
+            }
+        };
+        let (mailbox, hostname) = (mailbox.clone(), hostname.clone());
+        tokio::spawn(async move {
+            let (reader, writer) = stream.into_split();
+            if let Err(err) = handle_session(reader, writer, &*mailbox, &hostname, MAX_MESSAGE_SIZE).await {
+                tracing::debug!("SMTP session with {} ended: {:?}", peer, err);
+            }
+        });
+    }
+}
+
+// 1つの接続でのSMTPのやり取りを処理します
+async fn handle_session<R, W>(
+    reader: R,
+    mut writer: W,
+    mailbox: &dyn Mailbox,
+    hostname: &str,
+    max_message_size: usize,
+) -> std::io::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut reader = BufReader::new(reader);
+    let mut sender: Option<String> = None;
+    let mut tokens: Vec<String> = Vec::new();
+
+    reply(&mut writer, &format!("220 {} ESMTP diary", hostname)).await?;
+    loop {
+        let Some(line) = read_line(&mut reader, MAX_COMMAND_LENGTH).await? else {
+            return Ok(());
+        };
+        let line = String::from_utf8_lossy(&line);
+        let line = line.trim_end();
+        let (verb, argument) = line.split_once(' ').unwrap_or((line, ""));
+
+        match verb.to_ascii_uppercase().as_str() {
+            "EHLO" => {
+                let response = format!("250-{}\r\n250-8BITMIME\r\n250 SIZE {}", hostname, max_message_size);
+                reply(&mut writer, &response).await?;
+            }
+            "HELO" => reply(&mut writer, &format!("250 {}", hostname)).await?,
+            "MAIL" => match parse_path(argument, "FROM:") {
+                Some(path) => {
+                    sender = Some(path);
+                    tokens.clear();
+                    reply(&mut writer, "250 OK").await?;
+                }
+                None => reply(&mut writer, "501 Syntax: MAIL FROM:<address>").await?,
+            },
+            "RCPT" => {
+                if sender.is_none() {
+                    reply(&mut writer, "503 MAIL first").await?;
+                    continue;
+                }
+                let Some(path) = parse_path(argument, "TO:") else {
+                    reply(&mut writer, "501 Syntax: RCPT TO:<address>").await?;
+                    continue;
+                };
+                if tokens.len() >= MAX_RECIPIENTS {
+                    reply(&mut writer, "452 Too many recipients").await?;
+                    continue;
+                }
+                match recipient_token(&path) {
+                    Some(token) => match mailbox.verify_token(token).await {
+                        Ok(true) => {
+                            tokens.push(token.to_string());
+                            reply(&mut writer, "250 OK").await?;
+                        }
+                        Ok(false) => reply(&mut writer, "550 No such mailbox").await?,
+                        Err(_) => reply(&mut writer, "451 Temporary failure").await?,
+                    },
+                    None => reply(&mut writer, "550 No such mailbox").await?,
+                }
+            }
+            "DATA" => {
+                if tokens.is_empty() {
+                    reply(&mut writer, "503 RCPT first").await?;
+                    continue;
+                }
+                reply(&mut writer, "354 End data with <CR><LF>.<CR><LF>").await?;
+                let Some(message) = read_data(&mut reader, max_message_size).await? else {
+                    reply(&mut writer, "552 Message too large").await?;
+                    return Ok(());
+                };
+                let response = mailbox.deliver(&tokens, &message).await;
+                sender = None;
+                tokens.clear();
+                reply(&mut writer, &response).await?;
+            }
+            "RSET" => {
+                sender = None;
+                tokens.clear();
+                reply(&mut writer, "250 OK").await?;
+            }
+            "NOOP" => reply(&mut writer, "250 OK").await?,
+            "QUIT" => {
+                reply(&mut writer, &format!("221 {} closing connection", hostname)).await?;
+                return Ok(());
+            }
+            _ => reply(&mut writer, "502 Command not implemented").await?,
+        }
+    }
+}
+
+// 受け取ったメールから宛先ごとにエントリを作成し、クライアントへの応答を返します
+async fn deliver(pool: &MySqlPool, storage: &Arc<dyn Storage>, tokens: &[String], message: &[u8]) -> String {
+    let Some(mail) = parse_mail(message) else {
+        return "554 Could not parse message".to_string();
+    };
+
+    let capture_service = CaptureService::new(pool);
+    let attachment_service = AttachmentService::new(pool, storage);
+    for token in tokens {
+        let entry_id = match capture_service.capture(token, &mail.content, mail.tags.clone()).await {
+            Ok(entry_id) => entry_id,
+            Err(CaptureServiceError::EmptyContent) => return "554 Message has no text".to_string(),
+            Err(CaptureServiceError::Unauthorized) => return "550 No such mailbox".to_string(),
+            Err(_) => return "451 Temporary failure".to_string(),
+        };
+        if mail.images.is_empty() {
+            continue;
+        }
+        // エントリは作成済みのため、画像を保存できなくてもメールは受け取ったことにする
+        if let Err(err) = attachment_service.upload_attachments(entry_id, mail.images.clone(), false).await {
+            tracing::error!("Failed to attach images from mail to entry {}: {:?}", entry_id, err);
+        }
+    }
+    "250 OK".to_string()
+}
+
+/// メール（RFC 5322）を読み込み、エントリの本文・タグ・添付する画像を取り出します。
+/// 件名の `#` で始まる語と、本文の先頭行の `#タグ` がタグになります。
+pub fn parse_mail(raw: &[u8]) -> Option<MailEntry> {
+    let message = MessageParser::default().parse(raw)?;
+
+    // テキストのパートがない場合はHTMLのパートをテキストにしたものが返る
+    let content = message.body_text(0).map(|text| text.into_owned()).unwrap_or_default();
+    let tags = message
+        .subject()
+        .unwrap_or_default()
+        .split_whitespace()
+        .filter(|word| word.starts_with(['#', '＃']))
+        .map(|word| word.trim_start_matches(['#', '＃']).to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+
+    let images = message
+        .attachments()
+        .filter_map(|part| {
+            let content_type = part.content_type()?;
+            if !content_type.ctype().eq_ignore_ascii_case("image") {
+                return None;
+            }
+            let subtype = content_type.subtype().unwrap_or("octet-stream");
+            Some(UploadedFile {
+                filename: part
+                    .attachment_name()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("image.{}", subtype.to_ascii_lowercase())),
+                data: Bytes::copy_from_slice(part.contents()),
+            })
+        })
+        .collect();
+
+    Some(MailEntry { content, tags, images })
+}
+
+// `diary+<トークン>@ドメイン` からトークンを取り出します
+fn recipient_token(address: &str) -> Option<&str> {
+    let (local, _domain) = address.rsplit_once('@')?;
+    let token = local.strip_prefix(RECIPIENT_PREFIX)?;
+    (!token.is_empty()).then_some(token)
+}
+
+// `FROM:<address> SIZE=...` のような引数からアドレスを取り出します（空の送信者 `<>` も受け付ける）
+fn parse_path(argument: &str, keyword: &str) -> Option<String> {
+    let argument = argument.trim_start();
+    if argument.len() < keyword.len() || !argument[..keyword.len()].eq_ignore_ascii_case(keyword) {
+        return None;
+    }
+    let path = argument[keyword.len()..].trim_start();
+    let path = path.strip_prefix('<')?;
+    let end = path.find('>')?;
+    Some(path[..end].to_string())
+}
+
+// 改行までの1行を読み込みます。接続が閉じられた場合は `None` を返します
+async fn read_line<R>(reader: &mut BufReader<R>, max_length: usize) -> std::io::Result<Option<Vec<u8>>>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut line = Vec::new();
+    let read = (&mut *reader).take(max_length as u64).read_until(b'\n', &mut line).await?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if !line.ends_with(b"\n") && line.len() >= max_length {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "line too long"));
+    }
+    Ok(Some(line))
+}
+
+// DATA の本文を `.` だけの行まで読み込み、ドットの重ね書きを元に戻します。
+// 上限を超えた場合は `None` を返します
+async fn read_data<R>(reader: &mut BufReader<R>, max_size: usize) -> std::io::Result<Option<Vec<u8>>>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut message = Vec::new();
+    loop {
+        let Some(line) = read_line(reader, max_size.max(MAX_COMMAND_LENGTH)).await? else {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        };
+        if line == b".\r\n" || line == b".\n" {
+            return Ok(Some(message));
+        }
+        let line = line.strip_prefix(b".").unwrap_or(&line);
+        if message.len() + line.len() > max_size {
+            return Ok(None);
+        }
+        message.extend_from_slice(line);
+    }
+}
+
+async fn reply<W>(writer: &mut W, response: &str) -> std::io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    writer.write_all(response.as_bytes()).await?;
+    writer.write_all(b"\r\n").await?;
+    writer.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use tokio::io::duplex;
+
+    use super::*;
+
+    // `known` だけを有効なトークンとして扱い、受け取ったメールを記録する `Mailbox`
+    #[derive(Default)]
+    struct RecordingMailbox {
+        delivered: Mutex<Vec<(Vec<String>, Vec<u8>)>>,
+    }
+
+    #[async_trait]
+    impl Mailbox for RecordingMailbox {
+        async fn verify_token(&self, token: &str) -> Result<bool, CaptureServiceError> {
+            Ok(token == "known")
+        }
+
+        async fn deliver(&self, tokens: &[String], message: &[u8]) -> String {
+            self.delivered.lock().unwrap().push((tokens.to_vec(), message.to_vec()));
+            "250 OK".to_string()
+        }
+    }
+
+    // クライアントの送信内容を流し込み、サーバーの応答を1行ずつ返します
+    async fn converse(mailbox: &RecordingMailbox, input: &str, max_message_size: usize) -> Vec<String> {
+        let (client, server) = duplex(64 * 1024);
+        let (server_reader, server_writer) = tokio::io::split(server);
+        let (mut client_reader, mut client_writer) = tokio::io::split(client);
+
+        let session = async {
+            handle_session(server_reader, server_writer, mailbox, "diary.test", max_message_size).await.unwrap();
+        };
+        let client = async {
+            // セッションが途中で終了した場合は書き込みに失敗するため、結果は無視する
+            let _ = client_writer.write_all(input.as_bytes()).await;
+            let _ = client_writer.shutdown().await;
+            let mut output = String::new();
+            client_reader.read_to_string(&mut output).await.unwrap();
+            output
+        };
+        let ((), output) = tokio::join!(session, client);
+        output.lines().map(str::to_string).collect()
+    }
+
+    #[tokio::test]
+    async fn accepts_mail_for_known_tokens() {
+        let mailbox = RecordingMailbox::default();
+        let input = "EHLO client.test\r\n\
+                     MAIL FROM:<me@example.com> SIZE=100\r\n\
+                     RCPT TO:<diary+known@diary.test>\r\n\
+                     DATA\r\n\
+                     Subject: hello\r\n\
+                     \r\n\
+                     ..leading dot\r\n\
+                     .\r\n\
+                     QUIT\r\n";
+        let responses = converse(&mailbox, input, 1024).await;
+
+        assert_eq!(
+            responses,
+            vec![
+                "220 diary.test ESMTP diary",
+                "250-diary.test",
+                "250-8BITMIME",
+                "250 SIZE 1024",
+                "250 OK",
+                "250 OK",
+                "354 End data with <CR><LF>.<CR><LF>",
+                "250 OK",
+                "221 diary.test closing connection",
+            ]
+        );
+        let delivered = mailbox.delivered.lock().unwrap();
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(delivered[0].0, vec!["known".to_string()]);
+        // 先頭の `.` の重ね書きは元に戻される
+        assert_eq!(delivered[0].1, b"Subject: hello\r\n\r\n.leading dot\r\n");
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_tokens() {
+        let mailbox = RecordingMailbox::default();
+        let input = "HELO client.test\r\n\
+                     RCPT TO:<diary+known@diary.test>\r\n\
+                     MAIL FROM:<>\r\n\
+                     RCPT TO:<diary+unknown@diary.test>\r\n\
+                     RCPT TO:<someone@diary.test>\r\n\
+                     DATA\r\n\
+                     QUIT\r\n";
+        let responses = converse(&mailbox, input, 1024).await;
+
+        assert_eq!(
+            responses,
+            vec![
+                "220 diary.test ESMTP diary",
+                "250 diary.test",
+                "503 MAIL first",
+                "250 OK",
+                "550 No such mailbox",
+                "550 No such mailbox",
+                "503 RCPT first",
+                "221 diary.test closing connection",
+            ]
+        );
+        assert!(mailbox.delivered.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_messages_over_the_size_limit() {
+        let mailbox = RecordingMailbox::default();
+        let body = "0123456789\r\n".repeat(10);
+        let input = format!(
+            "EHLO client.test\r\nMAIL FROM:<me@example.com>\r\nRCPT TO:<diary+known@diary.test>\r\nDATA\r\n{}.\r\nQUIT\r\n",
+            body
+        );
+        let responses = converse(&mailbox, &input, 64).await;
+
+        // 上限を超えた時点で応答して接続を閉じる
+        assert_eq!(responses.last().unwrap(), "552 Message too large");
+        assert!(!responses.iter().any(|response| response.starts_with("221")));
+        assert!(mailbox.delivered.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn parses_subject_tags_and_image_parts() {
+        let raw = "From: me@example.com\r\n\
+                   To: diary+known@diary.test\r\n\
+                   Subject: =?UTF-8?B?5peF6KGM?= #旅行 ＃夏 #\r\n\
+                   MIME-Version: 1.0\r\n\
+                   Content-Type: multipart/mixed; boundary=\"b\"\r\n\
+                   \r\n\
+                   --b\r\n\
+                   Content-Type: text/plain; charset=utf-8\r\n\
+                   \r\n\
+                   海に行った\r\n\
+                   --b\r\n\
+                   Content-Type: image/png\r\n\
+                   Content-Disposition: attachment; filename=\"beach.png\"\r\n\
+                   Content-Transfer-Encoding: base64\r\n\
+                   \r\n\
+                   iVBORw0KGgo=\r\n\
+                   --b\r\n\
+                   Content-Type: image/jpeg\r\n\
+                   Content-Transfer-Encoding: base64\r\n\
+                   \r\n\
+                   /9j/\r\n\
+                   --b\r\n\
+                   Content-Type: application/pdf\r\n\
+                   Content-Disposition: attachment; filename=\"memo.pdf\"\r\n\
+                   \r\n\
+                   %PDF\r\n\
+                   --b--\r\n";
+        let mail = parse_mail(raw.as_bytes()).unwrap();
+
+        assert_eq!(mail.content.trim_end(), "海に行った");
+        assert_eq!(mail.tags, vec!["旅行", "夏"]);
+        let images: Vec<(&str, &[u8])> =
+            mail.images.iter().map(|image| (image.filename.as_str(), image.data.as_ref())).collect();
+        assert_eq!(
+            images,
+            vec![("beach.png", &b"\x89PNG\r\n\x1a\n"[..]), ("image.jpeg", &b"\xff\xd8\xff"[..])]
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use axum::extract::FromRef;
+use sqlx::MySqlPool;
+
+use crate::storage::Storage;
+
+/// ハンドラーで共有する状態
+/// 各ハンドラーは `State<MySqlPool>` のように必要なものだけを取り出します。
+#[derive(Clone)]
+pub struct AppState {
+    pub pool: MySqlPool,
+    pub storage: Arc<dyn Storage>,
+}
+
+impl FromRef<AppState> for MySqlPool {
+    fn from_ref(state: &AppState) -> Self {
+        state.pool.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn Storage> {
+    fn from_ref(state: &AppState) -> Self {
+        state.storage.clone()
+    }
+}
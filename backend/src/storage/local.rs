@@ -0,0 +1,100 @@
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use super::{validate_key, Storage, StorageError};
+
+// std::io::ErrorからStorageErrorへの変換
+impl From<std::io::Error> for StorageError {
+    fn from(err: std::io::Error) -> Self {
+        match err.kind() {
+            ErrorKind::NotFound => StorageError::NotFound,
+            _ => StorageError::Backend(err.to_string()),
+        }
+    }
+}
+
+/// ローカルのディレクトリにファイルを保存します。
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, StorageError> {
+        validate_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, data: Bytes, _content_type: &str) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // 書き込み途中のファイルを読まれないよう、一時ファイルに書いてから置き換える
+        let mut temporary = path.clone().into_os_string();
+        temporary.push(".partial");
+        tokio::fs::write(&temporary, &data).await?;
+        tokio::fs::rename(&temporary, &path).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, StorageError> {
+        let path = self.path(key)?;
+        Ok(Bytes::from(tokio::fs::read(path).await?))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+        match tokio::fs::remove_file(path).await {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn puts_gets_and_deletes_files() {
+        let root = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(root.path().to_path_buf());
+        let key = "entries/1/20240101000000-abc.txt";
+
+        storage.put(key, Bytes::from_static(b"first"), "text/plain").await.unwrap();
+        assert_eq!(&storage.get(key).await.unwrap()[..], b"first");
+        assert!(root.path().join("entries/1/20240101000000-abc.txt").is_file());
+
+        // 同じキーは上書きされ、一時ファイルは残らない
+        storage.put(key, Bytes::from_static(b"second"), "text/plain").await.unwrap();
+        assert_eq!(&storage.get(key).await.unwrap()[..], b"second");
+        assert!(!root.path().join("entries/1/20240101000000-abc.txt.partial").exists());
+
+        storage.delete(key).await.unwrap();
+        assert!(matches!(storage.get(key).await, Err(StorageError::NotFound)));
+        // 存在しないキーの削除は成功する
+        storage.delete(key).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_keys_outside_the_root() {
+        let root = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(root.path().join("attachments"));
+
+        for key in ["../secret", "entries//1", "/etc/passwd", "entries/./1", ""] {
+            let result = storage.put(key, Bytes::from_static(b"x"), "text/plain").await;
+            assert!(matches!(result, Err(StorageError::InvalidKey(_))), "{:?}", key);
+        }
+        assert!(!root.path().join("secret").exists());
+    }
+}
@@ -0,0 +1,64 @@
+pub mod local;
+pub mod s3;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use self::local::LocalStorage;
+use self::s3::S3Storage;
+
+/// 添付ファイルの保存先で発生するエラー
+#[derive(Debug)]
+pub enum StorageError {
+    // 指定されたキーのファイルが存在しない
+    NotFound,
+    // キーに使えない文字が含まれている
+    InvalidKey(String),
+    // 保存先での読み書きに失敗した
+    Backend(String),
+}
+
+/// 添付ファイルの保存先
+/// ファイルはアプリケーションが決めたキー（`entries/1/...` のような `/` 区切りのパス）で管理します。
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// ファイルを保存します。同じキーのファイルがある場合は上書きします。
+    async fn put(&self, key: &str, data: Bytes, content_type: &str) -> Result<(), StorageError>;
+
+    /// ファイルを読み込みます。
+    async fn get(&self, key: &str) -> Result<Bytes, StorageError>;
+
+    /// ファイルを削除します。存在しない場合も成功として扱います。
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+}
+
+/// 環境変数の設定から保存先を作成します。
+/// `ATTACHMENT_STORAGE` が `s3` の場合はS3互換のオブジェクトストレージ、それ以外はローカルのディレクトリを使います。
+pub fn from_env() -> Result<Arc<dyn Storage>, StorageError> {
+    match std::env::var("ATTACHMENT_STORAGE").unwrap_or_default().as_str() {
+        "s3" => Ok(Arc::new(S3Storage::from_env()?)),
+        _ => {
+            let root = std::env::var("ATTACHMENT_DIR").unwrap_or_else(|_| "attachments".to_string());
+            Ok(Arc::new(LocalStorage::new(PathBuf::from(root))))
+        }
+    }
+}
+
+// キーは英数字と `-` `_` `.` からなる要素を `/` でつないだものに限る
+fn validate_key(key: &str) -> Result<(), StorageError> {
+    let valid = !key.is_empty()
+        && key.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidKey(key.to_string()))
+    }
+}
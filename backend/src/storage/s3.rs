@@ -0,0 +1,204 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::{path::Path, Attribute, Attributes, ObjectStore, PutOptions, PutPayload};
+
+use super::{validate_key, Storage, StorageError};
+
+// object_store::ErrorからStorageErrorへの変換
+impl From<object_store::Error> for StorageError {
+    fn from(err: object_store::Error) -> Self {
+        match err {
+            object_store::Error::NotFound { .. } => StorageError::NotFound,
+            err => StorageError::Backend(err.to_string()),
+        }
+    }
+}
+
+/// S3互換のオブジェクトストレージ（AWS S3、MinIOなど）にファイルを保存します。
+pub struct S3Storage {
+    store: AmazonS3,
+}
+
+impl S3Storage {
+    /// 環境変数から接続先を設定します。
+    /// `S3_BUCKET` は必須で、`S3_ENDPOINT` を指定した場合はパス形式のURLでそのエンドポイントに接続します（MinIOなど）。
+    pub fn from_env() -> Result<Self, StorageError> {
+        let bucket = std::env::var("S3_BUCKET")
+            .map_err(|_| StorageError::Backend("S3_BUCKET is not set".to_string()))?;
+        let mut builder = AmazonS3Builder::new()
+            .with_bucket_name(bucket)
+            .with_region(std::env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()));
+        if let Ok(access_key_id) = std::env::var("S3_ACCESS_KEY_ID") {
+            builder = builder.with_access_key_id(access_key_id);
+        }
+        if let Ok(secret_access_key) = std::env::var("S3_SECRET_ACCESS_KEY") {
+            builder = builder.with_secret_access_key(secret_access_key);
+        }
+        if let Ok(endpoint) = std::env::var("S3_ENDPOINT") {
+            builder = with_custom_endpoint(builder, endpoint);
+        }
+
+        Ok(Self { store: builder.build()? })
+    }
+
+    fn path(key: &str) -> Result<Path, StorageError> {
+        validate_key(key)?;
+        Ok(Path::from(key))
+    }
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    async fn put(&self, key: &str, data: Bytes, content_type: &str) -> Result<(), StorageError> {
+        let mut attributes = Attributes::new();
+        attributes.insert(Attribute::ContentType, content_type.to_string().into());
+        let options = PutOptions {
+            attributes,
+            ..Default::default()
+        };
+        self.store
+            .put_opts(&Self::path(key)?, PutPayload::from(data), options)
+            .await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, StorageError> {
+        let result = self.store.get(&Self::path(key)?).await?;
+        Ok(result.bytes().await?)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match self.store.delete(&Self::path(key)?).await {
+            Err(err) if !matches!(err, object_store::Error::NotFound { .. }) => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+// パス形式のURLで、指定したエンドポイント（MinIOなど）に接続するよう設定します
+fn with_custom_endpoint(builder: AmazonS3Builder, endpoint: String) -> AmazonS3Builder {
+    builder
+        .with_allow_http(endpoint.starts_with("http://"))
+        .with_virtual_hosted_style_request(false)
+        .with_endpoint(endpoint)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use axum::body::Bytes as BodyBytes;
+    use axum::extract::{Path as UrlPath, State};
+    use axum::http::{header, HeaderMap, StatusCode};
+    use axum::response::IntoResponse;
+    use axum::routing::put;
+    use axum::Router;
+
+    use super::*;
+
+    // バケットの中身（キー → 内容と Content-Type）
+    type Objects = Arc<Mutex<HashMap<String, (BodyBytes, String)>>>;
+
+    // PUT・GET・DELETE だけに応答する、MinIOの代わりのS3互換サーバーをローカルで起動します
+    async fn spawn_stand_in() -> (String, Objects) {
+        async fn put_object(
+            State(objects): State<Objects>,
+            UrlPath((_bucket, key)): UrlPath<(String, String)>,
+            headers: HeaderMap,
+            body: BodyBytes,
+        ) -> impl IntoResponse {
+            let content_type = headers
+                .get(header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+            objects.lock().unwrap().insert(key, (body, content_type));
+            [(header::ETAG, "\"etag\"")]
+        }
+
+        async fn get_object(
+            State(objects): State<Objects>,
+            UrlPath((_bucket, key)): UrlPath<(String, String)>,
+        ) -> impl IntoResponse {
+            match objects.lock().unwrap().get(&key) {
+                Some((data, content_type)) => (
+                    [
+                        (header::ETAG, "\"etag\"".to_string()),
+                        (header::LAST_MODIFIED, "Mon, 01 Jan 2024 00:00:00 GMT".to_string()),
+                        (header::CONTENT_TYPE, content_type.clone()),
+                    ],
+                    data.clone(),
+                )
+                    .into_response(),
+                None => (
+                    StatusCode::NOT_FOUND,
+                    "<Error><Code>NoSuchKey</Code><Message>The specified key does not exist.</Message></Error>",
+                )
+                    .into_response(),
+            }
+        }
+
+        async fn delete_object(
+            State(objects): State<Objects>,
+            UrlPath((_bucket, key)): UrlPath<(String, String)>,
+        ) -> StatusCode {
+            // S3は存在しないキーの削除も成功として扱う
+            objects.lock().unwrap().remove(&key);
+            StatusCode::NO_CONTENT
+        }
+
+        let objects = Objects::default();
+        let app = Router::new()
+            .route("/:bucket/*key", put(put_object).get(get_object).delete(delete_object))
+            .with_state(objects.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (format!("http://{}", addr), objects)
+    }
+
+    fn storage(endpoint: String) -> S3Storage {
+        let builder = AmazonS3Builder::new()
+            .with_bucket_name("diary")
+            .with_region("us-east-1")
+            .with_access_key_id("test")
+            .with_secret_access_key("test");
+        S3Storage {
+            store: with_custom_endpoint(builder, endpoint).build().unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn puts_gets_and_deletes_objects() {
+        let (endpoint, objects) = spawn_stand_in().await;
+        let storage = storage(endpoint);
+        let key = "entries/1/20240101000000-abc.png";
+
+        storage.put(key, Bytes::from_static(b"image"), "image/png").await.unwrap();
+        let (data, content_type) = objects.lock().unwrap().get(key).cloned().unwrap();
+        assert_eq!(&data[..], b"image");
+        assert_eq!(content_type, "image/png");
+
+        assert_eq!(&storage.get(key).await.unwrap()[..], b"image");
+
+        storage.delete(key).await.unwrap();
+        assert!(objects.lock().unwrap().is_empty());
+        assert!(matches!(storage.get(key).await, Err(StorageError::NotFound)));
+        // 存在しないキーの削除は成功する
+        storage.delete(key).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_keys() {
+        let (endpoint, objects) = spawn_stand_in().await;
+        let storage = storage(endpoint);
+
+        let result = storage.put("entries/../secret", Bytes::from_static(b"x"), "text/plain").await;
+        assert!(matches!(result, Err(StorageError::InvalidKey(_))));
+        assert!(objects.lock().unwrap().is_empty());
+    }
+}
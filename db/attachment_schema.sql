@@ -0,0 +1,17 @@
+-- 添付ファイルテーブルの作成（ファイル本体は ATTACHMENT_STORAGE の保存先に置く）
+CREATE TABLE IF NOT EXISTS `diary`.`attachment` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `entry_id` int NOT NULL,
+  `storage_key` varchar(255) NOT NULL,
+  `filename` varchar(255) NOT NULL,
+  `content_type` varchar(255) NOT NULL,
+  `size` bigint NOT NULL,
+  `datetime` datetime NOT NULL,
+  `width` int DEFAULT NULL,
+  `height` int DEFAULT NULL,
+  `thumbnails` json NOT NULL DEFAULT (json_array()),
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `storage_key` (`storage_key`),
+  KEY `entry_id` (`entry_id`),
+  FOREIGN KEY (`entry_id`) REFERENCES `entry` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
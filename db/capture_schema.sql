@@ -0,0 +1,12 @@
+-- クイック投稿（キャプチャ）用のトークンテーブルの作成
+CREATE TABLE IF NOT EXISTS `diary`.`capture_token` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  -- トークンの持ち主（利用者や端末の名前）
+  `name` varchar(255) NOT NULL,
+  -- トークン本体は保存せず、SHA-256のハッシュだけを持つ
+  `token_hash` char(64) NOT NULL,
+  `datetime` datetime NOT NULL,
+  `last_used_at` datetime DEFAULT NULL,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `token_hash` (`token_hash`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,22 @@
+-- コメントテーブルの作成
+CREATE TABLE IF NOT EXISTS `diary`.`comment` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `entry_id` int NOT NULL,
+  `author` varchar(50) NOT NULL,
+  `content` text NOT NULL,
+  `datetime` datetime NOT NULL,
+  PRIMARY KEY (`id`),
+  KEY `entry_id` (`entry_id`),
+  FOREIGN KEY (`entry_id`) REFERENCES `entry` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- 絵文字リアクションテーブルの作成
+CREATE TABLE IF NOT EXISTS `diary`.`reaction` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `entry_id` int NOT NULL,
+  `emoji` varchar(32) NOT NULL,
+  `datetime` datetime NOT NULL,
+  PRIMARY KEY (`id`),
+  KEY `entry_id` (`entry_id`),
+  FOREIGN KEY (`entry_id`) REFERENCES `entry` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,8 @@
+-- ランダム表示でエントリを最後に表示した日時（「再発見」で最近表示したエントリを避けるために使う）
+CREATE TABLE IF NOT EXISTS `diary`.`entry_view` (
+  `entry_id` int NOT NULL,
+  `viewed_at` datetime NOT NULL,
+  PRIMARY KEY (`entry_id`),
+  KEY `viewed_at` (`viewed_at`),
+  FOREIGN KEY (`entry_id`) REFERENCES `entry` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -4,5 +4,30 @@ CREATE TABLE IF NOT EXISTS `diary`.`entry` (
   `id` int NOT NULL AUTO_INCREMENT,
   `content` text NOT NULL,
   `datetime` datetime NOT NULL,
-  PRIMARY KEY (`id`)
-) ENGINE=InnoDB DEFAULT CHARSET=utf8mb3;
\ No newline at end of file
+  -- インポート時の重複検出に使う本文（UTF-8）のハッシュ
+  `content_hash` char(64) GENERATED ALWAYS AS (sha2(`content`, 256)) STORED,
+  -- タイムカプセルの開封日時（この日時までは本文を隠す）
+  `unlock_at` datetime DEFAULT NULL,
+  -- タイムカプセルの開封を通知したか
+  `unlock_notified` boolean NOT NULL DEFAULT FALSE,
+  -- 下書き（draft）か公開済み（published）か
+  `status` enum('draft','published') NOT NULL DEFAULT 'published',
+  -- 自動保存で下書きを識別するキー（クライアントが決める）
+  `draft_key` varchar(64) DEFAULT NULL,
+  -- 下書きに付けるタグの名前（JSON配列）。タグは公開するときに作成する
+  `draft_tags` json DEFAULT NULL,
+  -- 予約投稿の公開日時（この日時まで一覧などに含めない）
+  `publish_at` datetime DEFAULT NULL,
+  -- 予約投稿の公開を通知したか
+  `publish_notified` boolean NOT NULL DEFAULT FALSE,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `draft_key` (`draft_key`),
+  KEY `content_hash` (`content_hash`),
+  -- 期間での絞り込み（アクティビティ・エクスポートなど）に使う
+  KEY `datetime` (`datetime`),
+  -- 開封日時を過ぎたタイムカプセルの確認に使う
+  KEY `unlock_at` (`unlock_at`),
+  KEY `status` (`status`),
+  -- 公開日時を過ぎた予約投稿の確認に使う
+  KEY `publish_at` (`publish_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,34 @@
+-- 書くことに迷った日のためのお題（プロンプト）テーブルの作成
+CREATE TABLE IF NOT EXISTS `diary`.`prompt` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `text` varchar(500) NOT NULL,
+  -- お題の言語（`ja` または `en`）
+  `language` varchar(8) NOT NULL,
+  -- 無効にしたお題は今日のお題に選ばれない
+  `enabled` tinyint(1) NOT NULL DEFAULT 1,
+  `datetime` datetime NOT NULL,
+  PRIMARY KEY (`id`),
+  KEY `language` (`language`, `enabled`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- 日ごとに選んだお題（同じ日は同じお題を返し、最近選んだお題を避けるために使う）
+CREATE TABLE IF NOT EXISTS `diary`.`daily_prompt` (
+  `date` date NOT NULL,
+  -- 言語を指定せずに選んだ場合は空文字列
+  `language` varchar(8) NOT NULL DEFAULT '',
+  `prompt_id` int NOT NULL,
+  PRIMARY KEY (`date`, `language`),
+  FOREIGN KEY (`prompt_id`) REFERENCES `prompt` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- エントリが答えたお題
+CREATE TABLE IF NOT EXISTS `diary`.`entry_prompt` (
+  `entry_id` int NOT NULL,
+  `prompt_id` int NOT NULL,
+  PRIMARY KEY (`entry_id`),
+  KEY `prompt_id` (`prompt_id`),
+  FOREIGN KEY (`entry_id`) REFERENCES `entry` (`id`) ON DELETE CASCADE,
+  FOREIGN KEY (`prompt_id`) REFERENCES `prompt` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- 初期のお題は、テーブルが空のときにバックエンドの起動時に登録します（backend/src/services/prompt_service.rs の DEFAULT_PROMPTS）
@@ -0,0 +1,23 @@
+-- 日記を書いていない日に知らせるリマインダーのテーブルの作成（通知する相手ごとの設定）
+-- エントリには書いた人の情報がないため、書いたかどうかは日記全体で判定する
+CREATE TABLE IF NOT EXISTS `diary`.`reminder` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  -- 通知する相手（利用者）の名前
+  `name` varchar(255) NOT NULL,
+  -- 通知する時刻（日記のタイムゾーン）
+  `remind_at` time NOT NULL,
+  -- 通知の方法
+  `channel` enum('webhook', 'email') NOT NULL,
+  -- `webhook` の送信先（登録済みのWebhook）
+  `webhook_id` int DEFAULT NULL,
+  -- `email` の宛先
+  `email` varchar(255) DEFAULT NULL,
+  `enabled` boolean NOT NULL DEFAULT TRUE,
+  -- 最後に確認した日（日記のタイムゾーン、同じ日に2回通知しないために使う）
+  `last_checked_on` date DEFAULT NULL,
+  `last_sent_at` datetime DEFAULT NULL,
+  `last_error` varchar(1000) DEFAULT NULL,
+  `datetime` datetime NOT NULL,
+  PRIMARY KEY (`id`),
+  FOREIGN KEY (`webhook_id`) REFERENCES `webhook` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
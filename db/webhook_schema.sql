@@ -0,0 +1,33 @@
+-- Webhookの購読テーブルの作成
+CREATE TABLE IF NOT EXISTS `diary`.`webhook` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `url` varchar(2048) NOT NULL,
+  -- 署名（HMAC-SHA256）の鍵
+  `secret` varchar(255) NOT NULL,
+  -- 配信するイベントの種類（例: ["entry.created", "tag.created"]）
+  `event_types` json NOT NULL,
+  `enabled` boolean NOT NULL DEFAULT TRUE,
+  `datetime` datetime NOT NULL,
+  PRIMARY KEY (`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- Webhookの配信キュー兼配信ログのテーブルの作成
+CREATE TABLE IF NOT EXISTS `diary`.`webhook_delivery` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `webhook_id` int NOT NULL,
+  `event_type` varchar(64) NOT NULL,
+  -- 送信するリクエストボディ（再送でも同じ内容を送る）
+  `payload` mediumtext NOT NULL,
+  `status` enum('pending', 'succeeded', 'failed') NOT NULL DEFAULT 'pending',
+  `attempts` int NOT NULL DEFAULT 0,
+  -- 次に送信する日時（送信中は処理が止まった場合に再開する日時）
+  `next_attempt_at` datetime NOT NULL,
+  `last_attempt_at` datetime DEFAULT NULL,
+  `response_status` int DEFAULT NULL,
+  `last_error` text DEFAULT NULL,
+  `datetime` datetime NOT NULL,
+  PRIMARY KEY (`id`),
+  KEY `webhook_id` (`webhook_id`, `id`),
+  KEY `status_next_attempt_at` (`status`, `next_attempt_at`),
+  FOREIGN KEY (`webhook_id`) REFERENCES `webhook` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -6,6 +6,7 @@ import {
   createEntryWithTags, 
   fetchTags, 
   fetchEntriesByTag,
+  subscribeEvents,
   EntryWithTags,
   Tag
 } from '@/lib/api';
@@ -65,6 +66,17 @@ export default function Home() {
     loadEntries(currentPage);
   }, [currentPage, loadEntries]);
 
+  // 他の人の投稿や編集を反映する
+  useEffect(() => {
+    return subscribeEvents(type => {
+      if (type === 'tag.created') {
+        loadTags();
+      } else {
+        loadEntries(currentPage);
+      }
+    });
+  }, [currentPage, loadEntries, loadTags]);
+
   const handleSubmit = async (e?: React.FormEvent) => {
     if (e) e.preventDefault();
     if (!content.trim()) return;
@@ -228,11 +240,10 @@ export default function Home() {
                   <div className="bg-gray-100 dark:bg-gray-700 p-3 border-b flex justify-between items-center">
                     <span>{formatDate(entry.datetime)}</span>
                   </div>
-                  <div className="p-4 whitespace-pre-wrap">
-                    {entry.content.split('\n').map((line, i) => (
-                      <p key={i}>{line}</p>
-                    ))}
-                  </div>
+                  <div
+                    className="p-4 break-words"
+                    dangerouslySetInnerHTML={{ __html: entry.content_html }}
+                  />
                   {entry.tags && entry.tags.length > 0 && (
                     <div className="px-4 pb-4 pt-2 border-t flex flex-wrap gap-2">
                       {entry.tags.map(tag => (
@@ -13,11 +13,80 @@ export interface Entry {
   datetime: string;
 }
 
+export interface ReactionCount {
+  emoji: string;
+  count: number;
+}
+
+export interface Thumbnail {
+  max_size: number;
+  format: string; // webp / jpg
+  width: number;
+  height: number;
+  url: string; // バックエンドのパス（/api/attachments/:id/thumbnails/:size.:format）
+}
+
+export interface Attachment {
+  id: number;
+  entry_id: number;
+  filename: string;
+  content_type: string;
+  size: number;
+  datetime: string;
+  width: number | null;
+  height: number | null;
+  url: string; // バックエンドのパス（/api/attachments/:id）
+  thumbnails: Thumbnail[];
+}
+
 export interface EntryWithTags {
   id: number;
   content: string;
+  content_html: string; // Markdownを描画し、サニタイズ済みのHTML
   datetime: string;
   tags: Tag[];
+  comment_count: number;
+  reaction_counts: ReactionCount[];
+  attachments: Attachment[];
+  prompt_id: number | null; // 答えたお題のID
+  unlock_at: string | null; // タイムカプセルの開封日時（UTC）
+  locked: boolean; // 開封前のタイムカプセル（本文は開封日時の案内に置き換え済み）
+  publish_at: string | null; // 予約投稿の公開日時（UTC）
+}
+
+export interface Draft {
+  key: string; // クライアントが決める下書きのキー
+  content: string;
+  tags: string[]; // 公開するときに付けるタグの名前
+  updated_at: string; // 最後に保存した日時（UTC）
+}
+
+export interface Prompt {
+  id: number;
+  text: string;
+  language: 'ja' | 'en';
+  enabled: boolean;
+  datetime: string;
+}
+
+export interface TodayPromptResponse {
+  date: string;
+  prompt: Prompt;
+}
+
+export interface Comment {
+  id: number;
+  entry_id: number;
+  author: string;
+  content: string;
+  datetime: string;
+}
+
+export interface Reaction {
+  id: number;
+  entry_id: number;
+  emoji: string;
+  datetime: string;
 }
 
 export interface EntriesResponse {
@@ -45,8 +114,97 @@ export const createEntry = async (content: string): Promise<void> => {
   await axios.post(`${API_URL}/entries`, { content });
 };
 
-export const createEntryWithTags = async (content: string, tags: string[]): Promise<void> => {
-  await axios.post(`${API_URL}/entries/with-tags`, { content, tags });
+export const createEntryWithTags = async (
+  content: string,
+  tags: string[],
+  options: { promptId?: number; unlockAt?: string; publishAt?: string } = {},
+): Promise<void> => {
+  await axios.post(`${API_URL}/entries/with-tags`, {
+    content,
+    tags,
+    prompt_id: options.promptId,
+    unlock_at: options.unlockAt,
+    publish_at: options.publishAt,
+  });
+};
+
+// 公開日時前の予約投稿（公開日時の順）
+export const fetchScheduledEntries = async (): Promise<EntryWithTags[]> => {
+  const response = await axios.get<EntryWithTags[]>(`${API_URL}/entries/scheduled`);
+  return response.data;
+};
+
+// 今日のお題（同じ日には同じお題が返る）
+export const fetchTodayPrompt = async (language?: 'ja' | 'en'): Promise<TodayPromptResponse> => {
+  const response = await axios.get<TodayPromptResponse>(`${API_URL}/prompts/today`, {
+    params: { language },
+  });
+  return response.data;
+};
+
+export const updateEntry = async (entryId: number, content: string, tags: string[]): Promise<void> => {
+  await axios.put(`${API_URL}/entries/${entryId}`, { content, tags });
+};
+
+export const deleteEntry = async (entryId: number): Promise<void> => {
+  await axios.delete(`${API_URL}/entries/${entryId}`);
+};
+
+// 下書き関連のAPI関数
+export const fetchDrafts = async (): Promise<Draft[]> => {
+  const response = await axios.get<Draft[]>(`${API_URL}/drafts`);
+  return response.data;
+};
+
+export const fetchDraft = async (key: string): Promise<Draft> => {
+  const response = await axios.get<Draft>(`${API_URL}/drafts/${key}`);
+  return response.data;
+};
+
+// 自動保存（同じキーへの保存は同じ下書きを上書きする）
+export const saveDraft = async (key: string, content: string, tags: string[]): Promise<Draft> => {
+  const response = await axios.put<Draft>(`${API_URL}/drafts/${key}`, { content, tags });
+  return response.data;
+};
+
+export const publishDraft = async (key: string): Promise<EntryWithTags> => {
+  const response = await axios.post<EntryWithTags>(`${API_URL}/drafts/${key}/publish`);
+  return response.data;
+};
+
+export const deleteDraft = async (key: string): Promise<void> => {
+  await axios.delete(`${API_URL}/drafts/${key}`);
+};
+
+export interface OnThisDayYear {
+  year: number;
+  years_ago: number;
+  entries: EntryWithTags[];
+}
+
+export interface OnThisDayResponse {
+  date: string; // MM-DD
+  week: boolean;
+  years: OnThisDayYear[];
+}
+
+// 過去の各年の同じ日（week が true なら前後3日を含む1週間）のエントリ
+export const fetchOnThisDay = async (date?: string, week: boolean = false): Promise<OnThisDayResponse> => {
+  const response = await axios.get<OnThisDayResponse>(`${API_URL}/entries/on-this-day`, {
+    params: { date, week },
+  });
+  return response.data;
+};
+
+// 条件に合うエントリをランダムに1件（rediscover なら最近表示していないものから）
+export const fetchRandomEntry = async (params: {
+  tag?: number;
+  from?: string;
+  to?: string;
+  mode?: 'random' | 'rediscover';
+} = {}): Promise<EntryWithTags> => {
+  const response = await axios.get<EntryWithTags>(`${API_URL}/entries/random`, { params });
+  return response.data;
 };
 
 export const fetchEntryCount = async (): Promise<number> => {
@@ -69,3 +227,87 @@ export const fetchEntriesByTag = async (tagId: number, page: number = 1): Promis
   const response = await axios.get(`${API_URL}/tags/${tagId}/entries?page=${page}`);
   return response.data;
 };
+
+// コメント関連のAPI関数
+export const fetchComments = async (entryId: number): Promise<Comment[]> => {
+  const response = await axios.get<Comment[]>(`${API_URL}/entries/${entryId}/comments`);
+  return response.data;
+};
+
+export const createComment = async (entryId: number, author: string, content: string): Promise<Comment> => {
+  const response = await axios.post<Comment>(`${API_URL}/entries/${entryId}/comments`, { author, content });
+  return response.data;
+};
+
+export const updateComment = async (commentId: number, content: string): Promise<Comment> => {
+  const response = await axios.put<Comment>(`${API_URL}/comments/${commentId}`, { content });
+  return response.data;
+};
+
+export const deleteComment = async (commentId: number): Promise<void> => {
+  await axios.delete(`${API_URL}/comments/${commentId}`);
+};
+
+// リアクション関連のAPI関数
+export const fetchReactions = async (entryId: number): Promise<Reaction[]> => {
+  const response = await axios.get<Reaction[]>(`${API_URL}/entries/${entryId}/reactions`);
+  return response.data;
+};
+
+export const createReaction = async (entryId: number, emoji: string): Promise<Reaction> => {
+  const response = await axios.post<Reaction>(`${API_URL}/entries/${entryId}/reactions`, { emoji });
+  return response.data;
+};
+
+export const deleteReaction = async (reactionId: number): Promise<void> => {
+  await axios.delete(`${API_URL}/reactions/${reactionId}`);
+};
+
+// 添付ファイル関連のAPI関数
+export const fetchAttachments = async (entryId: number): Promise<Attachment[]> => {
+  const response = await axios.get<Attachment[]>(`${API_URL}/entries/${entryId}/attachments`);
+  return response.data;
+};
+
+export const uploadAttachments = async (entryId: number, files: File[]): Promise<Attachment[]> => {
+  const form = new FormData();
+  files.forEach(file => form.append('file', file));
+  const response = await axios.post<Attachment[]>(`${API_URL}/entries/${entryId}/attachments`, form);
+  return response.data;
+};
+
+export const attachmentUrl = (attachmentId: number): string => `${API_URL}/attachments/${attachmentId}`;
+
+export const deleteAttachment = async (attachmentId: number): Promise<void> => {
+  await axios.delete(`${API_URL}/attachments/${attachmentId}`);
+};
+
+// 日ごとの活動量（counts と characters は from の日から1日ずつ並ぶ）
+export interface Activity {
+  from: string;
+  to: string;
+  counts: number[];
+  characters: number[];
+}
+
+export const fetchActivity = async (from?: string, to?: string, tagId?: number): Promise<Activity> => {
+  const response = await axios.get<Activity>(`${API_URL}/activity`, {
+    params: { from, to, tag: tagId },
+  });
+  return response.data;
+};
+
+// 変更の通知（Server-Sent Events）
+// `reset` は再送できない変更があったことを表す（すべて読み込み直す）
+export type DiaryEventType = 'entry.created' | 'entry.updated' | 'entry.deleted' | 'entry.unlocked' | 'tag.created' | 'reset';
+
+// イベントを購読し、購読を終了する関数を返す
+// 切断時はブラウザが Last-Event-ID を付けて自動で再接続する
+export const subscribeEvents = (onEvent: (type: DiaryEventType) => void): (() => void) => {
+  const source = new EventSource(`${API_URL}/events`);
+  const types: DiaryEventType[] = [
+    'entry.created', 'entry.updated', 'entry.deleted', 'entry.unlocked', 'tag.created', 'reset',
+  ];
+  types.forEach(type => source.addEventListener(type, () => onEvent(type)));
+  return () => source.close();
+};